@@ -1,24 +1,98 @@
+use crate::controllers::alert_controller::{
+    __path_create_alert, __path_delete_alert, __path_get_alerts, __path_get_notifications,
+    __path_mark_notification_read,
+};
+use crate::controllers::attribute_controller::{
+    __path_get_facets, __path_get_schema, __path_set_schema,
+};
 use crate::controllers::auth_controller::{__path_login, __path_refresh_token, __path_register};
+use crate::controllers::bundle_controller::{
+    __path_create_bundle, __path_delete_bundle, __path_get_admin_bundles, __path_get_bundle,
+    __path_get_bundles, __path_update_bundle,
+};
+use crate::controllers::campaign_controller::{
+    __path_create_campaign, __path_delete_campaign, __path_get_all_campaigns,
+};
+use crate::controllers::cart_controller::{
+    __path_add_cart_bundle, __path_add_cart_item, __path_checkout, __path_clear_cart,
+    __path_get_cart, __path_remove_cart_bundle, __path_remove_cart_item, __path_update_cart_bundle,
+    __path_update_cart_item,
+};
+use crate::controllers::coupon_controller::{
+    __path_create_coupon, __path_delete_coupon, __path_get_all_coupons, __path_get_redemptions,
+    __path_validate_coupon,
+};
+use crate::controllers::exchange_rate_controller::{
+    __path_get_current_rates, __path_get_rate_history, __path_set_rate,
+};
 use crate::controllers::order_controller::{
-    __path_create_order, __path_delete_order, __path_get_all_orders, __path_get_order,
-    __path_update_order,
+    __path_cancel_order, __path_change_order_status, __path_create_order, __path_delete_order,
+    __path_export_orders, __path_export_refund_ledger, __path_get_all_orders, __path_get_order,
+    __path_get_refund_ledger, __path_refund_order,
+};
+use crate::controllers::payment_controller::{
+    __path_create_intent, __path_reconcile, __path_webhook,
 };
 use crate::controllers::product_controller::{
-    __path_create_product, __path_delete_product, __path_get_all_products,
-    __path_get_most_advantageous, __path_get_product, __path_update_product,
+    __path_change_product_status, __path_compare_products, __path_create_product,
+    __path_delete_product, __path_delete_translation, __path_export_products,
+    __path_get_admin_products, __path_get_all_products, __path_get_deals,
+    __path_get_most_advantageous, __path_get_price_history, __path_get_product,
+    __path_get_product_analytics, __path_get_related_products, __path_import_products,
+    __path_rebuild_related, __path_restore_product, __path_set_stock, __path_set_translation,
+    __path_update_product,
+};
+use crate::controllers::tax_controller::{
+    __path_delete_tax_rule, __path_get_tax_rules, __path_set_tax_rule,
 };
 use crate::controllers::user_controller::{
     __path_delete_user, __path_get_all_users, __path_get_my_orders, __path_me, __path_update_user,
 };
+use crate::controllers::wishlist_controller::{
+    __path_add_to_wishlist, __path_get_wishlist, __path_move_to_cart, __path_remove_from_wishlist,
+};
+use crate::dto::alert::CreateAlertDto;
+use crate::dto::attribute::{Facet, FacetValue, FacetsResponse, SetCategorySchemaDto};
 use crate::dto::auth::{AuthResponse, LoginDto, RefreshTokenRequest, RegisterDto, UserInfo};
-use crate::dto::order::{CreateOrderDto, UpdateOrderDto};
-use crate::dto::product::{CreateProductDto, UpdateProductDto};
+use crate::dto::bundle::{BundleComponent, BundleDto, BundleView};
+use crate::dto::campaign::CreateCampaignDto;
+use crate::dto::cart::{CartView, UpdateCartQuantityDto};
+use crate::dto::coupon::CreateCouponDto;
+use crate::dto::exchange_rate::SetExchangeRateDto;
+use crate::dto::order::{
+    ChangeOrderStatusDto, CreateOrderDto, CreateOrderResponse, OrderBundleDto, OrderItemDto,
+    PriceBreakdown, RefundLedgerEntry, RefundOrderDto,
+};
+use crate::dto::payment::{PaymentIntentResponse, PaymentWebhookEvent, ReconcileReport};
+use crate::dto::product::{
+    BulkFormat, CategoryDeals, ChangeProductStatusDto, ComparedAttribute, CreateProductDto, Deal,
+    DealRanking, DealsResponse, ImportReport, ImportRowError, ProductAnalytics, ProductComparison,
+    SetStockDto, TranslationDto, UpdateProductDto,
+};
+use crate::dto::tax::SetTaxRuleDto;
 use crate::dto::user::UpdateUserDto;
+use crate::dto::wishlist::WishlistEntry;
 use crate::errors::ErrorResponse;
-use crate::models::order::Order;
-use crate::models::product::Product;
+use crate::models::alert::{AlertCondition, ProductAlert};
+use crate::models::attribute::{
+    AttributeDefinition, AttributeKind, AttributeValue, CategorySchema,
+};
+use crate::models::bundle::{Bundle, BundleItem, BundlePricing};
+use crate::models::campaign::DiscountCampaign;
+use crate::models::coupon::{AppliedCoupon, Coupon, CouponDiscount, CouponRedemption};
+use crate::models::exchange_rate::ExchangeRate;
+use crate::models::locale::Locale;
+use crate::models::money::{Currency, Money};
+use crate::models::notification::Notification;
+use crate::models::order::{
+    Order, OrderBundle, OrderItem, OrderRefund, OrderStatus, OrderStatusChange,
+};
+use crate::models::payment::PaymentStatus;
+use crate::models::price_history::{PriceChangeSource, PriceHistory};
+use crate::models::product::{Product, ProductStatus, ProductTranslation};
 use crate::models::res::MessageResponse;
 use crate::models::role::Role;
+use crate::models::tax::{TaxLine, TaxMode, TaxRule};
 use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::OpenApi;
 
@@ -34,7 +108,12 @@ use utoipa::OpenApi;
         create_order, 
         get_all_orders, 
         get_order, 
-        update_order, 
+        change_order_status, 
+        cancel_order, 
+        refund_order, 
+        get_refund_ledger, 
+        export_refund_ledger, 
+        export_orders,
         delete_order, 
         register, 
         login, 
@@ -43,7 +122,65 @@ use utoipa::OpenApi;
         update_user,
         delete_user,
         get_all_users,
-        get_my_orders
+        get_my_orders,
+        get_price_history,
+        create_campaign,
+        get_all_campaigns,
+        delete_campaign,
+        get_current_rates,
+        set_rate,
+        get_rate_history,
+        import_products,
+        export_products,
+        get_admin_products,
+        change_product_status,
+        restore_product,
+        get_deals,
+        get_related_products,
+        rebuild_related,
+        get_schema,
+        set_schema,
+        get_facets,
+        get_wishlist,
+        add_to_wishlist,
+        remove_from_wishlist,
+        move_to_cart,
+        get_cart,
+        add_cart_item,
+        update_cart_item,
+        remove_cart_item,
+        add_cart_bundle,
+        update_cart_bundle,
+        remove_cart_bundle,
+        clear_cart,
+        checkout,
+        create_intent,
+        webhook,
+        reconcile,
+        create_coupon,
+        get_all_coupons,
+        delete_coupon,
+        get_redemptions,
+        validate_coupon,
+        set_tax_rule,
+        get_tax_rules,
+        delete_tax_rule,
+        get_product_analytics,
+        set_stock,
+        get_alerts,
+        create_alert,
+        delete_alert,
+        get_notifications,
+        mark_notification_read,
+        compare_products,
+        get_bundles,
+        get_bundle,
+        get_admin_bundles,
+        create_bundle,
+        update_bundle,
+        delete_bundle,
+        set_translation,
+        delete_translation
     ),
     components(
         schemas(
@@ -52,7 +189,7 @@ use utoipa::OpenApi;
             UpdateProductDto, 
             Order, 
             CreateOrderDto, 
-            UpdateOrderDto, 
+            ChangeOrderStatusDto, 
             MessageResponse, 
             ErrorResponse, 
             UserInfo, 
@@ -61,7 +198,76 @@ use utoipa::OpenApi;
             AuthResponse, 
             RefreshTokenRequest,
             Role,
-            UpdateUserDto
+            UpdateUserDto,
+            DiscountCampaign,
+            CreateCampaignDto,
+            PriceHistory,
+            PriceChangeSource,
+            Money,
+            Currency,
+            ExchangeRate,
+            SetExchangeRateDto,
+            BulkFormat,
+            ImportReport,
+            ImportRowError,
+            ProductStatus,
+            ChangeProductStatusDto,
+            DealRanking,
+            Deal,
+            CategoryDeals,
+            DealsResponse,
+            AttributeKind,
+            AttributeValue,
+            AttributeDefinition,
+            CategorySchema,
+            SetCategorySchemaDto,
+            FacetValue,
+            Facet,
+            FacetsResponse,
+            WishlistEntry,
+            ProductAnalytics,
+            SetStockDto,
+            AlertCondition,
+            ProductAlert,
+            CreateAlertDto,
+            Notification,
+            ComparedAttribute,
+            ProductComparison,
+            BundleItem,
+            BundlePricing,
+            Bundle,
+            BundleDto,
+            BundleComponent,
+            BundleView,
+            Locale,
+            ProductTranslation,
+            TranslationDto,
+            OrderItem,
+            OrderBundle,
+            OrderStatus,
+            OrderStatusChange,
+            OrderItemDto,
+            OrderBundleDto,
+            PriceBreakdown,
+            CreateOrderResponse,
+            OrderRefund,
+            RefundOrderDto,
+            RefundLedgerEntry,
+            UpdateCartQuantityDto,
+            CartView,
+            PaymentStatus,
+            PaymentIntentResponse,
+            PaymentWebhookEvent,
+            ReconcileReport,
+            CouponDiscount,
+            Coupon,
+            AppliedCoupon,
+            CouponRedemption,
+            CreateCouponDto,
+            TaxMode,
+            TaxRule,
+            TaxLine,
+            SetTaxRuleDto
         )
     ),
     info(title = "Bike Shop API", version = "0.1.0"),
@@ -73,7 +279,17 @@ use utoipa::OpenApi;
         (name = "Products", description = "Product management endpoints"),
         (name = "Orders", description = "Order management endpoints"),
         (name = "Auth", description = "Auth management endpoints"),
-        (name = "Users", description = "Users management endpoints")
+        (name = "Users", description = "Users management endpoints"),
+        (name = "Campaigns", description = "Scheduled discount campaigns"),
+        (name = "Exchange rates", description = "Currency exchange rates"),
+        (name = "Attributes", description = "Technical specifications and catalog facets"),
+        (name = "Wishlist", description = "Products customers saved for later"),
+        (name = "Alerts", description = "Back-in-stock and price-drop alerts and the in-app inbox"),
+        (name = "Bundles", description = "Product kits sold at a bundle price"),
+        (name = "Cart", description = "Shopping carts of guests and signed-in customers"),
+        (name = "Payments", description = "Paying for orders through the payment provider"),
+        (name = "Coupons", description = "Promo codes taken off the order total"),
+        (name = "Taxes", description = "Tax rates per country, region and product category")
     )
 )]
 pub struct ApiDoc;
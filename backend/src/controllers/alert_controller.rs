@@ -0,0 +1,194 @@
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Result};
+
+use crate::{
+    dto::alert::CreateAlertDto,
+    errors::{auth_error::AuthError, AppErrors, ErrorResponse},
+    models::{
+        alert::ProductAlert, app::AppState, notification::Notification, res::MessageResponse,
+    },
+    services::{alert_service, notification_service},
+    utils::jwt::Claims,
+};
+
+fn claims(req: &HttpRequest) -> Result<Claims, AppErrors> {
+    req.extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/alerts",
+    responses(
+        (status = 200, description = "Alerts of the user, newest first, including ones that already fired", body = [ProductAlert]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Alerts",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_alerts(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    let claims = claims(&req)?;
+
+    let alerts = alert_service::get_alerts(&state.mongo, &claims.sub).await?;
+    Ok(HttpResponse::Ok().json(alerts))
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/alerts",
+    request_body = CreateAlertDto,
+    responses(
+        (status = 201, description = "Alert created; replaces a pending alert of the same kind", body = ProductAlert),
+        (status = 400, description = "The condition is already met", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "The product is in stock"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 404, description = "No published product with this id", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Product not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Alerts",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_alert(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    data: web::Json<CreateAlertDto>,
+) -> Result<HttpResponse, AppErrors> {
+    let claims = claims(&req)?;
+
+    let alert = alert_service::create_alert(&state.mongo, &claims.sub, data.into_inner()).await?;
+    Ok(HttpResponse::Created().json(alert))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/user/alerts/{id}",
+    params(
+        ("id" = String, Path, description = "Alert UUID")
+    ),
+    responses(
+        (status = 200, description = "Alert deleted", body = MessageResponse, example = json!({
+            "message": "Alert deleted successfully"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 404, description = "Alert not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Alert not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Alerts",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_alert(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    alert_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let claims = claims(&req)?;
+
+    let answer = alert_service::delete_alert(&state.mongo, &claims.sub, &alert_id).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/notifications",
+    responses(
+        (status = 200, description = "In-app inbox, newest first", body = [Notification]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Alerts",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_notifications(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    let claims = claims(&req)?;
+
+    let notifications = notification_service::get_inbox(&state.mongo, &claims.sub).await?;
+    Ok(HttpResponse::Ok().json(notifications))
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/notifications/{id}/read",
+    params(
+        ("id" = String, Path, description = "Notification UUID")
+    ),
+    responses(
+        (status = 200, description = "Notification marked as read", body = MessageResponse, example = json!({
+            "message": "Notification marked as read"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 404, description = "Notification not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Notification not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Alerts",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn mark_notification_read(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    notification_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let claims = claims(&req)?;
+
+    let answer =
+        notification_service::mark_read(&state.mongo, &claims.sub, &notification_id).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use actix_web::{web, HttpResponse, Result};
+use validator::Validate;
+
+use crate::{
+    dto::attribute::{FacetsResponse, ProductFilter, SetCategorySchemaDto},
+    errors::{AppErrors, ErrorResponse},
+    models::{app::AppState, attribute::CategorySchema, res::MessageResponse},
+    services::attribute_service,
+};
+
+#[utoipa::path(
+    get,
+    path = "/product/attributes/{category}",
+    params(
+        ("category" = u8, Path, description = "Product category")
+    ),
+    responses(
+        (status = 200, description = "Attributes products of the category can have", body = CategorySchema),
+        (status = 404, description = "The category has no schema", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Attribute schema not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Attributes"
+)]
+pub async fn get_schema(
+    state: web::Data<AppState>,
+    category: web::Path<u8>,
+) -> Result<HttpResponse, AppErrors> {
+    let schema = attribute_service::get_schema(&state.mongo, *category).await?;
+    Ok(HttpResponse::Ok().json(schema))
+}
+
+#[utoipa::path(
+    put,
+    path = "/product/admin/attributes/{category}",
+    params(
+        ("category" = u8, Path, description = "Product category")
+    ),
+    request_body = SetCategorySchemaDto,
+    responses(
+        (status = 200, description = "Schema saved", body = MessageResponse, example = json!({
+            "message": "Attribute schema saved successfully"
+        })),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Attributes",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_schema(
+    state: web::Data<AppState>,
+    category: web::Path<u8>,
+    data: web::Json<SetCategorySchemaDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let answer = attribute_service::set_schema(&state.mongo, *category, data).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product/facets",
+    params(
+        ("category" = Option<u8>, Query, description = "Only products of this category; also enables schema labels and order"),
+        ("q" = Option<String>, Query, description = "Only products whose name or description in any locale contains these words"),
+        ("attr.{key}" = Option<String>, Query, description = "Only products whose attribute `key` has one of these comma-separated values, e.g. `attr.wheel_size=27.5,29`")
+    ),
+    responses(
+        (status = 200, description = "Product counts per attribute value among the matching published products", body = FacetsResponse),
+        (status = 400, description = "Invalid filter", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Invalid attribute filter: attr.$where"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Attributes"
+)]
+pub async fn get_facets(
+    state: web::Data<AppState>,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<HttpResponse, AppErrors> {
+    let filter = ProductFilter::from_query(&query)?;
+    let facets = attribute_service::get_facets(&state.mongo, &filter).await?;
+    Ok(HttpResponse::Ok().json(facets))
+}
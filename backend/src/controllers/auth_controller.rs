@@ -2,13 +2,28 @@ use actix_web::{HttpRequest, HttpResponse, Result, web};
 use validator::Validate;
 
 use crate::{
-    dto::auth::{LoginDto, RegisterDto, UserInfo}, errors::{AppErrors, ErrorResponse, auth_error::AuthError}, models::{app::AppState, res::MessageResponse}, services::auth_service
+    dto::auth::{LoginDto, RegisterDto, UserInfo}, errors::{AppErrors, ErrorResponse, auth_error::AuthError}, models::{app::AppState, res::MessageResponse}, services::{auth_service, cart_service}, utils::cart::guest_token
 };
 
+/// Moves the guest cart of the request into the user's cart. Signing in
+/// must not fail because of the cart, so problems are only logged.
+async fn adopt_guest_cart(db: &AppState, req: &HttpRequest, user_id: &str) {
+    let Ok(Some(token)) = guest_token(req) else {
+        return;
+    };
+
+    if let Err(e) = cart_service::merge_guest_cart(db.redis.clone(), token, user_id).await {
+        eprintln!("❌ Failed to merge the guest cart: {:?}", e);
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/auth/register",
     request_body = RegisterDto,
+    params(
+        ("X-Cart-Token" = Option<String>, Header, description = "Guest cart token; the guest cart is merged into the user's cart")
+    ),
     responses(
         (   
             status = 200, 
@@ -35,6 +50,7 @@ use crate::{
 )]
 pub async fn register(
     db: web::Data<AppState>,
+    req: HttpRequest,
     data: web::Json<RegisterDto>,
 ) -> Result<HttpResponse, AppErrors> {
     if let Err(e) = data.validate() {
@@ -45,6 +61,7 @@ pub async fn register(
     }
 
     let res = auth_service::register(&db.mongo, data).await?;
+    adopt_guest_cart(&db, &req, &res.user.id).await;
     Ok(HttpResponse::Created()
         .insert_header(("X-Access-Token", res.access_token))
         .insert_header(("X-Refresh-Token", res.refresh_token))
@@ -55,6 +72,9 @@ pub async fn register(
     post,
     path = "/auth/login",
     request_body = LoginDto,
+    params(
+        ("X-Cart-Token" = Option<String>, Header, description = "Guest cart token; the guest cart is merged into the user's cart")
+    ),
     responses(
         (
             status = 200, 
@@ -78,6 +98,7 @@ pub async fn register(
 )]
 pub async fn login(
     db: web::Data<AppState>,
+    req: HttpRequest,
     data: web::Json<LoginDto>,
 ) -> Result<HttpResponse, AppErrors> {
     if let Err(_) = data.validate() {
@@ -88,6 +109,7 @@ pub async fn login(
     }
 
     let res = auth_service::login(&db.mongo, data).await?;
+    adopt_guest_cart(&db, &req, &res.user.id).await;
     Ok(HttpResponse::Ok()
         .insert_header(("X-Access-Token", res.access_token))
         .insert_header(("X-Refresh-Token", res.refresh_token))
@@ -0,0 +1,261 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use validator::Validate;
+
+use crate::{
+    dto::bundle::{BundleDto, BundleView},
+    errors::{AppErrors, ErrorResponse},
+    models::{
+        app::AppState,
+        bundle::Bundle,
+        locale::{Locale, Localize},
+        res::MessageResponse,
+    },
+    services::bundle_service,
+    utils::{currency::requested_currency, locale::requested_locale},
+};
+
+#[utoipa::path(
+    get,
+    path = "/product/bundles",
+    params(
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    responses(
+        (status = 200, description = "Published bundles with component prices and availability", body = [BundleView]),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Bundles"
+)]
+pub async fn get_bundles(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    let currency = requested_currency(&req)?;
+    let locale = requested_locale(&req)?;
+    let mut bundles = bundle_service::get_bundles(&state.mongo, currency).await?;
+    bundles.localize(locale);
+    Ok(HttpResponse::Ok().json(bundles))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product/bundles/{id}",
+    params(
+        ("id" = String, Path, description = "Bundle ID (UUID format)"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    responses(
+        (status = 200, description = "Bundle with component prices and availability", body = BundleView),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 404, description = "No published bundle with this ID", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Bundle not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Bundles"
+)]
+pub async fn get_bundle(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    bundle_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let currency = requested_currency(&req)?;
+    let locale = requested_locale(&req)?;
+    let mut bundle = bundle_service::get_bundle(&state.mongo, &bundle_id, currency).await?;
+    bundle.localize(locale);
+    Ok(HttpResponse::Ok().json(bundle))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product/admin/bundles",
+    responses(
+        (status = 200, description = "Bundles in any status, sorted by name", body = [Bundle]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Bundles",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_admin_bundles(state: web::Data<AppState>) -> Result<HttpResponse, AppErrors> {
+    let bundles = bundle_service::get_admin_bundles(&state.mongo).await?;
+    Ok(HttpResponse::Ok().json(bundles))
+}
+
+#[utoipa::path(
+    post,
+    path = "/product/admin/bundles",
+    request_body = BundleDto,
+    responses(
+        (status = 201, description = "Bundle created", body = MessageResponse, example = json!({
+            "message": "Bundle created successfully"
+        })),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "A component does not exist", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Some products not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Bundles",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_bundle(
+    state: web::Data<AppState>,
+    data: web::Json<BundleDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let answer = bundle_service::create_bundle(&state.mongo, data).await?;
+    Ok(HttpResponse::Created().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/product/admin/bundles/{id}",
+    params(
+        ("id" = String, Path, description = "Bundle ID (UUID format)")
+    ),
+    request_body = BundleDto,
+    responses(
+        (status = 200, description = "Bundle updated; the status is kept when left out", body = MessageResponse, example = json!({
+            "message": "Bundle updated successfully"
+        })),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Bundle or a component not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Bundle not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Bundles",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_bundle(
+    state: web::Data<AppState>,
+    bundle_id: web::Path<String>,
+    data: web::Json<BundleDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let answer = bundle_service::update_bundle(&state.mongo, &bundle_id, data).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/product/admin/bundles/{id}",
+    params(
+        ("id" = String, Path, description = "Bundle ID (UUID format)")
+    ),
+    responses(
+        (status = 200, description = "Bundle deleted", body = MessageResponse, example = json!({
+            "message": "Bundle deleted successfully"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Bundle not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Bundle not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Bundles",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_bundle(
+    state: web::Data<AppState>,
+    bundle_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let answer = bundle_service::delete_bundle(&state.mongo, &bundle_id).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
@@ -0,0 +1,141 @@
+use actix_web::{web, HttpResponse, Result};
+use validator::Validate;
+
+use crate::{
+    dto::campaign::CreateCampaignDto,
+    errors::{AppErrors, ErrorResponse},
+    models::{app::AppState, campaign::DiscountCampaign, res::MessageResponse},
+    services::campaign_service,
+};
+
+#[utoipa::path(
+    post,
+    path = "/product/admin/campaigns/create",
+    request_body = CreateCampaignDto,
+    responses(
+        (status = 201, description = "Campaign created successfully", body = MessageResponse, example = json!({
+            "message": "Campaign created successfully"
+        })),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 400, description = "Campaign overlaps another one", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "The campaign overlaps an existing campaign for this product"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Product not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Product not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Campaigns",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_campaign(
+    state: web::Data<AppState>,
+    data: web::Json<CreateCampaignDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let answer = campaign_service::create_campaign(&state.mongo, state.redis.clone(), data).await?;
+    Ok(HttpResponse::Created().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product/admin/campaigns",
+    responses(
+        (status = 200, description = "List of all discount campaigns", body = [DiscountCampaign]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Campaigns",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_all_campaigns(state: web::Data<AppState>) -> Result<HttpResponse, AppErrors> {
+    let campaigns = campaign_service::get_all_campaigns(&state.mongo).await?;
+    Ok(HttpResponse::Ok().json(campaigns))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/product/admin/campaigns/delete/{id}",
+    params(
+        ("id" = String, Path, description = "Campaign ID (UUID format)")
+    ),
+    responses(
+        (status = 200, description = "Campaign deleted successfully", body = MessageResponse, example = json!({
+            "message": "Campaign deleted successfully"
+        })),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Campaign not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Campaign not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Campaigns",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_campaign(
+    state: web::Data<AppState>,
+    campaign_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let answer =
+        campaign_service::delete_campaign(&state.mongo, state.redis.clone(), &campaign_id).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
@@ -0,0 +1,549 @@
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Result};
+use validator::Validate;
+
+use crate::{
+    dto::{
+        cart::{CartView, CheckoutQuery, UpdateCartQuantityDto},
+        order::{CreateOrderResponse, OrderBundleDto, OrderItemDto},
+    },
+    errors::{auth_error::AuthError, AppErrors, ErrorResponse},
+    models::{
+        app::AppState,
+        cart::{CartLine, CartOwner},
+        locale::{Locale, Localize},
+    },
+    services::cart_service,
+    utils::{
+        cart::{requested_cart, CART_TOKEN_HEADER},
+        currency::requested_currency,
+        jwt::Claims,
+        locale::requested_locale,
+    },
+};
+
+/// The cart after a change, with the guest token for guests
+async fn cart_response(
+    state: &AppState,
+    req: &HttpRequest,
+    owner: CartOwner,
+) -> Result<HttpResponse, AppErrors> {
+    let currency = requested_currency(req)?;
+    let locale = requested_locale(req)?;
+
+    let mut cart =
+        cart_service::view_cart(&state.mongo, state.redis.clone(), &owner, currency).await?;
+    cart.localize(locale);
+
+    let mut response = HttpResponse::Ok();
+    if let CartOwner::Guest(token) = owner {
+        response.insert_header((CART_TOKEN_HEADER, token.to_string()));
+    }
+
+    Ok(response.json(cart))
+}
+
+fn validation_error(e: validator::ValidationErrors) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": "validation_error",
+        "message": format!("Validation failed: {:?}", e)
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cart",
+    params(
+        ("X-Cart-Token" = Option<String>, Header, description = "Guest cart token from an earlier response; ignored when signed in"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to price the cart in; defaults to the base currency"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    responses(
+        (status = 200, description = "The cart at current prices; empty for a new guest", body = CartView, headers(
+            ("X-Cart-Token" = String, description = "Guest cart token to send with the next requests; only for guests")
+        )),
+        (status = 400, description = "Malformed cart token", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Invalid cart token"
+        })),
+        (status = 401, description = "Invalid access token", body = ErrorResponse, example = json!({
+            "error": "failed_decode",
+            "message": "Unable to decode token"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Cart",
+    security(
+        (),
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_cart(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    let owner = requested_cart(&req)?;
+    cart_response(&state, &req, owner).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/cart/items",
+    params(
+        ("X-Cart-Token" = Option<String>, Header, description = "Guest cart token from an earlier response; ignored when signed in"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to price the cart in; defaults to the base currency"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    request_body = OrderItemDto,
+    responses(
+        (status = 200, description = "Product added, quantities of the same product add up to at most 100", body = CartView, headers(
+            ("X-Cart-Token" = String, description = "Guest cart token to send with the next requests; only for guests")
+        )),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 400, description = "Malformed cart token", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Invalid cart token"
+        })),
+        (status = 401, description = "Invalid access token", body = ErrorResponse, example = json!({
+            "error": "failed_decode",
+            "message": "Unable to decode token"
+        })),
+        (status = 404, description = "Product or variant not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Product not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Cart",
+    security(
+        (),
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn add_cart_item(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    data: web::Json<OrderItemDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(validation_error(e));
+    }
+
+    let owner = requested_cart(&req)?;
+    cart_service::add_item(&state.mongo, state.redis.clone(), &owner, data.into_inner()).await?;
+    cart_response(&state, &req, owner).await
+}
+
+#[utoipa::path(
+    put,
+    path = "/cart/items/{product_id}",
+    params(
+        ("product_id" = String, Path, description = "Product UUID"),
+        ("X-Cart-Token" = Option<String>, Header, description = "Guest cart token from an earlier response; ignored when signed in"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to price the cart in; defaults to the base currency"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    request_body = UpdateCartQuantityDto,
+    responses(
+        (status = 200, description = "Quantity changed; 0 removes the product", body = CartView, headers(
+            ("X-Cart-Token" = String, description = "Guest cart token to send with the next requests; only for guests")
+        )),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Invalid access token", body = ErrorResponse, example = json!({
+            "error": "failed_decode",
+            "message": "Unable to decode token"
+        })),
+        (status = 404, description = "The product is not in the cart", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Cart item not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Cart",
+    security(
+        (),
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_cart_item(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    product_id: web::Path<String>,
+    data: web::Json<UpdateCartQuantityDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(validation_error(e));
+    }
+
+    let owner = requested_cart(&req)?;
+    let line = CartLine::Product(parse_id(&product_id)?);
+    cart_service::set_quantity(state.redis.clone(), &owner, line, data.quantity).await?;
+    cart_response(&state, &req, owner).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cart/items/{product_id}",
+    params(
+        ("product_id" = String, Path, description = "Product UUID"),
+        ("X-Cart-Token" = Option<String>, Header, description = "Guest cart token from an earlier response; ignored when signed in"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to price the cart in; defaults to the base currency"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    responses(
+        (status = 200, description = "Product removed", body = CartView, headers(
+            ("X-Cart-Token" = String, description = "Guest cart token to send with the next requests; only for guests")
+        )),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Invalid access token", body = ErrorResponse, example = json!({
+            "error": "failed_decode",
+            "message": "Unable to decode token"
+        })),
+        (status = 404, description = "The product is not in the cart", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Cart item not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Cart",
+    security(
+        (),
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn remove_cart_item(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    product_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let owner = requested_cart(&req)?;
+    let line = CartLine::Product(parse_id(&product_id)?);
+    cart_service::remove_line(state.redis.clone(), &owner, line).await?;
+    cart_response(&state, &req, owner).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/cart/bundles",
+    params(
+        ("X-Cart-Token" = Option<String>, Header, description = "Guest cart token from an earlier response; ignored when signed in"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to price the cart in; defaults to the base currency"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    request_body = OrderBundleDto,
+    responses(
+        (status = 200, description = "Bundle added, quantities of the same bundle add up to at most 100", body = CartView, headers(
+            ("X-Cart-Token" = String, description = "Guest cart token to send with the next requests; only for guests")
+        )),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 400, description = "A component of the bundle cannot be ordered", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Bundle Commuter kit is not available"
+        })),
+        (status = 401, description = "Invalid access token", body = ErrorResponse, example = json!({
+            "error": "failed_decode",
+            "message": "Unable to decode token"
+        })),
+        (status = 404, description = "Bundle not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Bundle not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Cart",
+    security(
+        (),
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn add_cart_bundle(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    data: web::Json<OrderBundleDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(validation_error(e));
+    }
+
+    let owner = requested_cart(&req)?;
+    cart_service::add_bundle(&state.mongo, state.redis.clone(), &owner, data.into_inner()).await?;
+    cart_response(&state, &req, owner).await
+}
+
+#[utoipa::path(
+    put,
+    path = "/cart/bundles/{bundle_id}",
+    params(
+        ("bundle_id" = String, Path, description = "Bundle UUID"),
+        ("X-Cart-Token" = Option<String>, Header, description = "Guest cart token from an earlier response; ignored when signed in"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to price the cart in; defaults to the base currency"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    request_body = UpdateCartQuantityDto,
+    responses(
+        (status = 200, description = "Quantity changed; 0 removes the bundle", body = CartView, headers(
+            ("X-Cart-Token" = String, description = "Guest cart token to send with the next requests; only for guests")
+        )),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Invalid access token", body = ErrorResponse, example = json!({
+            "error": "failed_decode",
+            "message": "Unable to decode token"
+        })),
+        (status = 404, description = "The bundle is not in the cart", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Cart item not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Cart",
+    security(
+        (),
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_cart_bundle(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    bundle_id: web::Path<String>,
+    data: web::Json<UpdateCartQuantityDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(validation_error(e));
+    }
+
+    let owner = requested_cart(&req)?;
+    let line = CartLine::Bundle(parse_id(&bundle_id)?);
+    cart_service::set_quantity(state.redis.clone(), &owner, line, data.quantity).await?;
+    cart_response(&state, &req, owner).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cart/bundles/{bundle_id}",
+    params(
+        ("bundle_id" = String, Path, description = "Bundle UUID"),
+        ("X-Cart-Token" = Option<String>, Header, description = "Guest cart token from an earlier response; ignored when signed in"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to price the cart in; defaults to the base currency"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    responses(
+        (status = 200, description = "Bundle removed", body = CartView, headers(
+            ("X-Cart-Token" = String, description = "Guest cart token to send with the next requests; only for guests")
+        )),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Invalid access token", body = ErrorResponse, example = json!({
+            "error": "failed_decode",
+            "message": "Unable to decode token"
+        })),
+        (status = 404, description = "The bundle is not in the cart", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Cart item not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Cart",
+    security(
+        (),
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn remove_cart_bundle(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    bundle_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let owner = requested_cart(&req)?;
+    let line = CartLine::Bundle(parse_id(&bundle_id)?);
+    cart_service::remove_line(state.redis.clone(), &owner, line).await?;
+    cart_response(&state, &req, owner).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cart",
+    params(
+        ("X-Cart-Token" = Option<String>, Header, description = "Guest cart token from an earlier response; ignored when signed in"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to price the cart in; defaults to the base currency"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    responses(
+        (status = 200, description = "Cart emptied", body = CartView, headers(
+            ("X-Cart-Token" = String, description = "Guest cart token to send with the next requests; only for guests")
+        )),
+        (status = 400, description = "Malformed cart token", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Invalid cart token"
+        })),
+        (status = 401, description = "Invalid access token", body = ErrorResponse, example = json!({
+            "error": "failed_decode",
+            "message": "Unable to decode token"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Cart",
+    security(
+        (),
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn clear_cart(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    let owner = requested_cart(&req)?;
+    cart_service::clear_cart(state.redis.clone(), &owner).await?;
+    cart_response(&state, &req, owner).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/cart/checkout",
+    params(
+        ("currency" = Option<String>, Query, description = "Currency to price the order in; defaults to the base currency"),
+        ("coupon" = Option<String>, Query, description = "Promo code to apply to the order"),
+        ("country" = Option<String>, Query, description = "ISO 3166-1 alpha-2 code of the country the order ships to; taxes follow the shop's country when omitted"),
+        ("region" = Option<String>, Query, description = "State or province code, for regional tax rates"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response back instead of repeating the request")
+    ),
+    responses(
+        (status = 201, description = "Order placed for everything in the cart at current catalog prices; the cart is emptied", body = CreateOrderResponse),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 400, description = "The cart is empty", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "The cart is empty"
+        })),
+        (status = 400, description = "A product is sold out", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Not enough stock for Trail bike"
+        })),
+        (status = 400, description = "The coupon cannot be used for this order", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "The coupon has been used up"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 404, description = "Some products in the cart can no longer be ordered", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Some products not found"
+        })),
+        (status = 409, description = "A request with the same idempotency key is still in progress", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "A request with this idempotency key is still in progress"
+        })),
+        (status = 422, description = "The idempotency key was already used for a different request", body = ErrorResponse, example = json!({
+            "error": "idempotency_key_reused",
+            "message": "The idempotency key was already used for a different request"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Cart",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn checkout(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<CheckoutQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = query.validate() {
+        return Ok(validation_error(e));
+    }
+
+    let currency = requested_currency(&req)?;
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let created = cart_service::checkout(
+        &state.mongo,
+        state.redis.clone(),
+        claims.sub,
+        currency,
+        query.into_inner(),
+    )
+    .await?;
+    Ok(HttpResponse::Created().json(created))
+}
+
+fn parse_id(id: &str) -> Result<String, AppErrors> {
+    uuid::Uuid::parse_str(id)
+        .map(|uuid| uuid.to_string())
+        .map_err(|_| AppErrors::InvalidUUID)
+}
@@ -0,0 +1,251 @@
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Result};
+use validator::Validate;
+
+use crate::{
+    dto::{
+        coupon::CreateCouponDto,
+        order::{CreateOrderDto, PriceBreakdown},
+    },
+    errors::{auth_error::AuthError, AppErrors, ErrorResponse},
+    models::{
+        app::AppState,
+        coupon::{Coupon, CouponRedemption},
+        res::MessageResponse,
+    },
+    services::{coupon_service, order_service},
+    utils::{currency::requested_currency, jwt::Claims},
+};
+
+#[utoipa::path(
+    post,
+    path = "/coupon/admin/create",
+    request_body = CreateCouponDto,
+    responses(
+        (status = 201, description = "Coupon created successfully", body = MessageResponse, example = json!({
+            "message": "Coupon created successfully"
+        })),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 409, description = "A coupon with this code already exists", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "The coupon SPRING10 already exists"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Coupons",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_coupon(
+    state: web::Data<AppState>,
+    data: web::Json<CreateCouponDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let answer = coupon_service::create_coupon(&state.mongo, data).await?;
+    Ok(HttpResponse::Created().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/coupon/admin/coupons",
+    responses(
+        (status = 200, description = "List of all coupons, latest start first", body = [Coupon]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Coupons",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_all_coupons(state: web::Data<AppState>) -> Result<HttpResponse, AppErrors> {
+    let coupons = coupon_service::get_all_coupons(&state.mongo).await?;
+    Ok(HttpResponse::Ok().json(coupons))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/coupon/admin/delete/{id}",
+    params(
+        ("id" = String, Path, description = "Coupon ID (UUID format)")
+    ),
+    responses(
+        (status = 200, description = "Coupon deleted successfully", body = MessageResponse, example = json!({
+            "message": "Coupon deleted successfully"
+        })),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Coupon not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Coupon not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Coupons",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_coupon(
+    state: web::Data<AppState>,
+    coupon_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let answer = coupon_service::delete_coupon(&state.mongo, &coupon_id).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/coupon/admin/redemptions/{id}",
+    params(
+        ("id" = String, Path, description = "Coupon ID (UUID format)")
+    ),
+    responses(
+        (status = 200, description = "Orders the coupon was used on, newest first", body = [CouponRedemption]),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Coupons",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_redemptions(
+    state: web::Data<AppState>,
+    coupon_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let redemptions = coupon_service::get_redemptions(&state.mongo, &coupon_id).await?;
+    Ok(HttpResponse::Ok().json(redemptions))
+}
+
+#[utoipa::path(
+    post,
+    path = "/coupon/validate",
+    request_body = CreateOrderDto,
+    params(
+        ("currency" = Option<String>, Query, description = "Currency to price the order in; defaults to the base currency"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter")
+    ),
+    responses(
+        (status = 200, description = "The order as it would be priced with the coupon; nothing is reserved or redeemed", body = PriceBreakdown),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 400, description = "No coupon code in the request", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "No coupon code given"
+        })),
+        (status = 400, description = "The coupon cannot be used for this order", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "The coupon is not valid at this time"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 404, description = "The coupon, some products or the exchange rate not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Coupon not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Coupons",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn validate_coupon(
+    state: web::Data<AppState>,
+    data: web::Json<CreateOrderDto>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    if data.coupon_code.is_none() {
+        return Err(AppErrors::BadRequest("No coupon code given".to_string()));
+    }
+
+    let currency = requested_currency(&req)?;
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let quote = order_service::quote_order(&state.mongo, &data, &claims.sub, currency).await?;
+    Ok(HttpResponse::Ok().json(quote.pricing))
+}
@@ -0,0 +1,123 @@
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Result};
+use validator::Validate;
+
+use crate::{
+    dto::exchange_rate::{RateHistoryQuery, SetExchangeRateDto},
+    errors::{auth_error::AuthError, AppErrors, ErrorResponse},
+    models::{app::AppState, exchange_rate::ExchangeRate, res::MessageResponse},
+    services::exchange_rate_service,
+    utils::jwt::Claims,
+};
+
+#[utoipa::path(
+    get,
+    path = "/exchange_rate/rates",
+    responses(
+        (status = 200, description = "Latest rate for every currency, relative to the base currency", body = [ExchangeRate]),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Exchange rates"
+)]
+pub async fn get_current_rates(state: web::Data<AppState>) -> Result<HttpResponse, AppErrors> {
+    let rates = exchange_rate_service::get_current_rates(&state.mongo).await?;
+    Ok(HttpResponse::Ok().json(rates))
+}
+
+#[utoipa::path(
+    post,
+    path = "/exchange_rate/admin/set",
+    request_body = SetExchangeRateDto,
+    responses(
+        (status = 201, description = "Exchange rate updated successfully", body = MessageResponse, example = json!({
+            "message": "Exchange rate updated successfully"
+        })),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 400, description = "Rate for the base currency", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "USD is the base currency and always has a rate of 1"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Exchange rates",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_rate(
+    state: web::Data<AppState>,
+    data: web::Json<SetExchangeRateDto>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let answer =
+        exchange_rate_service::set_rate(&state.mongo, state.redis.clone(), claims.sub, data)
+            .await?;
+    Ok(HttpResponse::Created().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/exchange_rate/admin/history",
+    params(
+        ("currency" = Option<String>, Query, description = "Only return rates for this currency (UAH, EUR, USD)")
+    ),
+    responses(
+        (status = 200, description = "Every rate ever set, newest first", body = [ExchangeRate]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Exchange rates",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_rate_history(
+    state: web::Data<AppState>,
+    query: web::Query<RateHistoryQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    let history = exchange_rate_service::get_rate_history(&state.mongo, query.currency).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
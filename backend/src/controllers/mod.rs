@@ -1,9 +1,19 @@
 use actix_web::{HttpResponse, Responder};
 
+pub mod alert_controller;
+pub mod attribute_controller;
 pub mod auth_controller;
+pub mod bundle_controller;
+pub mod campaign_controller;
+pub mod cart_controller;
+pub mod coupon_controller;
+pub mod exchange_rate_controller;
 pub mod order_controller;
+pub mod payment_controller;
 pub mod product_controller;
+pub mod tax_controller;
 pub mod user_controller;
+pub mod wishlist_controller;
 
 pub async fn not_found() -> impl Responder {
     HttpResponse::NotFound().body("Not found!")
@@ -1,16 +1,28 @@
-use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Result};
+use actix_web::{http::header, web, HttpMessage, HttpRequest, HttpResponse, Result};
+use validator::Validate;
 
 use crate::{
-    dto::order::{CreateOrderDto, UpdateOrderDto},
+    dto::order::{
+        ChangeOrderStatusDto, CreateOrderDto, CreateOrderResponse, OrderExportQuery,
+        RefundLedgerEntry, RefundLedgerQuery, RefundOrderDto,
+    },
     errors::{auth_error::AuthError, AppErrors, ErrorResponse},
     models::{app::AppState, order::Order},
-    services::order_service,
-    utils::jwt::Claims,
+    services::{exchange_rate_service, order_service},
+    utils::{
+        currency::requested_currency,
+        etag::{etag, if_match_version},
+        jwt::Claims,
+    },
 };
 
 #[utoipa::path(
     get,
     path = "/order/admin/orders",
+    params(
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter")
+    ),
     responses(
         (status = 200, description = "List of all orders", body = [Order]),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
@@ -33,8 +45,13 @@ use crate::{
         ("bearer_auth" = [])  
     )
 )]
-pub async fn get_all_orders(db: web::Data<AppState>) -> Result<HttpResponse, AppErrors> {
-    let orders = order_service::get_all_orders(&db.mongo).await?;
+pub async fn get_all_orders(
+    db: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    let currency = requested_currency(&req)?;
+    let mut orders = order_service::get_all_orders(&db.mongo).await?;
+    exchange_rate_service::convert_orders(&db.mongo, &mut orders, currency).await?;
     Ok(HttpResponse::Ok().json(orders))
 }
 
@@ -42,9 +59,28 @@ pub async fn get_all_orders(db: web::Data<AppState>) -> Result<HttpResponse, App
     post,
     path = "/order/create",
     request_body = CreateOrderDto,
+    params(
+        ("currency" = Option<String>, Query, description = "Currency to price the order in; defaults to the base currency"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response back instead of repeating the request")
+    ),
     responses(
-        (status = 201, description = "Order created successfully", body = ErrorResponse, example = json!({
-            "message": "Order created successfully"
+        (status = 201, description = "Order created at current catalog prices, with the price breakdown and the taxes of its destination", body = CreateOrderResponse),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 400, description = "Nothing to order", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "The order is empty"
+        })),
+        (status = 400, description = "A product is sold out", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Not enough stock for Trail bike"
+        })),
+        (status = 400, description = "The coupon cannot be used for this order", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "The coupon has been used up"
         })),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
             "error": "jwt_error",
@@ -56,10 +92,18 @@ pub async fn get_all_orders(db: web::Data<AppState>) -> Result<HttpResponse, App
                 "message": "Necessary role: Admin"
             })
         ),
-        (status = 404, description = "Some products not found", body = ErrorResponse, example = json!({
+        (status = 404, description = "Some products, a variant, the coupon or the exchange rate not found", body = ErrorResponse, example = json!({
             "error": "not_found",
             "message": "Some product not found"
         })),
+        (status = 409, description = "A request with the same idempotency key is still in progress", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "A request with this idempotency key is still in progress"
+        })),
+        (status = 422, description = "The idempotency key was already used for a different request", body = ErrorResponse, example = json!({
+            "error": "idempotency_key_reused",
+            "message": "The idempotency key was already used for a different request"
+        })),
         (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
             "error": "database_error",
             "message": "Database error"
@@ -75,25 +119,43 @@ pub async fn create_order(
     new_order_data: web::Json<CreateOrderDto>,
     req: HttpRequest,
 ) -> Result<HttpResponse, AppErrors> {
-    if let Some(claims) = req.extensions().get::<Claims>() {
-        let answer =
-            order_service::create_order(&db.mongo, new_order_data, claims.sub.clone()).await?;
-        Ok(HttpResponse::Created().json(serde_json::json!({
-            "message": answer
-        })))
-    } else {
-        Err(AppErrors::Auth(AuthError::Unauthorized))
+    if let Err(e) = new_order_data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
     }
+
+    let currency = requested_currency(&req)?;
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let created = order_service::create_order(
+        &db.mongo,
+        db.redis.clone(),
+        new_order_data,
+        claims.sub,
+        currency,
+    )
+    .await?;
+    Ok(HttpResponse::Created().json(created))
 }
 
 #[utoipa::path(
     get,
     path = "/order/{id}",
     params(
-        ("id" = String, Path, description = "Order ID (UUID format)")
+        ("id" = String, Path, description = "Order ID (UUID format)"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter")
     ),
     responses(
-        (status = 200, description = "Order found", body = Order),
+        (status = 200, description = "Order found; customers only see their own orders", body = Order, headers(
+            ("ETag" = String, description = "Current version, send it back in If-Match when updating")
+        )),
         (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
             "error": "invalid_uuid",
             "message": "Invalid UUID format"
@@ -102,7 +164,7 @@ pub async fn create_order(
             "error": "jwt_error",
             "message": "Authorization error"
         })),
-        (status = 404, description = "Order not found", body = ErrorResponse, example = json!({
+        (status = 404, description = "Order not found or placed by another customer", body = ErrorResponse, example = json!({
             "error": "not_found",
             "message": "Order not found"
         })),
@@ -119,18 +181,120 @@ pub async fn create_order(
 pub async fn get_order(
     db: web::Data<AppState>,
     order_id: web::Path<String>,
+    req: HttpRequest,
 ) -> Result<HttpResponse, AppErrors> {
-    let order = order_service::get_order(&db.mongo, &order_id).await?;
-    Ok(HttpResponse::Ok().json(order))
+    let currency = requested_currency(&req)?;
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let mut order = order_service::get_order_for(&db.mongo, &order_id, &claims).await?;
+    exchange_rate_service::convert_orders(&db.mongo, std::slice::from_mut(&mut order), currency)
+        .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag(order.version)))
+        .json(order))
 }
 
 #[utoipa::path(
     put,
-    path = "/order/admin/update",
-    request_body = UpdateOrderDto,
+    path = "/order/admin/status/{id}",
+    params(
+        ("id" = String, Path, description = "Order ID (UUID format)"),
+        ("If-Match" = Option<String>, Header, description = "ETag from GET /order/{id}; the change fails with 409 if the order changed since")
+    ),
+    request_body = ChangeOrderStatusDto,
     responses(
-        (status = 200, description = "Order updated successfully", body = ErrorResponse, example = json!({
-            "message": "Order updated successfully"
+        (status = 200, description = "Order moved to the new status, with the transition added to its history. Cancelling restocks the order and refunds a paid one in full.", body = Order, headers(
+            ("ETag" = String, description = "New version")
+        )),
+        (status = 400, description = "The transition is not allowed; refunds go through POST /order/admin/refund/{id}", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "A Pending order cannot become Shipped"
+        })),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Order not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Order not found"
+        })),
+        (status = 409, description = "Order changed since the If-Match version was read", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "The order was changed by someone else"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn change_order_status(
+    db: web::Data<AppState>,
+    req: HttpRequest,
+    order_id: web::Path<String>,
+    data: web::Json<ChangeOrderStatusDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let expected_version = if_match_version(&req)?;
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let order = order_service::change_status(
+        &db.mongo,
+        db.redis.clone(),
+        db.payments.as_ref(),
+        &order_id,
+        data.into_inner(),
+        claims.sub,
+        expected_version,
+    )
+    .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag(order.version)))
+        .json(order))
+}
+
+#[utoipa::path(
+    post,
+    path = "/order/cancel/{id}",
+    params(
+        ("id" = String, Path, description = "Order ID (UUID format)"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response back instead of repeating the request")
+    ),
+    responses(
+        (status = 200, description = "Order cancelled and its units put back into stock; a paid order is refunded in full", body = Order, headers(
+            ("ETag" = String, description = "New version")
+        )),
+        (status = 400, description = "The order is already being processed", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "A Shipped order can no longer be cancelled"
         })),
         (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
             "error": "invalid_uuid",
@@ -140,6 +304,85 @@ pub async fn get_order(
             "error": "jwt_error",
             "message": "Authorization error"
         })),
+        (status = 404, description = "Order not found or placed by another customer", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Order not found"
+        })),
+        (status = 409, description = "The order changed while it was being cancelled", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "The order was changed by someone else"
+        })),
+        (status = 409, description = "A request with the same idempotency key is still in progress", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "A request with this idempotency key is still in progress"
+        })),
+        (status = 422, description = "The idempotency key was already used for a different request", body = ErrorResponse, example = json!({
+            "error": "idempotency_key_reused",
+            "message": "The idempotency key was already used for a different request"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn cancel_order(
+    db: web::Data<AppState>,
+    req: HttpRequest,
+    order_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let order = order_service::cancel_order(
+        &db.mongo,
+        db.redis.clone(),
+        db.payments.as_ref(),
+        &order_id,
+        claims.sub,
+    )
+    .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag(order.version)))
+        .json(order))
+}
+
+#[utoipa::path(
+    post,
+    path = "/order/admin/refund/{id}",
+    params(
+        ("id" = String, Path, description = "Order ID (UUID format)"),
+        ("If-Match" = Option<String>, Header, description = "ETag from GET /order/{id}; the refund fails with 409 if the order changed since"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response back instead of repeating the request")
+    ),
+    request_body = RefundOrderDto,
+    responses(
+        (status = 200, description = "Refund recorded on the order; the order becomes Refunded once nothing is left to refund", body = Order, headers(
+            ("ETag" = String, description = "New version")
+        )),
+        (status = 400, description = "The order is not paid or the amount is more than what is left", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "At most 12.50 USD can be refunded"
+        })),
+        (status = 400, description = "The payment provider declined the refund; nothing was recorded", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "The provider declined the refund"
+        })),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
         (status = 403, description = "Not enough rights", body = ErrorResponse,
             example = json!({
                 "error": "insufficient_permissions",
@@ -150,6 +393,18 @@ pub async fn get_order(
             "error": "not_found",
             "message": "Order not found"
         })),
+        (status = 409, description = "Order changed since the If-Match version was read", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "The order was changed by someone else"
+        })),
+        (status = 409, description = "A request with the same idempotency key is still in progress", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "A request with this idempotency key is still in progress"
+        })),
+        (status = 422, description = "The idempotency key was already used for a different request", body = ErrorResponse, example = json!({
+            "error": "idempotency_key_reused",
+            "message": "The idempotency key was already used for a different request"
+        })),
         (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
             "error": "database_error",
             "message": "Database error"
@@ -157,17 +412,165 @@ pub async fn get_order(
     ),
     tag = "Orders",
     security(
-        ("bearer_auth" = [])  
+        ("bearer_auth" = [])
     )
 )]
-pub async fn update_order(
+pub async fn refund_order(
     db: web::Data<AppState>,
-    new_order_data: web::Json<UpdateOrderDto>,
+    req: HttpRequest,
+    order_id: web::Path<String>,
+    data: web::Json<RefundOrderDto>,
 ) -> Result<HttpResponse, AppErrors> {
-    let answer = order_service::update_order(&db.mongo, new_order_data).await?;
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "message": answer
-    })))
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let expected_version = if_match_version(&req)?;
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let order = order_service::refund_order(
+        &db.mongo,
+        db.payments.as_ref(),
+        &order_id,
+        data.into_inner(),
+        claims.sub,
+        expected_version,
+    )
+    .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag(order.version)))
+        .json(order))
+}
+
+#[utoipa::path(
+    get,
+    path = "/order/admin/refunds",
+    params(
+        ("from" = Option<i64>, Query, description = "Only refunds issued at or after this Unix timestamp (seconds)"),
+        ("to" = Option<i64>, Query, description = "Only refunds issued before this Unix timestamp (seconds)")
+    ),
+    responses(
+        (status = 200, description = "Refunds in the order currency, oldest first", body = [RefundLedgerEntry]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_refund_ledger(
+    db: web::Data<AppState>,
+    query: web::Query<RefundLedgerQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    let ledger = order_service::get_refund_ledger(&db.mongo, query.from, query.to).await?;
+    Ok(HttpResponse::Ok().json(ledger))
+}
+
+#[utoipa::path(
+    get,
+    path = "/order/admin/refunds/export",
+    params(
+        ("from" = Option<i64>, Query, description = "Only refunds issued at or after this Unix timestamp (seconds)"),
+        ("to" = Option<i64>, Query, description = "Only refunds issued before this Unix timestamp (seconds)")
+    ),
+    responses(
+        (status = 200, description = "The refund ledger with the header refund_id,order_id,customer_id,refunded_at,amount,currency,reason,refunded_by; amounts in minor units", body = String, content_type = "text/csv"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn export_refund_ledger(
+    db: web::Data<AppState>,
+    query: web::Query<RefundLedgerQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    let ledger = order_service::get_refund_ledger(&db.mongo, query.from, query.to).await?;
+    let body = order_service::refund_ledger_csv(&ledger)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            "Content-Disposition",
+            "attachment; filename=\"refunds.csv\"",
+        ))
+        .body(body))
+}
+
+#[utoipa::path(
+    get,
+    path = "/order/admin/export",
+    params(
+        ("from" = Option<i64>, Query, description = "Only orders placed at or after this Unix timestamp (seconds)"),
+        ("to" = Option<i64>, Query, description = "Only orders placed before this Unix timestamp (seconds)")
+    ),
+    responses(
+        (status = 200, description = "Orders with the header order_id,customer_id,placed_at,status,currency,subtotal,discount,coupon,coupon_discount,tax_mode,tax,total,refunded; amounts in minor units of the order currency", body = String, content_type = "text/csv"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn export_orders(
+    db: web::Data<AppState>,
+    query: web::Query<OrderExportQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    let orders = order_service::get_orders_placed(&db.mongo, query.from, query.to).await?;
+    let body = order_service::orders_csv(&orders)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(("Content-Disposition", "attachment; filename=\"orders.csv\""))
+        .body(body))
 }
 
 #[utoipa::path(
@@ -198,6 +601,10 @@ pub async fn update_order(
             "error": "not_found",
             "message": "Order not found"
         })),
+        (status = 409, description = "The order is not cancelled, was refunded or was just changed", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "Only cancelled orders without refunds can be deleted"
+        })),
         (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
             "error": "database_error",
             "message": "Database error"
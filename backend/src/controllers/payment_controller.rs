@@ -0,0 +1,160 @@
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Result};
+use chrono::Utc;
+
+use crate::{
+    dto::payment::{PaymentIntentResponse, PaymentWebhookEvent, ReconcileReport},
+    errors::{auth_error::AuthError, AppErrors, ErrorResponse},
+    models::{app::AppState, res::MessageResponse},
+    services::payment_service,
+    utils::{jwt::Claims, webhook},
+};
+
+#[utoipa::path(
+    post,
+    path = "/payment/intent/{order_id}",
+    params(
+        ("order_id" = String, Path, description = "Order ID (UUID format)"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response back instead of repeating the request")
+    ),
+    responses(
+        (status = 200, description = "Payment started for the order total; an open payment of the order is returned again", body = PaymentIntentResponse),
+        (status = 400, description = "The order is not waiting for payment, or online payments are disabled", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "A Paid order cannot be paid"
+        })),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 404, description = "Order not found or placed by another customer", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Order not found"
+        })),
+        (status = 409, description = "A request with the same idempotency key is still in progress", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "A request with this idempotency key is still in progress"
+        })),
+        (status = 422, description = "The idempotency key was already used for a different request", body = ErrorResponse, example = json!({
+            "error": "idempotency_key_reused",
+            "message": "The idempotency key was already used for a different request"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Payments",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_intent(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    order_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let intent = payment_service::create_intent(
+        &state.mongo,
+        state.payments.as_ref(),
+        &order_id,
+        &claims.sub,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(intent))
+}
+
+#[utoipa::path(
+    post,
+    path = "/payment/webhook",
+    params(
+        ("X-Payment-Signature" = String, Header, description = "t=<unix timestamp>,v1=<hex HMAC-SHA256 of \"<timestamp>.<raw body>\" with PAYMENT_WEBHOOK_SECRET>")
+    ),
+    request_body = PaymentWebhookEvent,
+    responses(
+        (status = 200, description = "Event applied; events about settled payments change nothing", body = MessageResponse, example = json!({
+            "message": "Event processed"
+        })),
+        (status = 400, description = "Missing, stale or wrong signature, or a malformed event", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Invalid webhook signature"
+        })),
+        (status = 404, description = "No payment with this intent id", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Payment not found"
+        })),
+        (status = 500, description = "Internal server error; the provider should retry", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Payments"
+)]
+pub async fn webhook(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, AppErrors> {
+    let signature = req
+        .headers()
+        .get(webhook::SIGNATURE_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .ok_or_else(|| AppErrors::BadRequest("Invalid webhook signature".to_string()))?;
+
+    webhook::verify(signature, &body, Utc::now().timestamp())?;
+
+    let event: PaymentWebhookEvent = serde_json::from_slice(&body)
+        .map_err(|e| AppErrors::BadRequest(format!("Invalid event: {}", e)))?;
+
+    payment_service::handle_webhook(
+        &state.mongo,
+        state.redis.clone(),
+        state.payments.as_ref(),
+        event,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(MessageResponse {
+        message: "Event processed".to_string(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/payment/admin/reconcile",
+    responses(
+        (status = 200, description = "Payments unsettled for PAYMENT_RECONCILE_AFTER_MINUTES checked with the provider; this also runs on a timer", body = ReconcileReport),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Payments",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn reconcile(state: web::Data<AppState>) -> Result<HttpResponse, AppErrors> {
+    let report =
+        payment_service::reconcile(&state.mongo, state.redis.clone(), state.payments.as_ref())
+            .await?;
+    Ok(HttpResponse::Ok().json(report))
+}
@@ -1,18 +1,61 @@
-use actix_web::{web, HttpResponse, Result};
+use std::collections::HashMap;
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Result};
 use validator::Validate;
 
 use crate::{
-    dto::product::{CreateProductDto, UpdateProductDto},
+    dto::attribute::ProductFilter,
+    dto::product::{
+        AdminProductsQuery, BulkFormat, ChangeProductStatusDto, CompareQuery, CreateProductDto,
+        DealRanking, DealsQuery, DealsResponse, ExportQuery, ImportQuery, ImportReport,
+        ProductAnalytics, ProductComparison, RelatedQuery, SetStockDto, TranslationDto,
+        UpdateProductDto,
+    },
     errors::{AppErrors, ErrorResponse},
-    models::{app::AppState, product::Product},
-    services::product_service,
+    models::{
+        app::AppState,
+        locale::{Locale, Localize},
+        price_history::PriceHistory,
+        product::{Product, ProductStatus},
+        res::MessageResponse,
+    },
+    services::{
+        exchange_rate_service, price_history_service, product_bulk_service, product_service,
+        recommendation_service,
+    },
+    utils::{
+        currency::requested_currency,
+        etag::{etag, if_match_version},
+        http_cache::{self, CacheRoute},
+        locale::requested_locale,
+    },
 };
 
 #[utoipa::path(
     get,
     path = "/product/products",
+    params(
+        ("category" = Option<u8>, Query, description = "Only products of this category"),
+        ("q" = Option<String>, Query, description = "Only products whose name or description in any locale contains these words"),
+        ("attr.{key}" = Option<String>, Query, description = "Only products whose attribute `key` has one of these comma-separated values, e.g. `attr.frame_material=steel`"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag of the cached copy; answers 304 when it is still current"),
+        ("If-Modified-Since" = Option<String>, Header, description = "Used when If-None-Match is absent")
+    ),
     responses(
-        (status = 200, description = "List of all products", body = [Product]),
+        (status = 200, description = "List of all products", body = [Product], headers(
+            ("ETag" = String, description = "Catalog version of this response"),
+            ("Last-Modified" = String, description = "Last catalog change"),
+            ("Cache-Control" = String, description = "Policy set by CACHE_CONTROL_PRODUCTS")
+        )),
+        (status = 304, description = "The cached copy is still current"),
+        (status = 400, description = "Unsupported currency or invalid filter", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Unsupported currency: GBP"
+        })),
         (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
             "error": "database_error",
             "message": "Database error"
@@ -20,9 +63,28 @@ use crate::{
     ),
     tag = "Products"
 )]
-pub async fn get_all_products(state: web::Data<AppState>) -> Result<HttpResponse, AppErrors> {
-    let products = product_service::get_all_products(&state.mongo).await?;
-    Ok(HttpResponse::Ok().json(products))
+pub async fn get_all_products(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<HttpResponse, AppErrors> {
+    let currency = requested_currency(&req)?;
+    let locale = requested_locale(&req)?;
+    let filter = ProductFilter::from_query(&query)?;
+
+    let validator =
+        http_cache::catalog_validator(&state.mongo, state.redis.clone(), &req, None).await?;
+    if validator.is_fresh(&req) {
+        return Ok(validator.not_modified(CacheRoute::Products));
+    }
+
+    let mut products = product_service::get_all_products(&state.mongo, &filter).await?;
+    exchange_rate_service::convert_products(&state.mongo, &mut products, currency).await?;
+    products.localize(locale);
+
+    let mut response = HttpResponse::Ok();
+    validator.respond(&mut response, CacheRoute::Products);
+    Ok(response.json(products))
 }
 
 #[utoipa::path(
@@ -80,10 +142,21 @@ pub async fn create_product(
     get,
     path = "/product/{id}",
     params(
-        ("id" = String, Path, description = "Product ID (UUID format)")
+        ("id" = String, Path, description = "Product ID (UUID format)"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag of the cached copy; answers 304 when it is still current"),
+        ("If-Modified-Since" = Option<String>, Header, description = "Used when If-None-Match is absent")
     ),
     responses(
-        (status = 200, description = "Product found", body = Product),
+        (status = 200, description = "Product found", body = Product, headers(
+            ("ETag" = String, description = "Starts with the product version; send it back in If-Match when updating"),
+            ("Last-Modified" = String, description = "Last catalog change"),
+            ("Cache-Control" = String, description = "Policy set by CACHE_CONTROL_PRODUCT")
+        )),
+        (status = 304, description = "The cached copy is still current"),
         (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
             "error": "invalid_uuid",
             "message": "Invalid UUID format",
@@ -103,17 +176,47 @@ pub async fn create_product(
 pub async fn get_product(
     state: web::Data<AppState>,
     product_id: web::Path<String>,
+    req: HttpRequest,
 ) -> Result<HttpResponse, AppErrors> {
-    let product = product_service::get_product(&state.mongo, &product_id).await?;
-    Ok(HttpResponse::Ok().json(product))
+    let currency = requested_currency(&req)?;
+    let locale = requested_locale(&req)?;
+    let mut product = product_service::get_product(&state.mongo, &product_id).await?;
+    exchange_rate_service::convert_products(
+        &state.mongo,
+        std::slice::from_mut(&mut product),
+        currency,
+    )
+    .await?;
+
+    let validator = http_cache::catalog_validator(
+        &state.mongo,
+        state.redis.clone(),
+        &req,
+        Some(product.version),
+    )
+    .await?;
+    if validator.is_fresh(&req) {
+        return Ok(validator.not_modified(CacheRoute::Product));
+    }
+
+    product.localize(locale);
+
+    let mut response = HttpResponse::Ok();
+    validator.respond(&mut response, CacheRoute::Product);
+    Ok(response.json(product))
 }
 
 #[utoipa::path(
     put,
     path = "/product/admin/update",
+    params(
+        ("If-Match" = Option<String>, Header, description = "ETag from GET /product/{id}; the update fails with 409 if the product changed since")
+    ),
     request_body = UpdateProductDto,
     responses(
-        (status = 200, description = "Product updated successfully", body = ErrorResponse, example = json!({
+        (status = 200, description = "Product updated successfully", body = ErrorResponse, headers(
+            ("ETag" = String, description = "New version")
+        ), example = json!({
             "message": "Product updated successfully"
         })),
         (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
@@ -134,6 +237,10 @@ pub async fn get_product(
             "error": "not_found",
             "message": "Product not found"
         })),
+        (status = 409, description = "Product changed since the If-Match version was read", body = ErrorResponse, example = json!({
+            "error": "conflict",
+            "message": "The product was changed by someone else"
+        })),
         (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
             "error": "database_error",
             "message": "Database error"
@@ -146,14 +253,22 @@ pub async fn get_product(
 )]
 pub async fn update_product(
     state: web::Data<AppState>,
+    req: HttpRequest,
     new_product_data: web::Json<UpdateProductDto>,
 ) -> Result<HttpResponse, AppErrors> {
-    let answer =
-        product_service::update_product(&state.mongo, state.redis.clone(), new_product_data)
-            .await?;
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "message": answer
-    })))
+    let expected_version = if_match_version(&req)?;
+    let version = product_service::update_product(
+        &state.mongo,
+        state.redis.clone(),
+        new_product_data,
+        expected_version,
+    )
+    .await?;
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag(version)))
+        .json(serde_json::json!({
+            "message": "Product updated successfully"
+        })))
 }
 
 #[utoipa::path(
@@ -163,7 +278,7 @@ pub async fn update_product(
         ("id" = String, Path, description = "Product ID (UUID format)")
     ),
     responses(
-        (status = 200, description = "Product deleted successfully", body = ErrorResponse, example = json!({
+        (status = 200, description = "Product hidden from the catalog; it can be restored later", body = ErrorResponse, example = json!({
             "message": "Product deleted successfully"
         })),
         (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
@@ -208,8 +323,18 @@ pub async fn delete_product(
 #[utoipa::path(
     get,
     path = "/product/most_advantageous",
+    params(
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
     responses(
         (status = 200, description = "Most advantageous product", body = Product),
+        (status = 400, description = "Unsupported currency", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Unsupported currency: GBP"
+        })),
         (status = 404, description = "No products found", body = ErrorResponse, example = json!({
             "error": "not_found",
             "message": "Product not found"
@@ -221,7 +346,717 @@ pub async fn delete_product(
     ),
     tag = "Products"
 )]
-pub async fn get_most_advantageous(state: web::Data<AppState>) -> Result<HttpResponse, AppErrors> {
-    let product = product_service::get_most_advantageous(&state.mongo, state.redis.clone()).await?;
+pub async fn get_most_advantageous(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    let currency = requested_currency(&req)?;
+    let locale = requested_locale(&req)?;
+    let mut product =
+        product_service::get_most_advantageous(&state.mongo, state.redis.clone()).await?;
+    exchange_rate_service::convert_products(
+        &state.mongo,
+        std::slice::from_mut(&mut product),
+        currency,
+    )
+    .await?;
+    product.localize(locale);
     Ok(HttpResponse::Ok().json(product))
 }
+
+#[utoipa::path(
+    get,
+    path = "/product/deals",
+    params(
+        ("limit" = Option<usize>, Query, description = "Deals per list, 1 to 50 (default 10)"),
+        ("rank_by" = Option<DealRanking>, Query, description = "savings (default) ranks by the amount taken off, percent by the discount percentage"),
+        ("category" = Option<u8>, Query, description = "Only deals from this category"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag of the cached copy; answers 304 when it is still current"),
+        ("If-Modified-Since" = Option<String>, Header, description = "Used when If-None-Match is absent")
+    ),
+    responses(
+        (status = 200, description = "Best deals overall and within each category", body = DealsResponse, headers(
+            ("ETag" = String, description = "Catalog version of this response"),
+            ("Last-Modified" = String, description = "Last catalog change"),
+            ("Cache-Control" = String, description = "Policy set by CACHE_CONTROL_DEALS")
+        )),
+        (status = 304, description = "The cached copy is still current"),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products"
+)]
+pub async fn get_deals(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<DealsQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = query.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let currency = requested_currency(&req)?;
+    let locale = requested_locale(&req)?;
+
+    let validator =
+        http_cache::catalog_validator(&state.mongo, state.redis.clone(), &req, None).await?;
+    if validator.is_fresh(&req) {
+        return Ok(validator.not_modified(CacheRoute::Deals));
+    }
+
+    let mut deals = product_service::get_deals(&state.mongo, state.redis.clone(), &query).await?;
+    exchange_rate_service::convert_deals(&state.mongo, &mut deals, currency).await?;
+    deals.localize(locale);
+
+    let mut response = HttpResponse::Ok();
+    validator.respond(&mut response, CacheRoute::Deals);
+    Ok(response.json(deals))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product/{id}/related",
+    params(
+        ("id" = String, Path, description = "Product ID (UUID format)"),
+        ("limit" = Option<usize>, Query, description = "Number of products, 1 to 20 (default 5)"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    responses(
+        (status = 200, description = "Products frequently bought together with this one, then products from the same category", body = [Product]),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 404, description = "Product not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Product not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products"
+)]
+pub async fn get_related_products(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    product_id: web::Path<String>,
+    query: web::Query<RelatedQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = query.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let currency = requested_currency(&req)?;
+    let locale = requested_locale(&req)?;
+    let mut products =
+        recommendation_service::get_related(&state.mongo, &product_id, &query).await?;
+    exchange_rate_service::convert_products(&state.mongo, &mut products, currency).await?;
+    products.localize(locale);
+    Ok(HttpResponse::Ok().json(products))
+}
+
+#[utoipa::path(
+    post,
+    path = "/product/admin/related/rebuild",
+    responses(
+        (status = 200, description = "Co-purchase counts recomputed from all orders", body = ErrorResponse, example = json!({
+            "message": "Rebuilt 42 product pairs"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn rebuild_related(state: web::Data<AppState>) -> Result<HttpResponse, AppErrors> {
+    let count = recommendation_service::rebuild_pairs(&state.mongo).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": format!("Rebuilt {} product pairs", count)
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product/admin/price_history/{id}",
+    params(
+        ("id" = String, Path, description = "Product ID (UUID format)")
+    ),
+    responses(
+        (status = 200, description = "Price and discount changes, oldest first", body = [PriceHistory]),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_price_history(
+    state: web::Data<AppState>,
+    product_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let history = price_history_service::get_price_history(&state.mongo, &product_id).await?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+fn format_from_content_type(req: &HttpRequest) -> Option<BulkFormat> {
+    let content_type = req
+        .headers()
+        .get("Content-Type")
+        .and_then(|h| h.to_str().ok())?;
+
+    if content_type.starts_with("text/csv") {
+        Some(BulkFormat::Csv)
+    } else if content_type.starts_with("application/x-ndjson")
+        || content_type.starts_with("application/jsonl")
+    {
+        Some(BulkFormat::Jsonl)
+    } else {
+        None
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/product/admin/import",
+    params(
+        ("format" = Option<BulkFormat>, Query, description = "csv or jsonl, defaults to the Content-Type (text/csv, application/x-ndjson)"),
+        ("mode" = Option<String>, Query, description = "insert (default) or upsert by SKU"),
+        ("dry_run" = Option<bool>, Query, description = "Validate every row without writing anything")
+    ),
+    request_body(
+        content = String,
+        description = "CSV with the header sku,name,price_amount,price_currency,description,images,discount,category (images separated by |), or one CreateProductDto JSON object per line",
+        content_type = "text/csv"
+    ),
+    responses(
+        (status = 200, description = "Import finished; rows are saved one by one, failed rows are listed in the report", body = ImportReport),
+        (status = 400, description = "Unknown file format", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Specify format=csv or format=jsonl"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn import_products(
+    state: web::Data<AppState>,
+    query: web::Query<ImportQuery>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, AppErrors> {
+    let format = query
+        .format
+        .or_else(|| format_from_content_type(&req))
+        .ok_or_else(|| AppErrors::BadRequest("Specify format=csv or format=jsonl".to_string()))?;
+
+    let report = product_bulk_service::import_products(
+        &state.mongo,
+        state.redis.clone(),
+        &body,
+        format,
+        query.mode,
+        query.dry_run,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product/admin/export",
+    params(
+        ("format" = Option<BulkFormat>, Query, description = "csv (default) or jsonl")
+    ),
+    responses(
+        (status = 200, description = "The whole catalog in the same format the import accepts", body = String, content_type = "text/csv"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn export_products(
+    state: web::Data<AppState>,
+    query: web::Query<ExportQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    let format = query.format.unwrap_or(BulkFormat::Csv);
+    let body = product_bulk_service::export_products(&state.mongo, format).await?;
+
+    let (content_type, extension) = match format {
+        BulkFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        BulkFormat::Jsonl => ("application/x-ndjson", "jsonl"),
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"products.{}\"", extension),
+        ))
+        .body(body))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product/admin/products",
+    params(
+        ("status" = Option<ProductStatus>, Query, description = "Only products with this status"),
+        ("include_deleted" = Option<bool>, Query, description = "Include soft-deleted products (default false)")
+    ),
+    responses(
+        (status = 200, description = "Products in any status, sorted by name", body = [Product]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_admin_products(
+    state: web::Data<AppState>,
+    query: web::Query<AdminProductsQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    let products = product_service::get_admin_products(&state.mongo, &query).await?;
+    Ok(HttpResponse::Ok().json(products))
+}
+
+#[utoipa::path(
+    put,
+    path = "/product/admin/status/{id}",
+    params(
+        ("id" = String, Path, description = "Product ID (UUID format)")
+    ),
+    request_body = ChangeProductStatusDto,
+    responses(
+        (status = 200, description = "Status changed", body = ErrorResponse, example = json!({
+            "message": "Product status changed to Published"
+        })),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Product not found or deleted", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Product not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn change_product_status(
+    state: web::Data<AppState>,
+    product_id: web::Path<String>,
+    data: web::Json<ChangeProductStatusDto>,
+) -> Result<HttpResponse, AppErrors> {
+    let answer =
+        product_service::change_status(&state.mongo, state.redis.clone(), &product_id, data.status)
+            .await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": answer
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/product/admin/restore/{id}",
+    params(
+        ("id" = String, Path, description = "Product ID (UUID format)")
+    ),
+    responses(
+        (status = 200, description = "Product restored with its previous status", body = ErrorResponse, example = json!({
+            "message": "Product restored successfully"
+        })),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "No deleted product with this ID", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Deleted product not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn restore_product(
+    state: web::Data<AppState>,
+    product_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let answer =
+        product_service::restore_product(&state.mongo, state.redis.clone(), &product_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": answer
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product/admin/analytics",
+    responses(
+        (status = 200, description = "Wishlist and order counts of every product, including archived and deleted ones, most wished-for first", body = [ProductAnalytics]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_product_analytics(state: web::Data<AppState>) -> Result<HttpResponse, AppErrors> {
+    let analytics = product_service::get_analytics(&state.mongo).await?;
+    Ok(HttpResponse::Ok().json(analytics))
+}
+
+#[utoipa::path(
+    put,
+    path = "/product/admin/stock/{id}",
+    params(
+        ("id" = String, Path, description = "Product ID (UUID format)")
+    ),
+    request_body = SetStockDto,
+    responses(
+        (status = 200, description = "Stock updated; back-in-stock alerts are evaluated in the background", body = MessageResponse, example = json!({
+            "message": "Stock updated successfully"
+        })),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Product not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Product not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_stock(
+    state: web::Data<AppState>,
+    product_id: web::Path<String>,
+    data: web::Json<SetStockDto>,
+) -> Result<HttpResponse, AppErrors> {
+    let answer =
+        product_service::set_stock(&state.mongo, state.redis.clone(), &product_id, data.stock)
+            .await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/product/admin/translations/{id}/{locale}",
+    params(
+        ("id" = String, Path, description = "Product ID (UUID format)"),
+        ("locale" = Locale, Path, description = "Any supported locale except DEFAULT_LOCALE, whose text is the product's own name and description")
+    ),
+    request_body = TranslationDto,
+    responses(
+        (status = 200, description = "Translation added or replaced", body = MessageResponse, example = json!({
+            "message": "Translation saved successfully"
+        })),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 400, description = "Unsupported or default locale", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Unsupported locale: de"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Product not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Product not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_translation(
+    state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+    data: web::Json<TranslationDto>,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let (product_id, locale) = path.into_inner();
+    let answer = product_service::set_translation(
+        &state.mongo,
+        state.redis.clone(),
+        &product_id,
+        &locale,
+        data.into_inner(),
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/product/admin/translations/{id}/{locale}",
+    params(
+        ("id" = String, Path, description = "Product ID (UUID format)"),
+        ("locale" = Locale, Path, description = "Locale of the translation")
+    ),
+    responses(
+        (status = 200, description = "Translation removed; the default text is shown in its place", body = MessageResponse, example = json!({
+            "message": "Translation deleted successfully"
+        })),
+        (status = 400, description = "Unsupported or default locale", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Unsupported locale: de"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Product or translation not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Translation not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_translation(
+    state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, AppErrors> {
+    let (product_id, locale) = path.into_inner();
+    let answer = product_service::delete_translation(
+        &state.mongo,
+        state.redis.clone(),
+        &product_id,
+        &locale,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product/compare",
+    params(
+        ("ids" = String, Query, description = "2 to 4 comma-separated product ids of the same category"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    responses(
+        (status = 200, description = "Products side by side, aligned by attribute", body = ProductComparison),
+        (status = 400, description = "Products from different categories", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Only products from the same category can be compared"
+        })),
+        (status = 400, description = "Too few or too many products", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Compare between 2 and 4 different products"
+        })),
+        (status = 404, description = "A product is not published", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Product not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Products"
+)]
+pub async fn compare_products(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<CompareQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    let currency = requested_currency(&req)?;
+    let locale = requested_locale(&req)?;
+    let mut comparison = product_service::compare_products(&state.mongo, &query, currency).await?;
+    comparison.localize(locale);
+    Ok(HttpResponse::Ok().json(comparison))
+}
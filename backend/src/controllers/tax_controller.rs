@@ -0,0 +1,150 @@
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Result};
+use validator::Validate;
+
+use crate::{
+    dto::tax::{SetTaxRuleDto, TaxRulesQuery},
+    errors::{auth_error::AuthError, AppErrors, ErrorResponse},
+    models::{app::AppState, res::MessageResponse, tax::TaxRule},
+    services::tax_service,
+    utils::jwt::Claims,
+};
+
+#[utoipa::path(
+    post,
+    path = "/tax/admin/rules/set",
+    request_body = SetTaxRuleDto,
+    responses(
+        (status = 201, description = "Tax rule saved successfully", body = MessageResponse, example = json!({
+            "message": "Tax rule saved successfully"
+        })),
+        (status = 400, description = "Validation failed", body = ErrorResponse, example = json!({
+            "error": "validation_error",
+            "message": "Validation failed"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 409, description = "The same rule was saved concurrently", body = ErrorResponse, example = json!({
+            "error": "duplicate_key",
+            "message": "The tax rule was just saved by someone else"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Taxes",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_tax_rule(
+    state: web::Data<AppState>,
+    data: web::Json<SetTaxRuleDto>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    if let Err(e) = data.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": format!("Validation failed: {:?}", e)
+        })));
+    }
+
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let answer = tax_service::set_rule(&state.mongo, claims.sub, data).await?;
+    Ok(HttpResponse::Created().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tax/admin/rules",
+    params(
+        ("country" = Option<String>, Query, description = "Only return rules for this country (ISO 3166-1 alpha-2)")
+    ),
+    responses(
+        (status = 200, description = "Tax rules by country, region and category", body = [TaxRule]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Taxes",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_tax_rules(
+    state: web::Data<AppState>,
+    query: web::Query<TaxRulesQuery>,
+) -> Result<HttpResponse, AppErrors> {
+    let rules = tax_service::get_rules(&state.mongo, query.country.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(rules))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/tax/admin/rules/delete/{id}",
+    params(
+        ("id" = String, Path, description = "Tax rule ID (UUID format)")
+    ),
+    responses(
+        (status = 200, description = "Tax rule deleted successfully", body = MessageResponse, example = json!({
+            "message": "Tax rule deleted successfully"
+        })),
+        (status = 400, description = "Invalid UUID format", body = ErrorResponse, example = json!({
+            "error": "invalid_uuid",
+            "message": "Invalid UUID format"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 403, description = "Not enough rights", body = ErrorResponse,
+            example = json!({
+                "error": "insufficient_permissions",
+                "message": "Necessary role: Admin"
+            })
+        ),
+        (status = 404, description = "Tax rule not found", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Tax rule not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Taxes",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_tax_rule(
+    state: web::Data<AppState>,
+    rule_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let answer = tax_service::delete_rule(&state.mongo, &rule_id).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
@@ -5,8 +5,8 @@ use crate::{
     dto::{auth::UserInfo, user::UpdateUserDto},
     errors::{auth_error::AuthError, AppErrors, ErrorResponse},
     models::{app::AppState, order::Order, res::MessageResponse},
-    services::user_service,
-    utils::jwt::Claims,
+    services::{exchange_rate_service, user_service},
+    utils::{currency::requested_currency, jwt::Claims},
 };
 
 #[utoipa::path(
@@ -153,6 +153,10 @@ pub async fn get_all_users(db: web::Data<AppState>) -> Result<HttpResponse, AppE
 #[utoipa::path(
     get,
     path = "/user/my_orders",
+    params(
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter")
+    ),
     responses(
         (status = 200, body = [Order]),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
@@ -177,10 +181,14 @@ pub async fn get_my_orders(
     db: web::Data<AppState>,
     req: HttpRequest,
 ) -> Result<HttpResponse, AppErrors> {
-    if let Some(claims) = req.extensions().get::<Claims>() {
-        let res = user_service::get_my_orders(&db.mongo, claims.sub.clone()).await?;
-        Ok(HttpResponse::Ok().json(res))
-    } else {
-        Err(AppErrors::Auth(AuthError::Unauthorized))
-    }
+    let currency = requested_currency(&req)?;
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))?;
+
+    let mut res = user_service::get_my_orders(&db.mongo, claims.sub).await?;
+    exchange_rate_service::convert_orders(&db.mongo, &mut res, currency).await?;
+    Ok(HttpResponse::Ok().json(res))
 }
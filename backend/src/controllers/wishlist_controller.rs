@@ -0,0 +1,194 @@
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Result};
+
+use crate::{
+    dto::wishlist::WishlistEntry,
+    errors::{auth_error::AuthError, AppErrors, ErrorResponse},
+    models::{
+        app::AppState,
+        locale::{Locale, Localize},
+        product::Product,
+        res::MessageResponse,
+    },
+    services::{exchange_rate_service, wishlist_service},
+    utils::{currency::requested_currency, jwt::Claims, locale::requested_locale},
+};
+
+fn claims(req: &HttpRequest) -> Result<Claims, AppErrors> {
+    req.extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(AppErrors::Auth(AuthError::Unauthorized))
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/wishlist",
+    params(
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    responses(
+        (status = 200, description = "Wishlist, newest first. Archived or deleted products are kept with `available: false`", body = [WishlistEntry]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Wishlist",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_wishlist(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppErrors> {
+    let currency = requested_currency(&req)?;
+    let locale = requested_locale(&req)?;
+    let claims = claims(&req)?;
+
+    let mut entries = wishlist_service::get_wishlist(&state.mongo, &claims.sub).await?;
+    exchange_rate_service::convert_wishlist(&state.mongo, &mut entries, currency).await?;
+    entries.localize(locale);
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/wishlist/{product_id}",
+    params(
+        ("product_id" = String, Path, description = "Product UUID")
+    ),
+    responses(
+        (status = 200, description = "Product added; adding it twice has no effect", body = MessageResponse, example = json!({
+            "message": "Product added to wishlist"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 404, description = "No published product with this id", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Product not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Wishlist",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn add_to_wishlist(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    product_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let claims = claims(&req)?;
+
+    let answer = wishlist_service::add_item(&state.mongo, &claims.sub, &product_id).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/user/wishlist/{product_id}",
+    params(
+        ("product_id" = String, Path, description = "Product UUID")
+    ),
+    responses(
+        (status = 200, description = "Product removed", body = MessageResponse, example = json!({
+            "message": "Product removed from wishlist"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 404, description = "The product is not on the wishlist", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Wishlist item not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Wishlist",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn remove_from_wishlist(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    product_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let claims = claims(&req)?;
+
+    let answer = wishlist_service::remove_item(&state.mongo, &claims.sub, &product_id).await?;
+    Ok(HttpResponse::Ok().json(MessageResponse { message: answer }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/wishlist/{product_id}/move_to_cart",
+    params(
+        ("product_id" = String, Path, description = "Product UUID"),
+        ("currency" = Option<String>, Query, description = "ISO code of the currency to show prices in (UAH, EUR, USD)"),
+        ("Accept-Currency" = Option<String>, Header, description = "Alternative to the `currency` query parameter"),
+        ("locale" = Option<Locale>, Query, description = "Language of product names and descriptions (uk, en); defaults to DEFAULT_LOCALE"),
+        ("Accept-Language" = Option<String>, Header, description = "Used when the `locale` query parameter is absent")
+    ),
+    responses(
+        (status = 200, description = "One unit of the product added to the cart and the product taken off the wishlist", body = Product),
+        (status = 400, description = "The product can no longer be ordered and stays on the wishlist", body = ErrorResponse, example = json!({
+            "error": "bad_request",
+            "message": "Product is no longer available"
+        })),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!({
+            "error": "jwt_error",
+            "message": "Authorization error"
+        })),
+        (status = 404, description = "The product is not on the wishlist", body = ErrorResponse, example = json!({
+            "error": "not_found",
+            "message": "Wishlist item not found"
+        })),
+        (status = 500, description = "Internal server error", body = ErrorResponse, example = json!({
+            "error": "database_error",
+            "message": "Database error"
+        }))
+    ),
+    tag = "Wishlist",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn move_to_cart(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    product_id: web::Path<String>,
+) -> Result<HttpResponse, AppErrors> {
+    let currency = requested_currency(&req)?;
+    let locale = requested_locale(&req)?;
+    let claims = claims(&req)?;
+
+    let mut product =
+        wishlist_service::move_to_cart(&state.mongo, state.redis.clone(), &claims.sub, &product_id)
+            .await?;
+    exchange_rate_service::convert_products(
+        &state.mongo,
+        std::slice::from_mut(&mut product),
+        currency,
+    )
+    .await?;
+    product.localize(locale);
+    Ok(HttpResponse::Ok().json(product))
+}
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::env;
+
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use mongodb::{
+    bson::{doc, to_bson, Bson},
+    Database,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        money::{Currency, Money},
+        order::{Order, OrderItem},
+        product_pair::{count_pairs, ProductPair},
+    },
+    utils::currency::get_base_currency,
+};
+
+/// Currency assumed for documents stored before prices carried one. Panics on
+/// an unsupported code rather than writing prices nobody can read back.
+fn get_legacy_currency() -> Currency {
+    match env::var("LEGACY_PRICE_CURRENCY") {
+        Ok(code) => code
+            .parse::<Currency>()
+            .unwrap_or_else(|e| panic!("Invalid LEGACY_PRICE_CURRENCY: {}", e)),
+        Err(_) => Currency::Usd,
+    }
+}
+
+pub async fn run_migrations(db: &Database) {
+    migrate_money_minor_units(db).await;
+    migrate_order_exchange_rate(db).await;
+    // Runs early because every later reader expects orders with line items
+    migrate_order_line_items(db).await;
+    migrate_order_status(db).await;
+    migrate_product_status(db).await;
+    migrate_product_pairs(db).await;
+    migrate_document_versions(db).await;
+}
+
+async fn is_applied(db: &Database, name: &str) -> bool {
+    db.collection::<mongodb::bson::Document>("migrations")
+        .find_one(doc! { "_id": name })
+        .await
+        .expect("Failed to read migrations")
+        .is_some()
+}
+
+async fn mark_applied(db: &Database, name: &str) {
+    db.collection::<mongodb::bson::Document>("migrations")
+        .insert_one(doc! { "_id": name, "applied_at": Utc::now().timestamp() })
+        .await
+        .expect("Failed to record migration");
+}
+
+/// Bare `u32` prices in major units become `Money { amount, currency }` in minor units
+async fn migrate_money_minor_units(db: &Database) {
+    const NAME: &str = "0001_money_minor_units";
+
+    if is_applied(db, NAME).await {
+        return;
+    }
+
+    let currency = get_legacy_currency();
+
+    for (collection, field) in [
+        ("products", "price"),
+        ("price_history", "price"),
+        ("orders", "total_price"),
+    ] {
+        let path = format!("${}", field);
+        let result = db
+            .collection::<mongodb::bson::Document>(collection)
+            .update_many(
+                doc! { field: { "$type": "number" } },
+                vec![doc! {
+                    "$set": {
+                        field: {
+                            "amount": { "$toLong": { "$multiply": [&path, 100] } },
+                            "currency": currency.code(),
+                        }
+                    }
+                }],
+            )
+            .await
+            .expect("Failed to migrate prices");
+
+        println!(
+            "✅ Migrated {} {} to minor units",
+            result.modified_count, collection
+        );
+    }
+
+    mark_applied(db, NAME).await;
+}
+
+/// Orders placed before multi-currency support were paid in the legacy
+/// currency, at a rate of 1.0 when that is the base currency
+async fn migrate_order_exchange_rate(db: &Database) {
+    const NAME: &str = "0002_order_exchange_rate";
+
+    if is_applied(db, NAME).await {
+        return;
+    }
+
+    let orders = db.collection::<mongodb::bson::Document>("orders");
+
+    // The rate of the day each order was paid was never stored, so it can
+    // only be known when the order was paid in the base currency
+    let legacy_currency = get_legacy_currency();
+    if legacy_currency != get_base_currency() {
+        let pending = orders
+            .count_documents(doc! { "exchange_rate": { "$exists": false } })
+            .await
+            .expect("Failed to count orders");
+
+        assert!(
+            pending == 0,
+            "{} orders were paid in {} without an exchange rate to the base currency {}; \
+             add one to each order before starting the server",
+            pending,
+            legacy_currency,
+            get_base_currency()
+        );
+    }
+
+    let result = orders
+        .update_many(
+            doc! { "exchange_rate": { "$exists": false } },
+            doc! { "$set": { "exchange_rate": 1.0 } },
+        )
+        .await
+        .expect("Failed to migrate orders");
+
+    println!("✅ Added exchange rate to {} orders", result.modified_count);
+
+    mark_applied(db, NAME).await;
+}
+
+#[derive(Deserialize)]
+struct LegacyOrder {
+    _id: Bson,
+    products_id: Vec<String>,
+    total_price: Money,
+    exchange_rate: f64,
+}
+
+#[derive(Deserialize)]
+struct LegacyProduct {
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    _id: Uuid,
+    name: String,
+    price: Money,
+}
+
+/// Orders used to list product ids only, one entry per unit. They get line
+/// items with today's names and prices, converted at the rate of the order;
+/// whatever the lines cost above the paid total is recorded as discount.
+async fn migrate_order_line_items(db: &Database) {
+    const NAME: &str = "0006_order_line_items";
+
+    if is_applied(db, NAME).await {
+        return;
+    }
+
+    let orders = db.collection::<mongodb::bson::Document>("orders");
+
+    let legacy: Vec<LegacyOrder> = orders
+        .find(doc! { "products_id": { "$exists": true }, "items": { "$exists": false } })
+        .await
+        .expect("Failed to read orders")
+        .with_type::<LegacyOrder>()
+        .try_collect()
+        .await
+        .expect("Failed to read orders");
+
+    let products: HashMap<String, LegacyProduct> = db
+        .collection::<LegacyProduct>("products")
+        .find(doc! {})
+        .projection(doc! { "name": 1, "price": 1 })
+        .await
+        .expect("Failed to read products")
+        .try_collect::<Vec<LegacyProduct>>()
+        .await
+        .expect("Failed to read products")
+        .into_iter()
+        .map(|product| (product._id.to_string(), product))
+        .collect();
+
+    for order in &legacy {
+        let currency = order.total_price.currency;
+
+        let mut items: Vec<OrderItem> = Vec::new();
+        for product_id in &order.products_id {
+            if let Some(item) = items.iter_mut().find(|i| &i.product_id == product_id) {
+                item.quantity += 1;
+                item.total = Money::new(item.total.amount + item.unit_price.amount, currency);
+                continue;
+            }
+
+            let (name, unit_price) = match products.get(product_id) {
+                Some(product) => (
+                    product.name.clone(),
+                    product.price.convert(currency, order.exchange_rate),
+                ),
+                None => ("Unknown product".to_string(), Money::zero(currency)),
+            };
+
+            items.push(OrderItem {
+                product_id: product_id.clone(),
+                variant_id: None,
+                name,
+                quantity: 1,
+                unit_price,
+                discount: Money::zero(currency),
+                total: unit_price,
+            });
+        }
+
+        let lines: u64 = items.iter().map(|item| item.total.amount).sum();
+        let subtotal = Money::new(lines.max(order.total_price.amount), currency);
+        let discount = Money::new(subtotal.amount - order.total_price.amount, currency);
+
+        orders
+            .update_one(
+                doc! { "_id": &order._id },
+                doc! {
+                    "$set": {
+                        "items": to_bson(&items).expect("Failed to serialize order items"),
+                        "bundles": [],
+                        "subtotal": to_bson(&subtotal).expect("Failed to serialize money"),
+                        "discount": to_bson(&discount).expect("Failed to serialize money"),
+                    },
+                    "$unset": { "products_id": "", "bundle_ids": "" },
+                },
+            )
+            .await
+            .expect("Failed to migrate orders");
+    }
+
+    println!("✅ Added line items to {} orders", legacy.len());
+
+    mark_applied(db, NAME).await;
+}
+
+/// Orders placed before the order lifecycle start out pending, with no history
+async fn migrate_order_status(db: &Database) {
+    const NAME: &str = "0007_order_status";
+
+    if is_applied(db, NAME).await {
+        return;
+    }
+
+    let result = db
+        .collection::<mongodb::bson::Document>("orders")
+        .update_many(
+            doc! { "status": { "$exists": false } },
+            doc! { "$set": { "status": "Pending", "history": [] } },
+        )
+        .await
+        .expect("Failed to migrate orders");
+
+    println!("✅ Added status to {} orders", result.modified_count);
+
+    mark_applied(db, NAME).await;
+}
+
+/// Products created before the publishing workflow were already public
+async fn migrate_product_status(db: &Database) {
+    const NAME: &str = "0003_product_status";
+
+    if is_applied(db, NAME).await {
+        return;
+    }
+
+    let result = db
+        .collection::<mongodb::bson::Document>("products")
+        .update_many(
+            doc! { "status": { "$exists": false } },
+            doc! { "$set": { "status": "Published", "deleted_at": null } },
+        )
+        .await
+        .expect("Failed to migrate products");
+
+    println!("✅ Published {} existing products", result.modified_count);
+
+    mark_applied(db, NAME).await;
+}
+
+/// Seeds "bought together" counts from the orders placed so far
+async fn migrate_product_pairs(db: &Database) {
+    const NAME: &str = "0004_product_pairs";
+
+    if is_applied(db, NAME).await {
+        return;
+    }
+
+    let mut orders = Vec::new();
+    let mut cursor = db
+        .collection::<Order>("orders")
+        .find(doc! {})
+        .await
+        .expect("Failed to read orders");
+    while let Some(order) = cursor.try_next().await.expect("Failed to read orders") {
+        orders.push(order.unit_product_ids());
+    }
+    let pairs = count_pairs(orders);
+
+    // Nothing is served yet, so the pairs are simply written in place; a
+    // failed earlier attempt may have left some behind
+    let collection = db.collection::<ProductPair>("product_pairs");
+    collection
+        .delete_many(doc! {})
+        .await
+        .expect("Failed to clear product pairs");
+    if !pairs.is_empty() {
+        collection
+            .insert_many(&pairs)
+            .await
+            .expect("Failed to build product pairs");
+    }
+
+    println!(
+        "✅ Built {} product pairs from existing orders",
+        pairs.len()
+    );
+
+    mark_applied(db, NAME).await;
+}
+
+/// Documents stored before optimistic locking start at version 1
+async fn migrate_document_versions(db: &Database) {
+    const NAME: &str = "0005_document_versions";
+
+    if is_applied(db, NAME).await {
+        return;
+    }
+
+    for collection in ["products", "orders"] {
+        let result = db
+            .collection::<mongodb::bson::Document>(collection)
+            .update_many(
+                doc! { "version": { "$exists": false } },
+                doc! { "$set": { "version": 1 } },
+            )
+            .await
+            .expect("Failed to migrate document versions");
+
+        println!(
+            "✅ Versioned {} documents in {}",
+            result.modified_count, collection
+        );
+    }
+
+    mark_applied(db, NAME).await;
+}
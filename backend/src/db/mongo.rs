@@ -5,6 +5,8 @@ use mongodb::{
 };
 use std::env;
 
+use crate::db::migrations::run_migrations;
+
 pub async fn init_db() -> Database {
     let db_url: String = env::var("DATABASE_URL").unwrap().parse().unwrap();
     let mut client_options = ClientOptions::parse(&db_url)
@@ -27,6 +29,7 @@ pub async fn init_db() -> Database {
     let db = client.database("bike_shop");
 
     ensure_indexes(&db).await;
+    run_migrations(&db).await;
 
     db
 }
@@ -39,4 +42,107 @@ pub async fn ensure_indexes(db: &Database) {
         .options(index_options)
         .build();
     users.create_index(model).await.unwrap();
+
+    let products = db.collection::<mongodb::bson::Document>("products");
+    let index_options = IndexOptions::builder()
+        .unique(true)
+        .partial_filter_expression(doc! { "sku": { "$type": "string" } })
+        .build();
+    let model = IndexModel::builder()
+        .keys(doc! { "sku": 1 })
+        .options(index_options)
+        .build();
+    products.create_index(model).await.unwrap();
+
+    // Catalog search covers the text of every locale, so stemming is off
+    let index_options = IndexOptions::builder()
+        .name("product_text".to_string())
+        .default_language("none".to_string())
+        .build();
+    let model = IndexModel::builder()
+        .keys(doc! {
+            "name": "text",
+            "description": "text",
+            "translations.name": "text",
+            "translations.description": "text",
+        })
+        .options(index_options)
+        .build();
+    products.create_index(model).await.unwrap();
+
+    let campaigns = db.collection::<mongodb::bson::Document>("campaigns");
+    let model = IndexModel::builder()
+        .keys(doc! { "product_id": 1, "starts_at": 1 })
+        .build();
+    campaigns.create_index(model).await.unwrap();
+
+    let price_history = db.collection::<mongodb::bson::Document>("price_history");
+    let model = IndexModel::builder()
+        .keys(doc! { "product_id": 1, "effective_at": 1 })
+        .build();
+    price_history.create_index(model).await.unwrap();
+
+    let exchange_rates = db.collection::<mongodb::bson::Document>("exchange_rates");
+    let model = IndexModel::builder()
+        .keys(doc! { "currency": 1, "created_at": -1 })
+        .build();
+    exchange_rates.create_index(model).await.unwrap();
+
+    let product_pairs = db.collection::<mongodb::bson::Document>("product_pairs");
+    let index_options = IndexOptions::builder().unique(true).build();
+    let model = IndexModel::builder()
+        .keys(doc! { "product_id": 1, "related_id": 1 })
+        .options(index_options)
+        .build();
+    product_pairs.create_index(model).await.unwrap();
+
+    let category_schemas = db.collection::<mongodb::bson::Document>("category_schemas");
+    let index_options = IndexOptions::builder().unique(true).build();
+    let model = IndexModel::builder()
+        .keys(doc! { "category": 1 })
+        .options(index_options)
+        .build();
+    category_schemas.create_index(model).await.unwrap();
+
+    let wishlists = db.collection::<mongodb::bson::Document>("wishlists");
+    let index_options = IndexOptions::builder().unique(true).build();
+    let model = IndexModel::builder()
+        .keys(doc! { "user_id": 1, "product_id": 1 })
+        .options(index_options)
+        .build();
+    wishlists.create_index(model).await.unwrap();
+
+    let product_alerts = db.collection::<mongodb::bson::Document>("product_alerts");
+    let model = IndexModel::builder()
+        .keys(doc! { "product_id": 1, "triggered_at": 1 })
+        .build();
+    product_alerts.create_index(model).await.unwrap();
+
+    let notifications = db.collection::<mongodb::bson::Document>("notifications");
+    let model = IndexModel::builder()
+        .keys(doc! { "user_id": 1, "created_at": -1 })
+        .build();
+    notifications.create_index(model).await.unwrap();
+
+    let coupons = db.collection::<mongodb::bson::Document>("coupons");
+    let index_options = IndexOptions::builder().unique(true).build();
+    let model = IndexModel::builder()
+        .keys(doc! { "code": 1 })
+        .options(index_options)
+        .build();
+    coupons.create_index(model).await.unwrap();
+
+    let coupon_redemptions = db.collection::<mongodb::bson::Document>("coupon_redemptions");
+    let model = IndexModel::builder()
+        .keys(doc! { "coupon_id": 1, "redeemed_at": -1 })
+        .build();
+    coupon_redemptions.create_index(model).await.unwrap();
+
+    let tax_rules = db.collection::<mongodb::bson::Document>("tax_rules");
+    let index_options = IndexOptions::builder().unique(true).build();
+    let model = IndexModel::builder()
+        .keys(doc! { "country": 1, "region": 1, "category": 1 })
+        .options(index_options)
+        .build();
+    tax_rules.create_index(model).await.unwrap();
 }
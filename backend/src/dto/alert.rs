@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::models::alert::AlertCondition;
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema)]
+#[ts(export, export_to = "../../db_types/CreateAlertDto.d.ts")]
+pub struct CreateAlertDto {
+    pub product_id: String,
+    /// `PriceBelow` thresholds may be in any supported currency
+    pub condition: AlertCondition,
+}
@@ -0,0 +1,201 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    errors::AppErrors,
+    models::attribute::{AttributeDefinition, AttributeKind, AttributeValue},
+};
+
+/// Prefix of query parameters that filter by attribute, e.g. `attr.wheel_size=29`
+pub const ATTRIBUTE_FILTER_PREFIX: &str = "attr.";
+
+/// Attribute keys end up in MongoDB paths, so only plain identifiers are allowed
+pub fn is_valid_attribute_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= 64
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/SetCategorySchemaDto.d.ts")]
+#[validate(schema(function = "validate_definitions"))]
+pub struct SetCategorySchemaDto {
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+fn validate_definitions(dto: &SetCategorySchemaDto) -> Result<(), ValidationError> {
+    let fail = |message: String| {
+        let mut err = ValidationError::new("attribute_schema");
+        err.message = Some(message.into());
+        Err(err)
+    };
+
+    let mut keys = HashSet::new();
+    for definition in &dto.attributes {
+        if !is_valid_attribute_key(&definition.key) {
+            return fail(format!(
+                "Attribute key {:?} may only contain lowercase letters, digits and underscores.",
+                definition.key
+            ));
+        }
+        if !keys.insert(definition.key.as_str()) {
+            return fail(format!("Attribute {} is defined twice.", definition.key));
+        }
+
+        let has_options = !definition.options.is_empty();
+        if (definition.kind == AttributeKind::Enum) != has_options {
+            return fail(format!(
+                "Attribute {} must list options if and only if it is an Enum.",
+                definition.key
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/FacetValue.d.ts")]
+pub struct FacetValue {
+    pub value: AttributeValue,
+    /// Matching products with this value
+    pub count: u32,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/Facet.d.ts")]
+pub struct Facet {
+    pub key: String,
+    /// Falls back to the key when the category has no schema
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub unit: Option<String>,
+    pub values: Vec<FacetValue>,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/FacetsResponse.d.ts")]
+pub struct FacetsResponse {
+    /// Products matching the filter
+    pub total: u64,
+    pub facets: Vec<Facet>,
+}
+
+/// Catalog filter taken from the query string
+#[derive(Debug, Default, PartialEq)]
+pub struct ProductFilter {
+    pub category: Option<u8>,
+    /// Words matched against the name and description in every locale
+    pub search: Option<String>,
+    /// Attribute key and the raw values any of which may match
+    pub attributes: Vec<(String, Vec<String>)>,
+}
+
+impl ProductFilter {
+    /// Reads `category`, `q` and every `attr.<key>=<value>[,<value>...]`
+    /// parameter, ignoring anything else
+    pub fn from_query(query: &HashMap<String, String>) -> Result<Self, AppErrors> {
+        let category = query
+            .get("category")
+            .map(|raw| {
+                raw.parse::<u8>()
+                    .map_err(|_| AppErrors::BadRequest(format!("Invalid category: {}", raw)))
+            })
+            .transpose()?;
+
+        let search = query
+            .get("q")
+            .map(|raw| raw.trim())
+            .filter(|raw| !raw.is_empty())
+            .map(str::to_string);
+
+        let mut attributes = Vec::new();
+        for (name, raw) in query {
+            let Some(key) = name.strip_prefix(ATTRIBUTE_FILTER_PREFIX) else {
+                continue;
+            };
+
+            if !is_valid_attribute_key(key) {
+                return Err(AppErrors::BadRequest(format!(
+                    "Invalid attribute filter: {}",
+                    name
+                )));
+            }
+
+            let values = raw
+                .split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .collect();
+
+            attributes.push((key.to_string(), values));
+        }
+        attributes.sort();
+
+        Ok(ProductFilter {
+            category,
+            search,
+            attributes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_reads_category_and_attribute_filters() {
+        let filter = ProductFilter::from_query(&query(&[
+            ("category", "1"),
+            ("currency", "EUR"),
+            ("q", " велосипед "),
+            ("attr.frame_material", "steel, aluminium"),
+            ("attr.wheel_size", "29"),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            filter,
+            ProductFilter {
+                category: Some(1),
+                search: Some("велосипед".to_string()),
+                attributes: vec![
+                    (
+                        "frame_material".to_string(),
+                        vec!["steel".to_string(), "aluminium".to_string()]
+                    ),
+                    ("wheel_size".to_string(), vec!["29".to_string()]),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_rejects_keys_that_could_reach_into_other_fields() {
+        assert!(ProductFilter::from_query(&query(&[("attr.$where", "1")])).is_err());
+        assert!(ProductFilter::from_query(&query(&[("attr.a.b", "1")])).is_err());
+        assert!(ProductFilter::from_query(&query(&[("category", "bike")])).is_err());
+    }
+
+    #[test]
+    fn test_ignores_blank_search() {
+        let filter = ProductFilter::from_query(&query(&[("q", "  ")])).unwrap();
+        assert_eq!(filter.search, None);
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+use crate::models::{
+    bundle::{Bundle, BundleItem, BundlePricing},
+    locale::{Locale, Localize},
+    money::Money,
+    product::{Product, ProductStatus},
+};
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/BundleDto.d.ts")]
+#[validate(schema(function = "validate_bundle"))]
+pub struct BundleDto {
+    #[validate(length(min = 2, message = "The number of letters must be at least 2."))]
+    #[schema(example = "Commuter kit")]
+    pub name: String,
+    #[validate(length(min = 2, message = "The number of letters must be at least 2."))]
+    pub description: String,
+    #[serde(default)]
+    pub images: Vec<String>,
+    pub items: Vec<BundleItem>,
+    pub pricing: BundlePricing,
+    /// Defaults to Draft
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub status: Option<ProductStatus>,
+}
+
+fn validate_bundle(dto: &BundleDto) -> Result<(), ValidationError> {
+    let fail = |message: &str| {
+        let mut err = ValidationError::new("bundle");
+        err.message = Some(message.to_string().into());
+        Err(err)
+    };
+
+    if dto.items.len() < 2 {
+        return fail("A bundle needs at least two products.");
+    }
+
+    let mut seen = HashSet::new();
+    for item in &dto.items {
+        if item.quantity == 0 || item.quantity > 100 {
+            return fail("Quantities must be between 1 and 100.");
+        }
+        if !seen.insert(item.product_id.as_str()) {
+            return fail("Every product may appear once; use the quantity instead.");
+        }
+    }
+
+    if let BundlePricing::Discount { percent } = dto.pricing {
+        if percent == 0 || percent > 100 {
+            return fail("The bundle discount must be between 1 and 100.");
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/BundleComponent.d.ts")]
+pub struct BundleComponent {
+    pub product: Product,
+    pub quantity: u32,
+}
+
+/// Bundle with its components and the prices a customer would see
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/BundleView.d.ts")]
+pub struct BundleView {
+    pub bundle: Bundle,
+    pub components: Vec<BundleComponent>,
+    /// What the components cost when bought separately
+    pub components_price: Money,
+    pub price: Money,
+    pub savings: Money,
+    /// False when a component is unpublished or sold out
+    pub available: bool,
+    /// Kits that can be put together from component stock; `null` when no
+    /// component tracks stock
+    #[ts(type = "number | null")]
+    pub stock: Option<u32>,
+}
+
+impl Localize for BundleView {
+    fn localize(&mut self, locale: Locale) {
+        for component in self.components.iter_mut() {
+            component.product.localize(locale);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/CreateCampaignDto.d.ts")]
+#[validate(schema(function = "validate_campaign_window"))]
+pub struct CreateCampaignDto {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    pub product_id: String,
+    #[validate(range(
+        min = 0,
+        max = 100,
+        message = "The discount must be between 0 and 100."
+    ))]
+    #[schema(example = 15, minimum = 0, maximum = 100)]
+    pub discount: u8,
+    /// Unix timestamp (seconds); must not be in the past
+    #[ts(type = "number")]
+    #[schema(example = 1767225600)]
+    pub starts_at: i64,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    #[schema(example = 1767830400)]
+    pub ends_at: i64,
+}
+
+/// Campaigns cannot start in the past, or the price history would show
+/// discounts no customer got
+fn validate_campaign_window(dto: &CreateCampaignDto) -> Result<(), ValidationError> {
+    if dto.starts_at < Utc::now().timestamp() {
+        let mut err = ValidationError::new("campaign_window");
+        err.message = Some("The campaign cannot start in the past.".into());
+        return Err(err);
+    }
+
+    if dto.ends_at <= dto.starts_at {
+        let mut err = ValidationError::new("campaign_window");
+        err.message = Some("The campaign must end after it starts.".into());
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campaign(starts_at: i64, ends_at: i64) -> CreateCampaignDto {
+        CreateCampaignDto {
+            product_id: String::new(),
+            discount: 10,
+            starts_at,
+            ends_at,
+        }
+    }
+
+    #[test]
+    fn test_campaign_window_starts_now_or_later_and_ends_after_it_starts() {
+        let now = Utc::now().timestamp();
+
+        assert!(campaign(now + 60, now + 3_600).validate().is_ok());
+        assert!(campaign(now - 60, now + 3_600).validate().is_err());
+        assert!(campaign(now + 3_600, now + 60).validate().is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    dto::{order::PriceBreakdown, tax::validate_country},
+    models::{
+        locale::{Locale, Localize},
+        product::Product,
+    },
+};
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/UpdateCartQuantityDto.d.ts")]
+pub struct UpdateCartQuantityDto {
+    /// 0 removes the line
+    #[validate(range(max = 100, message = "The quantity must be between 0 and 100."))]
+    #[schema(example = 2, minimum = 0, maximum = 100)]
+    pub quantity: u32,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct CheckoutQuery {
+    /// Promo code to apply to the order
+    pub coupon: Option<String>,
+    /// Where the order ships to, for taxes
+    #[validate(custom(function = "validate_country"))]
+    pub country: Option<String>,
+    #[validate(length(
+        min = 1,
+        max = 10,
+        message = "The region must be 1 to 10 characters long."
+    ))]
+    pub region: Option<String>,
+}
+
+/// The cart at current catalog prices
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/CartView.d.ts")]
+pub struct CartView {
+    /// The lines that can be ordered, priced as checkout would price them
+    pub pricing: PriceBreakdown,
+    /// Products of the priced lines
+    pub products: Vec<Product>,
+    /// Products and bundles in the cart that cannot be ordered right now,
+    /// or not in the quantity asked for
+    pub unavailable: Vec<String>,
+}
+
+impl Localize for CartView {
+    fn localize(&mut self, locale: Locale) {
+        self.products.localize(locale);
+
+        for item in self.pricing.items.iter_mut() {
+            if let Some(product) = self
+                .products
+                .iter()
+                .find(|p| p._id.to_string() == item.product_id)
+            {
+                item.name = product.name.clone();
+            }
+        }
+    }
+}
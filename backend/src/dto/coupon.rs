@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+use crate::models::{coupon::CouponDiscount, money::Money};
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/CreateCouponDto.d.ts")]
+#[validate(schema(function = "validate_coupon"))]
+pub struct CreateCouponDto {
+    /// Letters, digits, `-` and `_`; stored in upper case
+    #[validate(length(
+        min = 3,
+        max = 32,
+        message = "The code must be 3 to 32 characters long."
+    ))]
+    #[schema(example = "SPRING10")]
+    pub code: String,
+    pub discount: CouponDiscount,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub min_order_value: Option<Money>,
+    /// Empty applies the coupon to every product and bundle
+    #[serde(default)]
+    pub categories: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[validate(range(min = 1, message = "The usage limit must be positive."))]
+    pub max_uses: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[validate(range(min = 1, message = "The usage limit must be positive."))]
+    pub max_uses_per_user: Option<u32>,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    #[schema(example = 1767225600)]
+    pub starts_at: i64,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    #[schema(example = 1767830400)]
+    pub ends_at: i64,
+}
+
+fn validate_coupon(dto: &CreateCouponDto) -> Result<(), ValidationError> {
+    let fail = |code: &'static str, message: &'static str| {
+        let mut err = ValidationError::new(code);
+        err.message = Some(message.into());
+        Err(err)
+    };
+
+    if !dto
+        .code
+        .trim()
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return fail(
+            "coupon_code",
+            "The code may only contain letters, digits, - and _.",
+        );
+    }
+
+    match dto.discount {
+        CouponDiscount::Percentage { percent } if !(1..=100).contains(&percent) => {
+            return fail(
+                "coupon_discount",
+                "The discount must be between 1 and 100 percent.",
+            );
+        }
+        CouponDiscount::Fixed { amount } if amount.amount == 0 => {
+            return fail("coupon_discount", "The discount must be positive.");
+        }
+        _ => {}
+    }
+
+    if dto.categories.iter().any(|category| *category > 1) {
+        return fail("coupon_categories", "The category must be between 0 and 1.");
+    }
+
+    if dto.ends_at <= dto.starts_at {
+        return fail("coupon_window", "The coupon must end after it starts.");
+    }
+
+    Ok(())
+}
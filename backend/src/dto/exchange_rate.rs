@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::models::money::Currency;
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/SetExchangeRateDto.d.ts")]
+pub struct SetExchangeRateDto {
+    pub currency: Currency,
+    /// Units of `currency` for one unit of the base currency
+    #[validate(range(exclusive_min = 0.0, message = "The rate must be positive."))]
+    #[schema(example = 41.25)]
+    pub rate: f64,
+}
+
+#[derive(Deserialize)]
+pub struct RateHistoryQuery {
+    pub currency: Option<Currency>,
+}
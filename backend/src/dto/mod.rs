@@ -1,4 +1,14 @@
+pub mod alert;
+pub mod attribute;
 pub mod auth;
+pub mod bundle;
+pub mod campaign;
+pub mod cart;
+pub mod coupon;
+pub mod exchange_rate;
 pub mod order;
+pub mod payment;
 pub mod product;
+pub mod tax;
 pub mod user;
+pub mod wishlist;
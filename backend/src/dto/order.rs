@@ -1,24 +1,247 @@
+use chrono::DateTime;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use utoipa::ToSchema;
+use validator::Validate;
 
-#[derive(TS, Serialize, Deserialize, Clone, ToSchema)]
-#[ts(export, export_to = "../../db_types/CreateOrderDto.d.ts")]
-pub struct CreateOrderDto {
-    /// products id
-    pub products_id: Vec<String>,
-    pub total_price: u32,
+use crate::{
+    dto::tax::validate_country,
+    models::{
+        coupon::AppliedCoupon,
+        money::{Currency, Money},
+        order::{Order, OrderBundle, OrderItem, OrderRefund, OrderStatus},
+        tax::{TaxLine, TaxMode},
+    },
+};
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/OrderItemDto.d.ts")]
+pub struct OrderItemDto {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    pub product_id: String,
+    /// For products sold in variants
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub variant_id: Option<String>,
+    #[validate(range(
+        min = 1,
+        max = 100,
+        message = "The quantity must be between 1 and 100."
+    ))]
+    #[schema(example = 1, minimum = 1, maximum = 100)]
+    pub quantity: u32,
 }
 
-#[derive(TS, Serialize, Deserialize, Clone, ToSchema)]
-#[ts(export, export_to = "../../db_types/UpdateOrderDto.d.ts")]
-pub struct UpdateOrderDto {
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/OrderBundleDto.d.ts")]
+pub struct OrderBundleDto {
     #[ts(type = "string")]
     #[schema(value_type = String)]
-    pub _id: String,
-    /// products id
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub products_id: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub total_price: Option<u32>,
+    pub bundle_id: String,
+    #[validate(range(
+        min = 1,
+        max = 100,
+        message = "The quantity must be between 1 and 100."
+    ))]
+    #[schema(example = 1, minimum = 1, maximum = 100)]
+    pub quantity: u32,
+}
+
+/// What the customer orders. Prices are always taken from the catalog, so
+/// a `total_price` sent by older clients is ignored.
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/CreateOrderDto.d.ts")]
+pub struct CreateOrderDto {
+    /// Lines naming the same product and variant are merged
+    #[serde(default)]
+    #[validate(nested)]
+    pub items: Vec<OrderItemDto>,
+    /// Bundles to order; each adds its components to the order
+    #[serde(default)]
+    #[validate(nested)]
+    pub bundles: Vec<OrderBundleDto>,
+    /// Promo code to apply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[schema(example = "SPRING10")]
+    pub coupon_code: Option<String>,
+    /// ISO 3166-1 alpha-2 code of the country the order ships to; taxes
+    /// follow the shop's country when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[validate(custom(function = "validate_country"))]
+    #[schema(example = "US")]
+    pub country: Option<String>,
+    /// State or province code, for regional tax rates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[validate(length(
+        min = 1,
+        max = 10,
+        message = "The region must be 1 to 10 characters long."
+    ))]
+    #[schema(example = "CA")]
+    pub region: Option<String>,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/ChangeOrderStatusDto.d.ts")]
+pub struct ChangeOrderStatusDto {
+    pub status: OrderStatus,
+    /// Kept in the status history, e.g. a tracking number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[validate(length(max = 500, message = "The note must be at most 500 characters."))]
+    pub note: Option<String>,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/RefundOrderDto.d.ts")]
+pub struct RefundOrderDto {
+    /// Minor units of the order currency; everything not refunded yet when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, type = "number")]
+    #[validate(range(min = 1, message = "The refund must be positive."))]
+    #[schema(example = 2500, minimum = 1)]
+    pub amount: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[validate(length(max = 500, message = "The reason must be at most 500 characters."))]
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RefundLedgerQuery {
+    /// Unix timestamp (seconds), inclusive
+    pub from: Option<i64>,
+    /// Unix timestamp (seconds), exclusive
+    pub to: Option<i64>,
+}
+
+/// One refund in the ledger, with the order it belongs to
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/RefundLedgerEntry.d.ts")]
+pub struct RefundLedgerEntry {
+    pub order_id: String,
+    pub customer_id: String,
+    #[serde(flatten)]
+    pub refund: OrderRefund,
+}
+
+/// Flat CSV representation of a ledger entry
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RefundCsvRow {
+    pub refund_id: String,
+    pub order_id: String,
+    pub customer_id: String,
+    /// RFC 3339, UTC
+    pub refunded_at: String,
+    /// Minor units
+    pub amount: u64,
+    pub currency: Currency,
+    pub reason: Option<String>,
+    pub refunded_by: String,
+}
+
+impl From<&RefundLedgerEntry> for RefundCsvRow {
+    fn from(entry: &RefundLedgerEntry) -> Self {
+        RefundCsvRow {
+            refund_id: entry.refund.refund_id.clone(),
+            order_id: entry.order_id.clone(),
+            customer_id: entry.customer_id.clone(),
+            refunded_at: DateTime::from_timestamp(entry.refund.refunded_at, 0)
+                .map(|date| date.to_rfc3339())
+                .unwrap_or_default(),
+            amount: entry.refund.amount.amount,
+            currency: entry.refund.amount.currency,
+            reason: entry.refund.reason.clone(),
+            refunded_by: entry.refund.refunded_by.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct OrderExportQuery {
+    /// Unix timestamp (seconds), inclusive
+    pub from: Option<i64>,
+    /// Unix timestamp (seconds), exclusive
+    pub to: Option<i64>,
+}
+
+/// Flat CSV representation of an order; amounts in minor units of the
+/// order currency
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OrderCsvRow {
+    pub order_id: String,
+    pub customer_id: String,
+    /// RFC 3339, UTC
+    pub placed_at: String,
+    pub status: OrderStatus,
+    pub currency: Currency,
+    pub subtotal: u64,
+    pub discount: u64,
+    pub coupon: Option<String>,
+    pub coupon_discount: Option<u64>,
+    pub tax_mode: TaxMode,
+    /// Empty for orders placed before taxes were calculated
+    pub tax: Option<u64>,
+    pub total: u64,
+    pub refunded: u64,
+}
+
+impl From<&Order> for OrderCsvRow {
+    fn from(order: &Order) -> Self {
+        OrderCsvRow {
+            order_id: order._id.to_string(),
+            customer_id: order.customer_id.clone(),
+            placed_at: order
+                .placed_at()
+                .and_then(|placed_at| DateTime::from_timestamp(placed_at, 0))
+                .map(|date| date.to_rfc3339())
+                .unwrap_or_default(),
+            status: order.status,
+            currency: order.total_price.currency,
+            subtotal: order.subtotal.amount,
+            discount: order.discount.amount,
+            coupon: order.coupon.as_ref().map(|coupon| coupon.code.clone()),
+            coupon_discount: order.coupon.as_ref().map(|coupon| coupon.discount.amount),
+            tax_mode: order.tax_mode,
+            tax: order.tax.map(|tax| tax.amount),
+            total: order.total_price.amount,
+            refunded: order.refunded().amount,
+        }
+    }
+}
+
+/// Order lines priced in the order currency
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/PriceBreakdown.d.ts")]
+pub struct PriceBreakdown {
+    pub items: Vec<OrderItem>,
+    pub bundles: Vec<OrderBundle>,
+    /// Sum of the lines at regular prices
+    pub subtotal: Money,
+    /// Product discounts, campaigns and bundle savings together
+    pub discount: Money,
+    /// Coupon taken off after the line discounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub coupon: Option<AppliedCoupon>,
+    /// Whether the lines above already include the tax
+    pub tax_mode: TaxMode,
+    /// One line per tax rule that applied
+    pub taxes: Vec<TaxLine>,
+    /// Sum of the tax lines
+    pub tax: Money,
+    /// What the customer pays
+    pub total: Money,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/CreateOrderResponse.d.ts")]
+pub struct CreateOrderResponse {
+    pub message: String,
+    pub order_id: String,
+    pub pricing: PriceBreakdown,
 }
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::models::{money::Money, payment::PaymentStatus};
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/PaymentIntentResponse.d.ts")]
+pub struct PaymentIntentResponse {
+    pub payment_id: String,
+    pub intent_id: String,
+    /// Completes the payment with the provider on the client
+    pub client_secret: String,
+    pub amount: Money,
+    pub status: PaymentStatus,
+}
+
+/// What the provider posts to the webhook when a payment changes
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/PaymentWebhookEvent.d.ts")]
+pub struct PaymentWebhookEvent {
+    /// Unique per event; redelivered events repeat it
+    pub id: String,
+    pub intent_id: String,
+    pub status: PaymentStatus,
+}
+
+/// Outcome of checking unsettled payments with the provider
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, Default, PartialEq)]
+#[ts(export, export_to = "../../db_types/ReconcileReport.d.ts")]
+pub struct ReconcileReport {
+    pub checked: u32,
+    pub succeeded: u32,
+    pub failed: u32,
+    /// Still waiting for the customer
+    pub pending: u32,
+}
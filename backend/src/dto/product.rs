@@ -1,14 +1,37 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use utoipa::ToSchema;
+use uuid::Uuid;
 use validator::Validate;
 
+use crate::{
+    errors::AppErrors,
+    models::{
+        attribute::AttributeValue,
+        locale::{Locale, Localize},
+        money::{Currency, Money},
+        product::{Product, ProductStatus},
+    },
+};
+
 #[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
 #[ts(export, export_to = "../../db_types/CreateProductDto.d.ts")]
 pub struct CreateProductDto {
+    /// Stock keeping unit, unique across the catalog
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "The SKU must be between 1 and 64 characters."
+    ))]
+    #[ts(optional)]
+    #[schema(example = "BIKE-MTB-001")]
+    pub sku: Option<String>,
     #[validate(length(min = 2, message = "The number of letters must be at least 2."))]
     pub name: String,
-    pub price: u32,
+    pub price: Money,
     #[validate(length(min = 2, message = "The number of letters must be at least 2."))]
     pub description: String,
     pub images: Vec<String>,
@@ -24,6 +47,17 @@ pub struct CreateProductDto {
     #[validate(range(min = 0, max = 1, message = "The category must be between 0 and 1."))]
     #[schema(example = 0, minimum = 0, maximum = 1)]
     pub category: u8,
+    /// Technical specifications, checked against the category schema
+    #[serde(default)]
+    pub attributes: BTreeMap<String, AttributeValue>,
+    /// Units on hand; leave out to not track stock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub stock: Option<u32>,
+    /// Defaults to Draft
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub status: Option<ProductStatus>,
 }
 
 #[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
@@ -33,10 +67,18 @@ pub struct UpdateProductDto {
     #[schema(value_type = String)]
     pub _id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "The SKU must be between 1 and 64 characters."
+    ))]
+    #[schema(example = "BIKE-MTB-001")]
+    pub sku: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[validate(length(min = 2, message = "The number of letters must be at least 2."))]
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub price: Option<u32>,
+    pub price: Option<Money>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[validate(length(min = 2, message = "The number of letters must be at least 2."))]
     pub description: Option<String>,
@@ -56,4 +98,360 @@ pub struct UpdateProductDto {
     #[validate(range(min = 0, max = 1, message = "The category must be between 0 and 1."))]
     #[schema(example = 0, minimum = 0, maximum = 1)]
     pub category: Option<u8>,
+    /// Replaces all attributes of the product
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<BTreeMap<String, AttributeValue>>,
+    /// Units on hand
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stock: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkFormat {
+    Csv,
+    Jsonl,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Every row creates a new product, rows with a known SKU fail
+    #[default]
+    Insert,
+    /// Rows update the product with the same SKU or create it
+    Upsert,
+}
+
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    pub format: Option<BulkFormat>,
+    #[serde(default)]
+    pub mode: ImportMode,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<BulkFormat>,
+}
+
+/// Flat CSV representation of a product
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProductCsvRow {
+    pub sku: Option<String>,
+    pub name: String,
+    /// Minor units
+    pub price_amount: u64,
+    pub price_currency: Currency,
+    pub description: String,
+    /// Image URLs separated by `|`
+    pub images: String,
+    pub discount: u8,
+    pub category: u8,
+    /// JSON object, empty when the product has no attributes
+    #[serde(default)]
+    pub attributes: String,
+    /// Empty when stock is not tracked
+    #[serde(default)]
+    pub stock: Option<u32>,
+    #[serde(default)]
+    pub status: Option<ProductStatus>,
+}
+
+impl TryFrom<ProductCsvRow> for CreateProductDto {
+    type Error = String;
+
+    fn try_from(row: ProductCsvRow) -> Result<Self, Self::Error> {
+        let attributes = if row.attributes.is_empty() {
+            BTreeMap::new()
+        } else {
+            serde_json::from_str(&row.attributes)
+                .map_err(|e| format!("Invalid attributes: {}", e))?
+        };
+
+        Ok(CreateProductDto {
+            sku: row.sku,
+            name: row.name,
+            price: Money::new(row.price_amount, row.price_currency),
+            description: row.description,
+            images: row
+                .images
+                .split('|')
+                .map(str::trim)
+                .filter(|image| !image.is_empty())
+                .map(str::to_string)
+                .collect(),
+            discount: row.discount,
+            category: row.category,
+            attributes,
+            stock: row.stock,
+            status: row.status,
+        })
+    }
+}
+
+impl From<&Product> for ProductCsvRow {
+    fn from(product: &Product) -> Self {
+        ProductCsvRow {
+            sku: product.sku.clone(),
+            name: product.name.clone(),
+            price_amount: product.price.amount,
+            price_currency: product.price.currency,
+            description: product.description.clone(),
+            images: product.images.join("|"),
+            discount: product.discount,
+            category: product.category,
+            attributes: if product.attributes.is_empty() {
+                String::new()
+            } else {
+                serde_json::to_string(&product.attributes).unwrap_or_default()
+            },
+            stock: product.stock,
+            status: Some(product.status),
+        }
+    }
+}
+
+#[derive(TS, Serialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/ImportRowError.d.ts")]
+pub struct ImportRowError {
+    /// 1-based line number in the uploaded file
+    pub row: usize,
+    pub sku: Option<String>,
+    pub message: String,
+}
+
+#[derive(TS, Serialize, Clone, ToSchema, Debug, Default)]
+#[ts(export, export_to = "../../db_types/ImportReport.d.ts")]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub total: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub failed: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema)]
+#[ts(export, export_to = "../../db_types/ChangeProductStatusDto.d.ts")]
+pub struct ChangeProductStatusDto {
+    pub status: ProductStatus,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema)]
+#[ts(export, export_to = "../../db_types/SetStockDto.d.ts")]
+pub struct SetStockDto {
+    /// Units on hand; `null` stops tracking stock
+    #[ts(type = "number | null")]
+    pub stock: Option<u32>,
+}
+
+/// Name and description of a product in a locale other than the default one
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/TranslationDto.d.ts")]
+pub struct TranslationDto {
+    #[validate(length(min = 2, message = "The number of letters must be at least 2."))]
+    #[schema(example = "Mountain bike")]
+    pub name: String,
+    #[validate(length(min = 2, message = "The number of letters must be at least 2."))]
+    #[schema(example = "Hardtail with an aluminium frame")]
+    pub description: String,
+}
+
+#[derive(Deserialize)]
+pub struct AdminProductsQuery {
+    pub status: Option<ProductStatus>,
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DealRanking {
+    /// Largest amount taken off the price
+    #[default]
+    Savings,
+    /// Largest discount percentage
+    Percent,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct DealsQuery {
+    #[validate(range(min = 1, max = 50, message = "The limit must be between 1 and 50."))]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub rank_by: DealRanking,
+    pub category: Option<u8>,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/Deal.d.ts")]
+pub struct Deal {
+    pub product: Product,
+    /// Amount taken off the regular price
+    pub savings: Money,
+    /// Price after the discount
+    pub final_price: Money,
+}
+
+impl From<Product> for Deal {
+    fn from(product: Product) -> Self {
+        Deal {
+            savings: product.price.discount_amount(product.discount),
+            final_price: product.price.apply_discount(product.discount),
+            product,
+        }
+    }
+}
+
+impl Localize for Deal {
+    fn localize(&mut self, locale: Locale) {
+        self.product.localize(locale);
+    }
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/CategoryDeals.d.ts")]
+pub struct CategoryDeals {
+    pub category: u8,
+    pub deals: Vec<Deal>,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/DealsResponse.d.ts")]
+pub struct DealsResponse {
+    pub overall: Vec<Deal>,
+    /// Categories in ascending order
+    pub by_category: Vec<CategoryDeals>,
+}
+
+impl Localize for DealsResponse {
+    fn localize(&mut self, locale: Locale) {
+        self.overall.localize(locale);
+        for category in self.by_category.iter_mut() {
+            category.deals.localize(locale);
+        }
+    }
+}
+
+/// Most products shown side by side
+pub const MAX_COMPARED_PRODUCTS: usize = 4;
+
+#[derive(Deserialize)]
+pub struct CompareQuery {
+    /// Comma-separated product ids
+    pub ids: String,
+}
+
+impl CompareQuery {
+    /// Distinct ids in the order given
+    pub fn product_ids(&self) -> Result<Vec<Uuid>, AppErrors> {
+        let mut ids: Vec<Uuid> = Vec::new();
+
+        for raw in self
+            .ids
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+        {
+            let id = Uuid::parse_str(raw).map_err(|_| AppErrors::InvalidUUID)?;
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+
+        if !(2..=MAX_COMPARED_PRODUCTS).contains(&ids.len()) {
+            return Err(AppErrors::BadRequest(format!(
+                "Compare between 2 and {} different products",
+                MAX_COMPARED_PRODUCTS
+            )));
+        }
+
+        Ok(ids)
+    }
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/ComparedAttribute.d.ts")]
+pub struct ComparedAttribute {
+    pub key: String,
+    /// Falls back to the key when the category has no schema
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub unit: Option<String>,
+    /// One value per compared product, `null` where the product lacks it
+    pub values: Vec<Option<AttributeValue>>,
+    /// Whether the products disagree on this attribute
+    pub differs: bool,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/ProductComparison.d.ts")]
+pub struct ProductComparison {
+    pub category: u8,
+    /// Compared products in the requested order, with their effective price
+    pub products: Vec<Deal>,
+    /// Schema attributes first, in schema order, then any others by key
+    pub attributes: Vec<ComparedAttribute>,
+}
+
+impl Localize for ProductComparison {
+    fn localize(&mut self, locale: Locale) {
+        self.products.localize(locale);
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RelatedQuery {
+    #[validate(range(min = 1, max = 20, message = "The limit must be between 1 and 20."))]
+    pub limit: Option<usize>,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/ProductAnalytics.d.ts")]
+pub struct ProductAnalytics {
+    pub product_id: String,
+    pub name: String,
+    pub status: ProductStatus,
+    pub deleted: bool,
+    /// Customers who have the product on their wishlist
+    pub wishlist_count: u32,
+    /// Orders containing the product
+    pub order_count: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(ids: &str) -> CompareQuery {
+        CompareQuery {
+            ids: ids.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compare_ids_are_distinct_and_bounded() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+
+        assert_eq!(
+            query(&format!("{}, {},{}", a, b, a)).product_ids().unwrap(),
+            vec![a, b]
+        );
+        assert!(matches!(
+            query(&format!("{},{}", a, a)).product_ids(),
+            Err(AppErrors::BadRequest(_))
+        ));
+        assert!(matches!(
+            query(&format!("{},bike", a)).product_ids(),
+            Err(AppErrors::InvalidUUID)
+        ));
+
+        let five: Vec<String> = (0..5).map(|_| Uuid::new_v4().to_string()).collect();
+        assert!(query(&five.join(",")).product_ids().is_err());
+    }
 }
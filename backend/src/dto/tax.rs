@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+/// Creates the rule for this country, region and category, or replaces its
+/// name and rate
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[ts(export, export_to = "../../db_types/SetTaxRuleDto.d.ts")]
+pub struct SetTaxRuleDto {
+    /// ISO 3166-1 alpha-2 code
+    #[validate(custom(function = "validate_country"))]
+    #[schema(example = "US")]
+    pub country: String,
+    /// State or province code; omit for the whole country
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[validate(length(
+        min = 1,
+        max = 10,
+        message = "The region must be 1 to 10 characters long."
+    ))]
+    #[schema(example = "CA")]
+    pub region: Option<String>,
+    /// Omit for every category and bundles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[validate(range(max = 1, message = "The category must be between 0 and 1."))]
+    pub category: Option<u8>,
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "The name must be 1 to 50 characters long."
+    ))]
+    #[schema(example = "Sales tax")]
+    pub name: String,
+    /// Basis points, 725 is 7.25%
+    #[validate(range(max = 10000, message = "The rate must be at most 100%."))]
+    #[schema(example = 725, maximum = 10000)]
+    pub rate: u32,
+}
+
+#[derive(Deserialize)]
+pub struct TaxRulesQuery {
+    pub country: Option<String>,
+}
+
+pub fn validate_country(country: &str) -> Result<(), ValidationError> {
+    if country.len() == 2 && country.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Ok(());
+    }
+
+    let mut err = ValidationError::new("country");
+    err.message = Some("The country must be a two-letter ISO code.".into());
+    Err(err)
+}
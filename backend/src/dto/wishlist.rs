@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::models::{
+    locale::{Locale, Localize},
+    product::Product,
+};
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/WishlistEntry.d.ts")]
+pub struct WishlistEntry {
+    pub product_id: String,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub added_at: i64,
+    /// Current product data; missing if the product was removed for good
+    pub product: Option<Product>,
+    /// False once the product is archived, unpublished or deleted
+    pub available: bool,
+}
+
+impl WishlistEntry {
+    pub fn new(product_id: String, added_at: i64, product: Option<Product>) -> Self {
+        WishlistEntry {
+            available: product.as_ref().is_some_and(Product::is_available),
+            product_id,
+            added_at,
+            product,
+        }
+    }
+}
+
+impl Localize for WishlistEntry {
+    fn localize(&mut self, locale: Locale) {
+        self.product.localize(locale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::product::{ProductBuilder, ProductStatus};
+
+    #[test]
+    fn test_only_published_products_are_available() {
+        let entry = |product| WishlistEntry::new("id".to_string(), 0, product);
+
+        assert!(entry(Some(ProductBuilder::new().build())).available);
+        assert!(
+            !entry(Some(
+                ProductBuilder::new()
+                    .status(ProductStatus::Archived)
+                    .build()
+            ))
+            .available
+        );
+        assert!(!entry(Some(ProductBuilder::new().deleted_at(Some(1)).build())).available);
+        assert!(!entry(None).available);
+    }
+}
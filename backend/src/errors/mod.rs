@@ -16,6 +16,12 @@ pub enum AppErrors {
     #[error("BSON serialization error: {0}")]
     Bson(#[from] bson::ser::Error),
 
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("Redis error")]
+    Redis(#[from] redis::RedisError),
+
     #[error(transparent)]
     Jwt(#[from] jwt_error::JWTError),
 
@@ -30,6 +36,12 @@ pub enum AppErrors {
 
     #[error("{0} not found")]
     NotFound(String),
+
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("{0}")]
+    Conflict(String),
 }
 
 #[derive(TS, Serialize, Clone, ToSchema)]
@@ -114,6 +126,24 @@ impl ResponseError for AppErrors {
                 eprintln!("MongoDB error: {:?}", e);
 
                 match &*e.kind {
+                    ErrorKind::Write(WriteFailure::WriteError(write_err)) if write_err.code == 11000 && write_err.message.contains("coupons") => (
+                        StatusCode::CONFLICT,
+                        "duplicate_key",
+                        "Coupon code already exists".to_string(),
+                        None,
+                    ),
+                    ErrorKind::Write(WriteFailure::WriteError(write_err)) if write_err.code == 11000 && write_err.message.contains("tax_rules") => (
+                        StatusCode::CONFLICT,
+                        "duplicate_key",
+                        "The tax rule was just saved by someone else".to_string(),
+                        None,
+                    ),
+                    ErrorKind::Write(WriteFailure::WriteError(write_err)) if write_err.code == 11000 && write_err.message.contains("sku") => (
+                        StatusCode::CONFLICT,
+                        "duplicate_key",
+                        "SKU already exists".to_string(),
+                        None,
+                    ),
                     ErrorKind::Write(WriteFailure::WriteError(write_err)) if write_err.code == 11000 => (
                         StatusCode::CONFLICT,
                         "duplicate_key",
@@ -139,6 +169,26 @@ impl ResponseError for AppErrors {
                 )
             }
 
+            AppErrors::Csv(e) => {
+                eprintln!("CSV error: {:?}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "serialization_error",
+                    "Data serialization error".to_string(),
+                    None,
+                )
+            }
+
+            AppErrors::Redis(e) => {
+                eprintln!("Redis error: {:?}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "storage_error",
+                    "Storage error".to_string(),
+                    None,
+                )
+            }
+
             AppErrors::InvalidUUID => (
                 StatusCode::BAD_REQUEST,
                 "invalid_uuid",
@@ -152,6 +202,20 @@ impl ResponseError for AppErrors {
                 format!("{} not found", resource),  
                 None,
             ),
+
+            AppErrors::BadRequest(message) => (
+                StatusCode::BAD_REQUEST,
+                "bad_request",
+                message.clone(),
+                None,
+            ),
+
+            AppErrors::Conflict(message) => (
+                StatusCode::CONFLICT,
+                "conflict",
+                message.clone(),
+                None,
+            ),
         };
 
         HttpResponse::build(status).json(ErrorResponse {
@@ -168,8 +232,12 @@ impl ResponseError for AppErrors {
             AppErrors::Hash(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppErrors::Mongo(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppErrors::Bson(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppErrors::Csv(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppErrors::Redis(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppErrors::InvalidUUID => StatusCode::BAD_REQUEST,
             AppErrors::NotFound(_) => StatusCode::NOT_FOUND,
+            AppErrors::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppErrors::Conflict(_) => StatusCode::CONFLICT,
         }
     }
 }
\ No newline at end of file
@@ -4,6 +4,8 @@ use crate::{
     api_docs::ApiDoc,
     db::{mongo::init_db, redis::init_redis},
     models::app::AppState,
+    services::{payment_service, tax_service},
+    utils::{currency, webhook},
 };
 use actix_cors::Cors;
 use actix_governor::{Governor, GovernorConfigBuilder};
@@ -31,6 +33,10 @@ mod utils;
 #[actix_web::main]
 async fn main() {
     config::init();
+    // Refuse to start rather than price the catalog in the wrong currency or
+    // charge the wrong tax
+    currency::get_base_currency();
+    tax_service::get_tax_mode();
     let mongo: mongodb::Database = init_db().await;
     let redis = init_redis().await.expect("Failed to connect to Redis");
     let port: u16 = env::var("PORT").unwrap().parse().unwrap();
@@ -41,7 +47,16 @@ async fn main() {
         .finish()
         .unwrap();
 
-    let state = web::Data::new(AppState { mongo, redis });
+    // Refuse to start rather than accept webhooks nobody can verify
+    webhook::get_webhook_secrets();
+    let payments = payment_service::get_payment_provider();
+    payment_service::spawn_reconciliation(mongo.clone(), redis.clone(), payments.clone());
+
+    let state = web::Data::new(AppState {
+        mongo,
+        redis,
+        payments,
+    });
 
     info!("Server started in the port: {}", port);
 
@@ -49,7 +64,23 @@ async fn main() {
         let cors = Cors::default()
             .allow_any_origin()
             .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
-            .allowed_headers(vec!["Content-Type", "Authorization"])
+            .allowed_headers(vec![
+                "Content-Type",
+                "Authorization",
+                "X-Cart-Token",
+                "If-Match",
+                "If-None-Match",
+                "If-Modified-Since",
+                "Idempotency-Key",
+                "Accept-Currency",
+                "Accept-Language",
+            ])
+            .expose_headers(vec![
+                "X-Cart-Token",
+                "ETag",
+                "Last-Modified",
+                "Idempotent-Replayed",
+            ])
             .max_age(3600);
 
         let configure = web::scope("/api")
@@ -0,0 +1,330 @@
+use std::{
+    env,
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use actix_web::{
+    body::{self, BoxBody, MessageBody},
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{HeaderName, HeaderValue},
+        Method, StatusCode,
+    },
+    web, Error, HttpMessage, HttpResponse,
+};
+use futures_util::future::LocalBoxFuture;
+use redis::{aio::ConnectionManager, AsyncCommands};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{errors::AppErrors, models::app::AppState, utils::jwt::Claims};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Set on responses that were replayed instead of handled again
+pub const REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+/// Longest key accepted, UUIDs and similar fit easily
+const MAX_KEY_LENGTH: usize = 255;
+
+/// How long a key stays claimed while its request is handled. Should the
+/// process die before the response is stored, retries are turned away as
+/// in progress for this long only.
+const LOCK_SECONDS: u64 = 60;
+
+/// How long the first response of a key is replayed
+fn get_idempotency_ttl_hours() -> u64 {
+    env::var("IDEMPOTENCY_TTL_HOURS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(24)
+}
+
+/// What is kept under a key: the request it was first used with and, once
+/// the handler finished, the response to replay
+#[derive(Serialize, Deserialize)]
+struct IdempotencyRecord {
+    fingerprint: String,
+    response: Option<StoredResponse>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// Hex, responses are not always text
+    body: String,
+}
+
+/// Identifies a request by method, path, query and body, so a key reused for
+/// anything else is caught
+fn fingerprint(method: &Method, path: &str, query: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b"\n");
+    hasher.update(path);
+    hasher.update(b"?");
+    hasher.update(query);
+    hasher.update(b"\n");
+    hasher.update(body);
+
+    hex::encode(hasher.finalize())
+}
+
+/// Keys belong to the caller, two customers picking the same key never meet
+fn redis_key(owner: &str, key: &str) -> String {
+    format!("idempotency:{}:{}", owner, key)
+}
+
+fn error_response(status: StatusCode, error: &str, message: &str) -> HttpResponse {
+    HttpResponse::build(status).json(serde_json::json!({
+        "error": error,
+        "message": message
+    }))
+}
+
+fn replay(stored: StoredResponse) -> HttpResponse {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = HttpResponse::build(status);
+
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(name.as_str()),
+            HeaderValue::from_str(&value),
+        ) {
+            response.append_header((name, value));
+        }
+    }
+
+    response
+        .insert_header((REPLAYED_HEADER, "true"))
+        .body(hex::decode(stored.body).unwrap_or_default())
+}
+
+/// Honours the `Idempotency-Key` header on mutating requests. The first
+/// response of a key is stored in Redis for `IDEMPOTENCY_TTL_HOURS` and
+/// replayed on retries; reusing the key for a different request is rejected.
+/// When Redis cannot be reached the request is handled without the
+/// guarantee rather than failed. Wrap it inside `JwtMiddleware` so keys are
+/// kept per user.
+pub struct Idempotency;
+
+impl<S, B> Transform<S, ServiceRequest> for Idempotency
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = IdempotencyService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotencyService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct IdempotencyService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotencyService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let key = req
+                .headers()
+                .get(IDEMPOTENCY_KEY_HEADER)
+                .and_then(|h| h.to_str().ok())
+                .map(|h| h.trim().to_string());
+
+            let key = match key {
+                Some(key)
+                    if !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) =>
+                {
+                    key
+                }
+                _ => return Ok(service.call(req).await?.map_into_boxed_body()),
+            };
+
+            if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+                let (req, _) = req.into_parts();
+                let response = error_response(
+                    StatusCode::BAD_REQUEST,
+                    "bad_request",
+                    &format!(
+                        "{} must be 1 to {} characters long",
+                        IDEMPOTENCY_KEY_HEADER, MAX_KEY_LENGTH
+                    ),
+                );
+                return Ok(ServiceResponse::new(req, response));
+            }
+
+            let Some(mut redis) = req
+                .app_data::<web::Data<AppState>>()
+                .map(|state| state.redis.clone())
+            else {
+                return Ok(service.call(req).await?.map_into_boxed_body());
+            };
+
+            let owner = req
+                .extensions()
+                .get::<Claims>()
+                .map(|claims| claims.sub.clone())
+                .unwrap_or_else(|| "anonymous".to_string());
+            let redis_key = redis_key(&owner, &key);
+
+            // The body is read here to fingerprint it and handed back to the handler
+            let body = req.extract::<web::Bytes>().await?;
+            let fingerprint = fingerprint(req.method(), req.path(), req.query_string(), &body);
+            req.set_payload(Payload::from(body));
+
+            let record = IdempotencyRecord {
+                fingerprint: fingerprint.clone(),
+                response: None,
+            };
+
+            let claimed = redis::cmd("SET")
+                .arg(&redis_key)
+                .arg(serde_json::to_string(&record)?)
+                .arg("NX")
+                .arg("EX")
+                .arg(LOCK_SECONDS)
+                .query_async::<Option<String>>(&mut redis)
+                .await;
+            let claimed = match claimed {
+                Ok(claimed) => claimed.is_some(),
+                Err(e) => {
+                    eprintln!("❌ Idempotency keys are unavailable: {:?}", e);
+                    return Ok(service.call(req).await?.map_into_boxed_body());
+                }
+            };
+
+            if !claimed {
+                let existing: Option<String> =
+                    redis.get(&redis_key).await.map_err(AppErrors::from)?;
+                let existing = existing
+                    .and_then(|value| serde_json::from_str::<IdempotencyRecord>(&value).ok());
+
+                let (req, _) = req.into_parts();
+                let response = match existing {
+                    Some(existing) if existing.fingerprint != fingerprint => error_response(
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "idempotency_key_reused",
+                        "The idempotency key was already used for a different request",
+                    ),
+                    Some(IdempotencyRecord {
+                        response: Some(stored),
+                        ..
+                    }) => replay(stored),
+                    // Still being handled, or expired between the two calls
+                    _ => error_response(
+                        StatusCode::CONFLICT,
+                        "conflict",
+                        "A request with this idempotency key is still in progress",
+                    ),
+                };
+                return Ok(ServiceResponse::new(req, response));
+            }
+
+            let res = match service.call(req).await {
+                Ok(res) => res,
+                Err(e) => {
+                    forget(&mut redis, &redis_key).await;
+                    return Err(e);
+                }
+            };
+
+            // Server errors are worth retrying, so the key is freed for the retry
+            if res.status().is_server_error() {
+                forget(&mut redis, &redis_key).await;
+                return Ok(res.map_into_boxed_body());
+            }
+
+            let (req, res) = res.into_parts();
+            let (res, body) = res.into_parts();
+            let body = body::to_bytes(body)
+                .await
+                .map_err(|e| actix_web::error::ErrorInternalServerError(e.into().to_string()))?;
+
+            let stored = StoredResponse {
+                status: res.status().as_u16(),
+                headers: res
+                    .headers()
+                    .iter()
+                    .filter_map(|(name, value)| {
+                        Some((name.to_string(), value.to_str().ok()?.to_string()))
+                    })
+                    .collect(),
+                body: hex::encode(&body),
+            };
+            let record = IdempotencyRecord {
+                fingerprint,
+                response: Some(stored),
+            };
+
+            let ttl = get_idempotency_ttl_hours() * 60 * 60;
+            let saved: redis::RedisResult<()> = redis
+                .set_ex(&redis_key, serde_json::to_string(&record)?, ttl)
+                .await;
+            if let Err(e) = saved {
+                eprintln!("❌ Failed to store idempotent response: {:?}", e);
+                forget(&mut redis, &redis_key).await;
+            }
+
+            Ok(ServiceResponse::new(
+                req,
+                res.set_body(body).map_into_boxed_body(),
+            ))
+        })
+    }
+}
+
+/// Frees a key so the request can be tried again
+async fn forget(redis: &mut ConnectionManager, redis_key: &str) {
+    let _: Result<i64, _> = redis.del(redis_key).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_changes_with_the_body() {
+        let a = fingerprint(&Method::POST, "/api/order/create", "", br#"{"items":[]}"#);
+        let b = fingerprint(&Method::POST, "/api/order/create", "", br#"{"items":[1]}"#);
+
+        assert_ne!(a, b);
+        assert_eq!(
+            a,
+            fingerprint(&Method::POST, "/api/order/create", "", br#"{"items":[]}"#)
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_changes_with_the_target() {
+        let a = fingerprint(&Method::POST, "/api/order/cancel/1", "", b"");
+        let b = fingerprint(&Method::POST, "/api/order/cancel/2", "", b"");
+        let c = fingerprint(&Method::PUT, "/api/order/cancel/1", "", b"");
+
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+    }
+}
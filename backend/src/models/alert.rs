@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::{money::Money, product::Product};
+
+#[derive(TS, Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[ts(export, export_to = "../../db_types/AlertCondition.d.ts")]
+#[serde(tag = "kind")]
+pub enum AlertCondition {
+    /// A sold-out product is restocked
+    BackInStock,
+    /// The discounted price drops to `price` or below
+    PriceBelow { price: Money },
+}
+
+impl AlertCondition {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AlertCondition::BackInStock => "BackInStock",
+            AlertCondition::PriceBelow { .. } => "PriceBelow",
+        }
+    }
+
+    pub fn is_met(&self, product: &Product) -> bool {
+        match self {
+            AlertCondition::BackInStock => product.stock != Some(0),
+            AlertCondition::PriceBelow { price } => {
+                let current = product.price.apply_discount(product.discount);
+                current.currency == price.currency && current.amount <= price.amount
+            }
+        }
+    }
+}
+
+/// A customer's request to be told when a product meets the condition.
+/// Alerts fire once; `triggered_at` is set when they do.
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/ProductAlert.d.ts")]
+pub struct ProductAlert {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub user_id: String,
+    pub product_id: String,
+    pub condition: AlertCondition,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub created_at: i64,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number | null")]
+    pub triggered_at: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{money::Currency, product::ProductBuilder};
+
+    #[test]
+    fn test_back_in_stock_when_units_arrive_or_stock_is_untracked() {
+        let condition = AlertCondition::BackInStock;
+
+        assert!(!condition.is_met(&ProductBuilder::new().price(1_000).stock(Some(0)).build()));
+        assert!(condition.is_met(&ProductBuilder::new().price(1_000).stock(Some(3)).build()));
+        assert!(condition.is_met(&ProductBuilder::new().price(1_000).build()));
+    }
+
+    #[test]
+    fn test_price_below_uses_the_discounted_price() {
+        let condition = AlertCondition::PriceBelow {
+            price: Money::new(900, Currency::Usd),
+        };
+
+        assert!(!condition.is_met(&ProductBuilder::new().price(1_000).build()));
+        assert!(condition.is_met(&ProductBuilder::new().price(1_000).discount(10).build()));
+        assert!(!AlertCondition::PriceBelow {
+            price: Money::new(900, Currency::Eur)
+        }
+        .is_met(&ProductBuilder::new().price(1_000).discount(10).build()));
+    }
+
+    #[test]
+    fn test_condition_is_tagged_by_kind() {
+        let json = serde_json::to_value(AlertCondition::PriceBelow {
+            price: Money::new(900, Currency::Usd),
+        })
+        .unwrap();
+
+        assert_eq!(json["kind"], "PriceBelow");
+        assert_eq!(json["price"]["amount"], 900);
+    }
+}
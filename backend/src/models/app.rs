@@ -1,6 +1,11 @@
+use std::sync::Arc;
+
 use redis::aio::ConnectionManager;
 
+use crate::services::payment_service::PaymentProvider;
+
 pub struct AppState {
     pub mongo: mongodb::Database,
     pub redis: ConnectionManager,
+    pub payments: Arc<dyn PaymentProvider>,
 }
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(TS, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[ts(export, export_to = "../../db_types/AttributeKind.d.ts")]
+pub enum AttributeKind {
+    Text,
+    Number,
+    Boolean,
+    /// One of the `options` of the definition
+    Enum,
+}
+
+/// Value of one technical specification, e.g. `29`, `true` or `"aluminium"`
+#[derive(TS, Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[ts(export, export_to = "../../db_types/AttributeValue.d.ts")]
+#[serde(untagged)]
+pub enum AttributeValue {
+    Boolean(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeValue::Boolean(value) => write!(f, "{}", value),
+            AttributeValue::Number(value) => write!(f, "{}", value),
+            AttributeValue::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/AttributeDefinition.d.ts")]
+pub struct AttributeDefinition {
+    /// Name used in product documents and filters
+    #[schema(example = "wheel_size")]
+    pub key: String,
+    #[schema(example = "Wheel size")]
+    pub label: String,
+    pub kind: AttributeKind,
+    /// Allowed values of an `Enum` attribute
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[schema(example = "in")]
+    pub unit: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl AttributeDefinition {
+    fn accepts(&self, value: &AttributeValue) -> bool {
+        match (self.kind, value) {
+            (AttributeKind::Text, AttributeValue::Text(_)) => true,
+            (AttributeKind::Number, AttributeValue::Number(n)) => n.is_finite(),
+            (AttributeKind::Boolean, AttributeValue::Boolean(_)) => true,
+            (AttributeKind::Enum, AttributeValue::Text(text)) => self.options.contains(text),
+            _ => false,
+        }
+    }
+}
+
+/// Technical specifications products of one category can have
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/CategorySchema.d.ts")]
+pub struct CategorySchema {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub category: u8,
+    pub attributes: Vec<AttributeDefinition>,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+impl CategorySchema {
+    pub fn definition(&self, key: &str) -> Option<&AttributeDefinition> {
+        self.attributes.iter().find(|d| d.key == key)
+    }
+
+    /// Checks product attribute values against the schema
+    pub fn check(&self, values: &BTreeMap<String, AttributeValue>) -> Result<(), String> {
+        for (key, value) in values {
+            let definition = self
+                .definition(key)
+                .ok_or_else(|| format!("Unknown attribute: {}", key))?;
+
+            if !definition.accepts(value) {
+                return Err(match definition.kind {
+                    AttributeKind::Enum => format!(
+                        "Attribute {} must be one of: {}",
+                        key,
+                        definition.options.join(", ")
+                    ),
+                    kind => format!("Attribute {} must be a {:?} value", key, kind),
+                });
+            }
+        }
+
+        if let Some(missing) = self
+            .attributes
+            .iter()
+            .find(|d| d.required && !values.contains_key(&d.key))
+        {
+            return Err(format!("Attribute {} is required", missing.key));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(key: &str, kind: AttributeKind, required: bool) -> AttributeDefinition {
+        AttributeDefinition {
+            key: key.to_string(),
+            label: key.to_string(),
+            kind,
+            options: match kind {
+                AttributeKind::Enum => vec!["aluminium".to_string(), "steel".to_string()],
+                _ => vec![],
+            },
+            unit: None,
+            required,
+        }
+    }
+
+    fn bike_schema() -> CategorySchema {
+        CategorySchema {
+            _id: Uuid::new_v4(),
+            category: 1,
+            attributes: vec![
+                definition("frame_material", AttributeKind::Enum, true),
+                definition("wheel_size", AttributeKind::Number, false),
+                definition("disc_brakes", AttributeKind::Boolean, false),
+            ],
+            updated_at: 0,
+        }
+    }
+
+    fn values(json: &str) -> BTreeMap<String, AttributeValue> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_untagged_values_keep_their_type() {
+        let parsed = values(r#"{"a": true, "b": 29, "c": "steel"}"#);
+
+        assert_eq!(parsed["a"], AttributeValue::Boolean(true));
+        assert_eq!(parsed["b"], AttributeValue::Number(29.0));
+        assert_eq!(parsed["c"], AttributeValue::Text("steel".to_string()));
+    }
+
+    #[test]
+    fn test_accepts_matching_values() {
+        let schema = bike_schema();
+
+        assert!(schema
+            .check(&values(
+                r#"{"frame_material": "steel", "wheel_size": 27.5, "disc_brakes": true}"#
+            ))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_rejects_wrong_type_unknown_key_and_missing_required() {
+        let schema = bike_schema();
+
+        assert!(schema
+            .check(&values(
+                r#"{"frame_material": "steel", "wheel_size": "29"}"#
+            ))
+            .is_err());
+        assert!(schema
+            .check(&values(r#"{"frame_material": "carbon"}"#))
+            .is_err());
+        assert!(schema
+            .check(&values(r#"{"frame_material": "steel", "color": "red"}"#))
+            .is_err());
+        assert_eq!(
+            schema.check(&values(r#"{"wheel_size": 29}"#)),
+            Err("Attribute frame_material is required".to_string())
+        );
+    }
+}
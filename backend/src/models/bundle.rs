@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::{money::Money, product::ProductStatus};
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/BundleItem.d.ts")]
+pub struct BundleItem {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    pub product_id: String,
+    #[schema(example = 1, minimum = 1)]
+    pub quantity: u32,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, Copy, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/BundlePricing.d.ts")]
+#[serde(tag = "kind")]
+pub enum BundlePricing {
+    /// Fixed price for the whole kit, in the base currency
+    Price { price: Money },
+    /// Percentage off the sum of the component prices
+    Discount { percent: u8 },
+}
+
+impl BundlePricing {
+    /// Kit price given what the components cost on their own
+    pub fn apply(&self, components: Money) -> Money {
+        match self {
+            BundlePricing::Price { price } => *price,
+            BundlePricing::Discount { percent } => components.apply_discount(*percent),
+        }
+    }
+}
+
+/// Kit of products sold together, e.g. bike + helmet + lock
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/Bundle.d.ts")]
+pub struct Bundle {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub images: Vec<String>,
+    pub items: Vec<BundleItem>,
+    pub pricing: BundlePricing,
+    pub status: ProductStatus,
+    /// Incremented on every change
+    pub version: u32,
+}
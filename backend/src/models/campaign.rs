@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/DiscountCampaign.d.ts")]
+pub struct DiscountCampaign {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub product_id: String,
+    #[schema(example = 15, minimum = 0, maximum = 100)]
+    pub discount: u8,
+    /// Unix timestamp (seconds), inclusive
+    #[ts(type = "number")]
+    pub starts_at: i64,
+    /// Unix timestamp (seconds), exclusive
+    #[ts(type = "number")]
+    pub ends_at: i64,
+}
+
+impl DiscountCampaign {
+    pub fn is_active_at(&self, timestamp: i64) -> bool {
+        self.starts_at <= timestamp && timestamp < self.ends_at
+    }
+
+    /// Whether both campaigns run at some common moment
+    pub fn overlaps(&self, other: &DiscountCampaign) -> bool {
+        self.starts_at < other.ends_at && other.starts_at < self.ends_at
+    }
+}
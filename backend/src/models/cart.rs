@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+/// Whose cart a request works with
+#[derive(Debug, Clone, PartialEq)]
+pub enum CartOwner {
+    User(String),
+    /// Anonymous cart named by the `X-Cart-Token` header
+    Guest(Uuid),
+}
+
+impl CartOwner {
+    /// Redis hash holding the cart
+    pub fn key(&self) -> String {
+        match self {
+            CartOwner::User(user_id) => format!("cart:user:{}", user_id),
+            CartOwner::Guest(token) => format!("cart:guest:{}", token),
+        }
+    }
+}
+
+/// One line of a cart
+#[derive(Debug, Clone, PartialEq)]
+pub enum CartLine {
+    Product(String),
+    Bundle(String),
+}
+
+impl CartLine {
+    /// Field of the cart hash holding the quantity
+    pub fn field(&self) -> String {
+        match self {
+            CartLine::Product(product_id) => format!("product:{}", product_id),
+            CartLine::Bundle(bundle_id) => format!("bundle:{}", bundle_id),
+        }
+    }
+
+    fn from_field(field: &str) -> Option<Self> {
+        match field.split_once(':')? {
+            ("product", id) => Some(CartLine::Product(id.to_string())),
+            ("bundle", id) => Some(CartLine::Bundle(id.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Product and bundle ids in a cart with their quantities, sorted by id
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Cart {
+    pub items: Vec<(String, u32)>,
+    pub bundles: Vec<(String, u32)>,
+}
+
+impl Cart {
+    /// Reads the fields of the cart hash, skipping ones it does not know
+    pub fn from_fields(fields: HashMap<String, u32>) -> Self {
+        let mut cart = Cart::default();
+
+        for (field, quantity) in fields {
+            match CartLine::from_field(&field) {
+                Some(CartLine::Product(id)) => cart.items.push((id, quantity)),
+                Some(CartLine::Bundle(id)) => cart.bundles.push((id, quantity)),
+                None => {}
+            }
+        }
+
+        cart.items.sort();
+        cart.bundles.sort();
+
+        cart
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.bundles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_lines_from_hash_fields() {
+        let cart = Cart::from_fields(HashMap::from([
+            ("product:b".to_string(), 2),
+            ("product:a".to_string(), 1),
+            ("bundle:kit".to_string(), 3),
+            ("unknown".to_string(), 5),
+        ]));
+
+        assert_eq!(cart.items, vec![("a".to_string(), 1), ("b".to_string(), 2)]);
+        assert_eq!(cart.bundles, vec![("kit".to_string(), 3)]);
+        assert!(Cart::default().is_empty());
+    }
+
+    #[test]
+    fn test_fields_round_trip() {
+        let line = CartLine::Bundle("kit".to_string());
+
+        assert_eq!(line.field(), "bundle:kit");
+        assert_eq!(CartLine::from_field(&line.field()), Some(line));
+    }
+}
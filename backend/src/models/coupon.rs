@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::money::Money;
+
+/// What a coupon takes off the products it applies to
+#[derive(TS, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[ts(export, export_to = "../../db_types/CouponDiscount.d.ts")]
+#[serde(tag = "type")]
+pub enum CouponDiscount {
+    /// Percentage of the eligible lines
+    Percentage {
+        #[schema(example = 10, minimum = 1, maximum = 100)]
+        percent: u8,
+    },
+    /// Fixed amount, converted to the order currency and capped at the
+    /// eligible lines
+    Fixed { amount: Money },
+}
+
+/// Promo code customers enter at checkout
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/Coupon.d.ts")]
+pub struct Coupon {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    /// Stored in upper case, matched case-insensitively
+    #[schema(example = "SPRING10")]
+    pub code: String,
+    pub discount: CouponDiscount,
+    /// Smallest order total, before the coupon, the coupon works for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub min_order_value: Option<Money>,
+    /// Product categories the coupon applies to; empty means every product
+    /// and bundle
+    #[serde(default)]
+    pub categories: Vec<u8>,
+    /// Redemptions allowed in total
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub max_uses: Option<u32>,
+    /// Redemptions allowed per customer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub max_uses_per_user: Option<u32>,
+    /// Redemptions so far
+    #[serde(default)]
+    pub uses: u32,
+    /// Unix timestamp (seconds), inclusive
+    #[ts(type = "number")]
+    pub starts_at: i64,
+    /// Unix timestamp (seconds), exclusive
+    #[ts(type = "number")]
+    pub ends_at: i64,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+impl Coupon {
+    pub fn is_active_at(&self, timestamp: i64) -> bool {
+        self.starts_at <= timestamp && timestamp < self.ends_at
+    }
+
+    /// Whether products of `category` get the discount
+    pub fn covers_category(&self, category: u8) -> bool {
+        self.categories.is_empty() || self.categories.contains(&category)
+    }
+
+    /// Bundles mix categories, so only unrestricted coupons cover them
+    pub fn covers_bundles(&self) -> bool {
+        self.categories.is_empty()
+    }
+
+    /// Codes are compared in upper case without surrounding spaces
+    pub fn normalize_code(code: &str) -> String {
+        code.trim().to_uppercase()
+    }
+}
+
+/// Coupon applied to an order
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/AppliedCoupon.d.ts")]
+pub struct AppliedCoupon {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    pub coupon_id: String,
+    pub code: String,
+    /// Taken off the order total, in the order currency
+    pub discount: Money,
+}
+
+/// One use of a coupon
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/CouponRedemption.d.ts")]
+pub struct CouponRedemption {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub coupon_id: String,
+    pub code: String,
+    pub order_id: String,
+    pub customer_id: String,
+    /// In the order currency
+    pub discount: Money,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub redeemed_at: i64,
+}
+
+#[cfg(test)]
+impl Coupon {
+    /// Coupon without limits that never expires, for tests
+    pub fn unlimited(discount: CouponDiscount, categories: Vec<u8>) -> Coupon {
+        Coupon {
+            _id: Uuid::new_v4(),
+            code: "SPRING10".to_string(),
+            discount,
+            min_order_value: None,
+            categories,
+            max_uses: None,
+            max_uses_per_user: None,
+            uses: 0,
+            starts_at: 0,
+            ends_at: i64::MAX,
+            created_at: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed() -> CouponDiscount {
+        CouponDiscount::Fixed {
+            amount: Money::usd(1_000),
+        }
+    }
+
+    #[test]
+    fn test_active_window_is_half_open() {
+        let coupon = Coupon {
+            starts_at: 100,
+            ends_at: 200,
+            ..Coupon::unlimited(fixed(), vec![])
+        };
+
+        assert!(!coupon.is_active_at(99));
+        assert!(coupon.is_active_at(100));
+        assert!(coupon.is_active_at(199));
+        assert!(!coupon.is_active_at(200));
+    }
+
+    #[test]
+    fn test_empty_categories_cover_everything() {
+        assert!(Coupon::unlimited(fixed(), vec![]).covers_category(1));
+        assert!(Coupon::unlimited(fixed(), vec![0]).covers_category(0));
+        assert!(!Coupon::unlimited(fixed(), vec![0]).covers_category(1));
+    }
+
+    #[test]
+    fn test_normalize_code() {
+        assert_eq!(Coupon::normalize_code("  spring10 "), "SPRING10");
+    }
+}
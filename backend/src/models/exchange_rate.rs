@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppErrors,
+    models::money::{Currency, Money},
+};
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/ExchangeRate.d.ts")]
+pub struct ExchangeRate {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub currency: Currency,
+    /// Units of `currency` for one unit of the base currency
+    #[schema(example = 41.25)]
+    pub rate: f64,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub created_at: i64,
+    /// ID of the admin who set the rate
+    pub created_by: String,
+}
+
+/// Latest rates, keyed by currency, relative to the base currency
+pub struct RateTable {
+    pub base: Currency,
+    pub rates: HashMap<Currency, f64>,
+}
+
+impl RateTable {
+    pub fn rate(&self, currency: Currency) -> Result<f64, AppErrors> {
+        if currency == self.base {
+            return Ok(1.0);
+        }
+
+        self.rates
+            .get(&currency)
+            .copied()
+            .ok_or_else(|| AppErrors::NotFound(format!("Exchange rate for {}", currency)))
+    }
+
+    pub fn convert(&self, money: Money, to: Currency) -> Result<Money, AppErrors> {
+        if money.currency == to {
+            return Ok(money);
+        }
+
+        let rate = self.rate(to)? / self.rate(money.currency)?;
+
+        Ok(money.convert(to, rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> RateTable {
+        RateTable {
+            base: Currency::Usd,
+            rates: HashMap::from([(Currency::Uah, 40.0), (Currency::Eur, 0.8)]),
+        }
+    }
+
+    #[test]
+    fn test_convert_from_base() {
+        let converted = table()
+            .convert(Money::new(1000, Currency::Usd), Currency::Uah)
+            .unwrap();
+
+        assert_eq!(converted, Money::new(40_000, Currency::Uah));
+    }
+
+    #[test]
+    fn test_convert_between_quoted_currencies() {
+        let converted = table()
+            .convert(Money::new(40_000, Currency::Uah), Currency::Eur)
+            .unwrap();
+
+        assert_eq!(converted, Money::new(800, Currency::Eur));
+    }
+
+    #[test]
+    fn test_convert_missing_rate() {
+        let table = RateTable {
+            base: Currency::Usd,
+            rates: HashMap::new(),
+        };
+
+        assert!(matches!(
+            table.convert(Money::new(100, Currency::Usd), Currency::Eur),
+            Err(AppErrors::NotFound(_))
+        ));
+        assert_eq!(
+            table
+                .convert(Money::new(100, Currency::Usd), Currency::Usd)
+                .unwrap(),
+            Money::new(100, Currency::Usd)
+        );
+    }
+}
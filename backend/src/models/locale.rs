@@ -0,0 +1,64 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+#[derive(TS, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+#[ts(export, export_to = "../../db_types/Locale.d.ts")]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    Uk,
+    En,
+}
+
+impl Locale {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Uk => "uk",
+            Locale::En => "en",
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Accepts a language code or a language tag such as `en-US`
+    fn from_str(tag: &str) -> Result<Self, Self::Err> {
+        let language = tag.trim().split(['-', '_']).next().unwrap_or_default();
+
+        match language.to_ascii_lowercase().as_str() {
+            "uk" => Ok(Locale::Uk),
+            "en" => Ok(Locale::En),
+            _ => Err(format!("Unsupported locale: {}", tag.trim())),
+        }
+    }
+}
+
+/// Responses that show product text in the requested locale
+pub trait Localize {
+    fn localize(&mut self, locale: Locale);
+}
+
+impl<T: Localize> Localize for [T] {
+    fn localize(&mut self, locale: Locale) {
+        for item in self.iter_mut() {
+            item.localize(locale);
+        }
+    }
+}
+
+impl<T: Localize> Localize for Option<T> {
+    fn localize(&mut self, locale: Locale) {
+        if let Some(item) = self {
+            item.localize(locale);
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
@@ -1,6 +1,21 @@
+pub mod alert;
 pub mod app;
+pub mod attribute;
+pub mod bundle;
+pub mod campaign;
+pub mod cart;
+pub mod coupon;
+pub mod exchange_rate;
+pub mod locale;
+pub mod money;
+pub mod notification;
 pub mod order;
+pub mod payment;
+pub mod price_history;
 pub mod product;
+pub mod product_pair;
 pub mod res;
 pub mod role;
+pub mod tax;
 pub mod user;
+pub mod wishlist;
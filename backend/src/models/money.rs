@@ -0,0 +1,233 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+#[derive(TS, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+#[ts(export, export_to = "../../db_types/Currency.d.ts")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    Uah,
+    Eur,
+    Usd,
+}
+
+impl Currency {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Uah => "UAH",
+            Currency::Eur => "EUR",
+            Currency::Usd => "USD",
+        }
+    }
+
+    /// Number of minor units digits (kopiyky, cents)
+    pub fn exponent(&self) -> u32 {
+        2
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        match code.trim().to_ascii_uppercase().as_str() {
+            "UAH" => Ok(Currency::Uah),
+            "EUR" => Ok(Currency::Eur),
+            "USD" => Ok(Currency::Usd),
+            other => Err(format!("Unsupported currency: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+#[derive(TS, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[ts(export, export_to = "../../db_types/Money.d.ts")]
+pub struct Money {
+    /// Amount in minor units, e.g. 1250 is 12.50
+    #[ts(type = "number")]
+    #[schema(example = 129900)]
+    pub amount: u64,
+    pub currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount: u64, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// Size of a percentage discount, rounded half up to the nearest minor unit
+    pub fn discount_amount(&self, percent: u8) -> Money {
+        let percent = u128::from(percent.min(100));
+        let amount = (u128::from(self.amount) * percent + 50) / 100;
+
+        Money::new(amount as u64, self.currency)
+    }
+
+    /// Price after a percentage discount. The discount is rounded first and then
+    /// subtracted, so price and discount always add up to the original amount.
+    pub fn apply_discount(&self, percent: u8) -> Money {
+        Money::new(
+            self.amount - self.discount_amount(percent).amount,
+            self.currency,
+        )
+    }
+
+    /// Converts using `rate` units of `to` per one unit of this currency,
+    /// rounded half up to the nearest minor unit
+    pub fn convert(&self, to: Currency, rate: f64) -> Money {
+        let scale = 10f64.powi(to.exponent() as i32 - self.currency.exponent() as i32);
+        let amount = (self.amount as f64 * rate * scale).round();
+
+        Money::new(amount as u64, to)
+    }
+
+    /// Price of `quantity` units; `None` on overflow
+    pub fn checked_mul(&self, quantity: u32) -> Option<Money> {
+        self.amount
+            .checked_mul(u64::from(quantity))
+            .map(|amount| Money::new(amount, self.currency))
+    }
+
+    /// `None` when the currencies differ or the sum overflows
+    pub fn checked_add(&self, other: Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+
+        self.amount
+            .checked_add(other.amount)
+            .map(|amount| Money::new(amount, self.currency))
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let divisor = 10u64.pow(self.currency.exponent());
+        write!(
+            f,
+            "{}.{:0width$} {}",
+            self.amount / divisor,
+            self.amount % divisor,
+            self.currency,
+            width = self.currency.exponent() as usize
+        )
+    }
+}
+
+#[cfg(test)]
+impl Money {
+    /// Cents, for tests
+    pub fn usd(amount: u64) -> Money {
+        Money::new(amount, Currency::Usd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discount_amount_rounds_half_up() {
+        // 10% of 12.345 is 1.2345 -> 1.23
+        assert_eq!(
+            Money::new(1234, Currency::Uah).discount_amount(10).amount,
+            123
+        );
+        // 10% of 12.35 is 1.235 -> 1.24
+        assert_eq!(
+            Money::new(1235, Currency::Uah).discount_amount(10).amount,
+            124
+        );
+        // 15% of 0.10 is 0.015 -> 0.02
+        assert_eq!(Money::new(10, Currency::Usd).discount_amount(15).amount, 2);
+    }
+
+    #[test]
+    fn test_apply_discount_keeps_total() {
+        let price = Money::new(99_999, Currency::Eur);
+
+        for percent in 0..=100 {
+            let discounted = price.apply_discount(percent);
+            let discount = price.discount_amount(percent);
+
+            assert_eq!(discounted.amount + discount.amount, price.amount);
+            assert_eq!(discounted.currency, Currency::Eur);
+        }
+    }
+
+    #[test]
+    fn test_apply_discount_bounds() {
+        let price = Money::new(5000, Currency::Usd);
+
+        assert_eq!(price.apply_discount(0), price);
+        assert_eq!(price.apply_discount(100), Money::zero(Currency::Usd));
+        assert_eq!(price.apply_discount(250), Money::zero(Currency::Usd));
+    }
+
+    #[test]
+    fn test_checked_add_requires_same_currency() {
+        let uah = Money::new(100, Currency::Uah);
+
+        assert_eq!(
+            uah.checked_add(Money::new(50, Currency::Uah)),
+            Some(Money::new(150, Currency::Uah))
+        );
+        assert_eq!(uah.checked_add(Money::new(50, Currency::Usd)), None);
+        assert_eq!(Money::new(u64::MAX, Currency::Uah).checked_add(uah), None);
+    }
+
+    #[test]
+    fn test_convert_rounds_half_up() {
+        let price = Money::new(10_000, Currency::Usd);
+
+        assert_eq!(
+            price.convert(Currency::Uah, 41.25),
+            Money::new(412_500, Currency::Uah)
+        );
+        assert_eq!(
+            Money::new(1, Currency::Uah).convert(Currency::Eur, 0.5),
+            Money::new(1, Currency::Eur)
+        );
+        assert_eq!(
+            Money::new(3, Currency::Uah).convert(Currency::Eur, 0.1),
+            Money::new(0, Currency::Eur)
+        );
+    }
+
+    #[test]
+    fn test_currency_from_str() {
+        assert_eq!("uah".parse::<Currency>(), Ok(Currency::Uah));
+        assert_eq!(" EUR ".parse::<Currency>(), Ok(Currency::Eur));
+        assert!("GBP".parse::<Currency>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Money::new(129_905, Currency::Uah).to_string(),
+            "1299.05 UAH"
+        );
+        assert_eq!(Money::new(7, Currency::Usd).to_string(), "0.07 USD");
+    }
+
+    #[test]
+    fn test_serialization_format() {
+        let json = serde_json::to_value(Money::new(1250, Currency::Eur)).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({ "amount": 1250, "currency": "EUR" })
+        );
+    }
+}
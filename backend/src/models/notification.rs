@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Message in a user's in-app inbox
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/Notification.d.ts")]
+pub struct Notification {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub user_id: String,
+    pub title: String,
+    pub body: String,
+    /// Product the notification is about, if any
+    #[ts(type = "string | null")]
+    pub product_id: Option<String>,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub created_at: i64,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number | null")]
+    pub read_at: Option<i64>,
+}
+
+/// Email waiting to be picked up by the mailer
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OutboxEmail {
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    /// Unix timestamp (seconds)
+    pub created_at: i64,
+    /// Unix timestamp (seconds), set by the mailer
+    pub sent_at: Option<i64>,
+}
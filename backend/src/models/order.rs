@@ -3,6 +3,123 @@ use ts_rs::TS;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::models::{
+    bundle::BundleItem,
+    coupon::AppliedCoupon,
+    money::Money,
+    tax::{TaxLine, TaxMode},
+};
+
+#[derive(TS, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema, Default)]
+#[ts(export, export_to = "../../db_types/OrderStatus.d.ts")]
+pub enum OrderStatus {
+    /// Placed, waiting for payment
+    #[default]
+    Pending,
+    Paid,
+    /// Being picked and packed
+    Processing,
+    Shipped,
+    Delivered,
+    Cancelled,
+    Refunded,
+}
+
+impl OrderStatus {
+    /// States an order in this state may move to
+    pub fn next(&self) -> &'static [OrderStatus] {
+        use OrderStatus::*;
+
+        match self {
+            Pending => &[Paid, Cancelled],
+            Paid => &[Processing, Cancelled, Refunded],
+            Processing => &[Shipped, Cancelled, Refunded],
+            Shipped => &[Delivered, Refunded],
+            Delivered => &[Refunded],
+            Cancelled | Refunded => &[],
+        }
+    }
+
+    pub fn can_become(&self, status: OrderStatus) -> bool {
+        self.next().contains(&status)
+    }
+
+    /// Customers may cancel until the order is being picked
+    pub fn customer_can_cancel(&self) -> bool {
+        matches!(self, OrderStatus::Pending | OrderStatus::Paid)
+    }
+}
+
+/// One entry of an order's status history
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/OrderStatusChange.d.ts")]
+pub struct OrderStatusChange {
+    pub status: OrderStatus,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub changed_at: i64,
+    /// User who placed the order or moved it to this state
+    pub changed_by: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub note: Option<String>,
+}
+
+/// Money given back to the customer
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/OrderRefund.d.ts")]
+pub struct OrderRefund {
+    pub refund_id: String,
+    /// In the order currency
+    pub amount: Money,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub reason: Option<String>,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub refunded_at: i64,
+    /// User who issued the refund or cancelled the paid order
+    pub refunded_by: String,
+}
+
+/// Product line of an order as it was at purchase time
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/OrderItem.d.ts")]
+pub struct OrderItem {
+    pub product_id: String,
+    /// Only set for products sold in variants
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub variant_id: Option<String>,
+    /// Product name at purchase time
+    pub name: String,
+    pub quantity: u32,
+    /// Regular price of one unit
+    pub unit_price: Money,
+    /// Taken off the whole line
+    pub discount: Money,
+    /// `unit_price * quantity - discount`
+    pub total: Money,
+}
+
+/// Bundle line of an order as it was at purchase time
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/OrderBundle.d.ts")]
+pub struct OrderBundle {
+    pub bundle_id: String,
+    /// Bundle name at purchase time
+    pub name: String,
+    pub quantity: u32,
+    /// What the components of one bundle cost separately
+    pub unit_price: Money,
+    /// Bundle savings on the whole line
+    pub discount: Money,
+    /// `unit_price * quantity - discount`
+    pub total: Money,
+    /// Components of one bundle
+    pub components: Vec<BundleItem>,
+}
+
 #[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
 #[ts(export, export_to = "../../db_types/Order.d.ts")]
 pub struct Order {
@@ -10,8 +127,246 @@ pub struct Order {
     #[schema(value_type = String)]
     #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
     pub _id: Uuid,
-    /// products id
-    pub products_id: Vec<String>,
-    pub total_price: u32,
+    pub items: Vec<OrderItem>,
+    #[serde(default)]
+    pub bundles: Vec<OrderBundle>,
+    /// Sum of the lines at regular prices
+    pub subtotal: Money,
+    /// Sum of the line discounts
+    pub discount: Money,
+    /// Coupon taken off after the line discounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub coupon: Option<AppliedCoupon>,
+    /// Whether the lines already include the tax
+    #[serde(default)]
+    pub tax_mode: TaxMode,
+    /// One line per tax rule that applied
+    #[serde(default)]
+    pub taxes: Vec<TaxLine>,
+    /// Sum of the tax lines; missing on orders placed before taxes were
+    /// calculated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub tax: Option<Money>,
+    pub total_price: Money,
+    /// Units of the order currency per one unit of the base currency at purchase time
+    pub exchange_rate: f64,
     pub customer_id: String,
+    #[serde(default)]
+    pub status: OrderStatus,
+    /// Every state the order has been in, oldest first
+    #[serde(default)]
+    pub history: Vec<OrderStatusChange>,
+    /// Refunds issued so far, oldest first
+    #[serde(default)]
+    pub refunds: Vec<OrderRefund>,
+    /// Incremented on every change, sent as the ETag. Missing on documents
+    /// stored before migration 0005, which earlier migrations still read.
+    #[serde(default)]
+    pub version: u32,
+}
+
+impl Order {
+    /// When the order was placed, from the first status change
+    pub fn placed_at(&self) -> Option<i64> {
+        self.history.first().map(|change| change.changed_at)
+    }
+
+    /// Ordered product ids, one entry per unit, bundle components included
+    pub fn unit_product_ids(&self) -> Vec<String> {
+        let mut ids = Vec::new();
+
+        for item in &self.items {
+            ids.extend(std::iter::repeat_n(
+                item.product_id.clone(),
+                item.quantity as usize,
+            ));
+        }
+
+        for bundle in &self.bundles {
+            for component in &bundle.components {
+                let units = component.quantity as usize * bundle.quantity as usize;
+                ids.extend(std::iter::repeat_n(component.product_id.clone(), units));
+            }
+        }
+
+        ids
+    }
+
+    /// Sum of the refunds issued so far
+    pub fn refunded(&self) -> Money {
+        let amount = self
+            .refunds
+            .iter()
+            .fold(0u64, |sum, refund| sum.saturating_add(refund.amount.amount));
+
+        Money::new(amount, self.total_price.currency)
+    }
+
+    /// What is left to refund
+    pub fn refundable(&self) -> Money {
+        Money::new(
+            self.total_price
+                .amount
+                .saturating_sub(self.refunded().amount),
+            self.total_price.currency,
+        )
+    }
+}
+
+#[cfg(test)]
+impl OrderItem {
+    /// One unit of `product_id` at `total` cents, without discount, for tests
+    pub fn single(product_id: &str, total: u64) -> OrderItem {
+        OrderItem {
+            product_id: product_id.to_string(),
+            variant_id: None,
+            name: product_id.to_string(),
+            quantity: 1,
+            unit_price: Money::usd(total),
+            discount: Money::usd(0),
+            total: Money::usd(total),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::money::Currency;
+
+    fn money(amount: u64) -> Money {
+        Money::new(amount, Currency::Usd)
+    }
+
+    #[test]
+    fn test_unit_product_ids_repeat_every_unit() {
+        let order = Order {
+            _id: Uuid::new_v4(),
+            items: vec![OrderItem {
+                product_id: "helmet".to_string(),
+                variant_id: None,
+                name: "Helmet".to_string(),
+                quantity: 2,
+                unit_price: money(5_000),
+                discount: money(0),
+                total: money(10_000),
+            }],
+            bundles: vec![OrderBundle {
+                bundle_id: "kit".to_string(),
+                name: "Commuter kit".to_string(),
+                quantity: 2,
+                unit_price: money(3_000),
+                discount: money(1_000),
+                total: money(5_000),
+                components: vec![
+                    BundleItem {
+                        product_id: "light".to_string(),
+                        quantity: 2,
+                    },
+                    BundleItem {
+                        product_id: "lock".to_string(),
+                        quantity: 1,
+                    },
+                ],
+            }],
+            subtotal: money(16_000),
+            discount: money(1_000),
+            coupon: None,
+            tax_mode: TaxMode::Exclusive,
+            taxes: vec![],
+            tax: None,
+            total_price: money(15_000),
+            exchange_rate: 1.0,
+            customer_id: "customer".to_string(),
+            status: OrderStatus::Pending,
+            history: vec![],
+            refunds: vec![],
+            version: 1,
+        };
+
+        let ids = order.unit_product_ids();
+
+        assert_eq!(ids.iter().filter(|id| *id == "helmet").count(), 2);
+        assert_eq!(ids.iter().filter(|id| *id == "light").count(), 4);
+        assert_eq!(ids.iter().filter(|id| *id == "lock").count(), 2);
+    }
+
+    #[test]
+    fn test_refundable_is_what_is_left_of_the_total() {
+        let refund = |amount| OrderRefund {
+            refund_id: Uuid::new_v4().to_string(),
+            amount: money(amount),
+            reason: None,
+            refunded_at: 0,
+            refunded_by: "admin".to_string(),
+        };
+        let order = Order {
+            _id: Uuid::new_v4(),
+            items: vec![],
+            bundles: vec![],
+            subtotal: money(10_000),
+            discount: money(0),
+            coupon: None,
+            tax_mode: TaxMode::Exclusive,
+            taxes: vec![],
+            tax: None,
+            total_price: money(10_000),
+            exchange_rate: 1.0,
+            customer_id: "customer".to_string(),
+            status: OrderStatus::Delivered,
+            history: vec![],
+            refunds: vec![refund(2_500), refund(1_000)],
+            version: 1,
+        };
+
+        assert_eq!(order.refunded(), money(3_500));
+        assert_eq!(order.refundable(), money(6_500));
+    }
+
+    #[test]
+    fn test_order_stored_before_versions_decodes() {
+        let total = bson::doc! { "amount": 10_000_i64, "currency": "USD" };
+        let stored = bson::doc! {
+            "_id": bson::Uuid::new(),
+            "items": [],
+            "subtotal": total.clone(),
+            "discount": { "amount": 0_i64, "currency": "USD" },
+            "total_price": total,
+            "exchange_rate": 1.0,
+            "customer_id": "customer",
+            "status": "Delivered",
+        };
+
+        let order: Order = bson::from_document(stored).unwrap();
+
+        assert_eq!(order.version, 0);
+        assert_eq!(order.total_price, money(10_000));
+    }
+
+    #[test]
+    fn test_customers_cancel_before_processing() {
+        assert!(OrderStatus::Pending.customer_can_cancel());
+        assert!(OrderStatus::Paid.customer_can_cancel());
+        assert!(!OrderStatus::Processing.customer_can_cancel());
+        assert!(!OrderStatus::Shipped.customer_can_cancel());
+        assert!(!OrderStatus::Cancelled.customer_can_cancel());
+    }
+
+    #[test]
+    fn test_finished_orders_cannot_move() {
+        assert!(OrderStatus::Cancelled.next().is_empty());
+        assert!(OrderStatus::Refunded.next().is_empty());
+    }
+
+    #[test]
+    fn test_orders_move_forward_only() {
+        assert!(OrderStatus::Pending.can_become(OrderStatus::Paid));
+        assert!(OrderStatus::Shipped.can_become(OrderStatus::Delivered));
+        assert!(!OrderStatus::Pending.can_become(OrderStatus::Shipped));
+        assert!(!OrderStatus::Delivered.can_become(OrderStatus::Cancelled));
+        assert!(!OrderStatus::Paid.can_become(OrderStatus::Pending));
+        assert!(!OrderStatus::Paid.can_become(OrderStatus::Paid));
+    }
 }
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::money::Money;
+
+/// Where a payment is at the provider
+#[derive(TS, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[ts(export, export_to = "../../db_types/PaymentStatus.d.ts")]
+pub enum PaymentStatus {
+    /// Waiting for the customer to pay
+    RequiresPayment,
+    /// Authorized by the customer, the money still has to be captured
+    RequiresCapture,
+    Succeeded,
+    Failed,
+}
+
+/// An attempt to pay for an order through the payment provider
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/Payment.d.ts")]
+pub struct Payment {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub order_id: String,
+    /// Name of the provider that handles the payment
+    pub provider: String,
+    /// Id of the payment at the provider
+    pub intent_id: String,
+    /// Handed to the client to complete the payment with the provider
+    pub client_secret: String,
+    pub amount: Money,
+    pub status: PaymentStatus,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub created_at: i64,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
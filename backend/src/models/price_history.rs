@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::money::Money;
+
+#[derive(TS, Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema, Copy)]
+#[ts(export, export_to = "../../db_types/PriceChangeSource.d.ts")]
+pub enum PriceChangeSource {
+    Manual,
+    Campaign,
+}
+
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug)]
+#[ts(export, export_to = "../../db_types/PriceHistory.d.ts")]
+pub struct PriceHistory {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub product_id: String,
+    pub price: Money,
+    pub discount: u8,
+    pub source: PriceChangeSource,
+    /// Set when the change comes from a discount campaign
+    pub campaign_id: Option<String>,
+    /// Unix timestamp (seconds) from which this price applies
+    #[ts(type = "number")]
+    pub effective_at: i64,
+}
@@ -1,9 +1,37 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::models::{
+    attribute::AttributeValue,
+    locale::{Locale, Localize},
+    money::Money,
+};
+
+#[derive(TS, Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema, Copy, Default)]
+#[ts(export, export_to = "../../db_types/ProductStatus.d.ts")]
+pub enum ProductStatus {
+    /// Only visible to admins
+    #[default]
+    Draft,
+    Published,
+    /// No longer sold, kept for existing orders
+    Archived,
+}
+
+/// Name and description of a product in one more locale
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/ProductTranslation.d.ts")]
+pub struct ProductTranslation {
+    pub locale: Locale,
+    pub name: String,
+    pub description: String,
+}
+
 #[derive(TS, Serialize, Deserialize, Clone, ToSchema, Validate, Debug)]
 #[ts(export, export_to = "../../db_types/Product.d.ts")]
 pub struct Product {
@@ -11,11 +39,19 @@ pub struct Product {
     #[schema(value_type = String)]
     #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
     pub _id: Uuid,
+    /// Stock keeping unit, unique across the catalog
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sku: Option<String>,
     #[validate(length(min = 2, message = "The number of letters must be at least 2."))]
     pub name: String,
-    pub price: u32,
+    pub price: Money,
     #[validate(length(min = 2, message = "The number of letters must be at least 2."))]
     pub description: String,
+    /// Name and description in other locales than the default one. Public
+    /// endpoints apply the requested locale and send this empty.
+    #[serde(default)]
+    pub translations: Vec<ProductTranslation>,
     pub images: Vec<String>,
     #[validate(range(
         min = 0,
@@ -29,4 +65,153 @@ pub struct Product {
     #[validate(range(min = 0, max = 1, message = "The category must be between 0 and 1."))]
     #[schema(example = 0, minimum = 0, maximum = 1)]
     pub category: u8,
+    /// Technical specifications, checked against the category schema
+    #[serde(default)]
+    pub attributes: BTreeMap<String, AttributeValue>,
+    /// Units on hand; `None` when stock is not tracked
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub stock: Option<u32>,
+    pub status: ProductStatus,
+    /// Unix timestamp (seconds) of a soft delete
+    #[ts(type = "number | null")]
+    pub deleted_at: Option<i64>,
+    /// Incremented on every change, sent as the ETag. Missing on documents
+    /// stored before migration 0005, which earlier migrations still read.
+    #[serde(default)]
+    pub version: u32,
+}
+
+impl Product {
+    /// Whether the product can still be ordered
+    pub fn is_available(&self) -> bool {
+        self.status == ProductStatus::Published && self.deleted_at.is_none()
+    }
+}
+
+impl Localize for Product {
+    /// Shows the text of `locale`, keeping the default text when there is no
+    /// translation, and drops the translations from the product
+    fn localize(&mut self, locale: Locale) {
+        let translations = std::mem::take(&mut self.translations);
+
+        if let Some(translation) = translations.into_iter().find(|t| t.locale == locale) {
+            self.name = translation.name;
+            self.description = translation.description;
+        }
+    }
+}
+
+/// Builds products for unit tests, starting from a published helmet at
+/// 50.00 USD without discount or tracked stock
+#[cfg(test)]
+pub struct ProductBuilder(Product);
+
+#[cfg(test)]
+impl ProductBuilder {
+    pub fn new() -> Self {
+        Self(Product {
+            _id: Uuid::new_v4(),
+            sku: None,
+            name: "Helmet".to_string(),
+            price: Money::new(5_000, crate::models::money::Currency::Usd),
+            description: String::new(),
+            translations: vec![],
+            images: vec![],
+            discount: 0,
+            category: 0,
+            attributes: Default::default(),
+            stock: None,
+            status: ProductStatus::Published,
+            deleted_at: None,
+            version: 1,
+        })
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.0.name = name.to_string();
+        self
+    }
+
+    /// Price in minor units of USD
+    pub fn price(mut self, amount: u64) -> Self {
+        self.0.price = Money::new(amount, crate::models::money::Currency::Usd);
+        self
+    }
+
+    pub fn discount(mut self, discount: u8) -> Self {
+        self.0.discount = discount;
+        self
+    }
+
+    pub fn category(mut self, category: u8) -> Self {
+        self.0.category = category;
+        self
+    }
+
+    pub fn stock(mut self, stock: Option<u32>) -> Self {
+        self.0.stock = stock;
+        self
+    }
+
+    pub fn status(mut self, status: ProductStatus) -> Self {
+        self.0.status = status;
+        self
+    }
+
+    pub fn deleted_at(mut self, deleted_at: Option<i64>) -> Self {
+        self.0.deleted_at = deleted_at;
+        self
+    }
+
+    pub fn build(self) -> Product {
+        self.0
+    }
+}
+
+#[cfg(test)]
+impl Default for ProductBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::money::Currency;
+
+    #[test]
+    fn test_localize_falls_back_to_the_default_text() {
+        let mut product = Product {
+            _id: Uuid::new_v4(),
+            sku: None,
+            name: "Шолом".to_string(),
+            price: Money::new(5_000, Currency::Uah),
+            description: "Дорожній шолом".to_string(),
+            translations: vec![ProductTranslation {
+                locale: Locale::En,
+                name: "Helmet".to_string(),
+                description: "Road helmet".to_string(),
+            }],
+            images: vec![],
+            discount: 0,
+            category: 0,
+            attributes: Default::default(),
+            stock: None,
+            status: ProductStatus::Published,
+            deleted_at: None,
+            version: 1,
+        };
+
+        let mut english = product.clone();
+        english.localize(Locale::En);
+        assert_eq!(english.name, "Helmet");
+        assert_eq!(english.description, "Road helmet");
+        assert!(english.translations.is_empty());
+
+        product.localize(Locale::Uk);
+        assert_eq!(product.name, "Шолом");
+        assert!(product.translations.is_empty());
+    }
 }
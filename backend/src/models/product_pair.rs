@@ -0,0 +1,106 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Number of orders that contained both products. Every pair is stored in
+/// both directions so lookups only need `product_id`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProductPair {
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    pub product_id: String,
+    pub related_id: String,
+    pub count: u32,
+}
+
+/// Every ordered pair of distinct products in one order
+pub fn order_pairs(products_id: &[String]) -> Vec<(String, String)> {
+    let mut seen = HashSet::new();
+    let distinct: Vec<&String> = products_id
+        .iter()
+        .filter(|id| seen.insert(id.as_str()))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for a in &distinct {
+        for b in &distinct {
+            if a != b {
+                pairs.push((a.to_string(), b.to_string()));
+            }
+        }
+    }
+
+    pairs
+}
+
+/// Pairs of a whole order history, each order given as its product ids
+pub fn count_pairs(orders: impl IntoIterator<Item = Vec<String>>) -> Vec<ProductPair> {
+    let mut counts: HashMap<(String, String), u32> = HashMap::new();
+    for products_id in orders {
+        for pair in order_pairs(&products_id) {
+            *counts.entry(pair).or_default() += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|((product_id, related_id), count)| ProductPair {
+            _id: Uuid::new_v4(),
+            product_id,
+            related_id,
+            count,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_pairs_go_both_ways() {
+        let pairs = order_pairs(&ids(&["a", "b"]));
+
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".to_string(), "b".to_string()),
+                ("b".to_string(), "a".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repeated_products_count_once() {
+        let pairs = order_pairs(&ids(&["a", "b", "a", "c"]));
+
+        assert_eq!(pairs.len(), 6);
+        assert!(!pairs.iter().any(|(x, y)| x == y));
+    }
+
+    #[test]
+    fn test_single_product_has_no_pairs() {
+        assert!(order_pairs(&ids(&["a", "a"])).is_empty());
+    }
+
+    #[test]
+    fn test_pairs_are_counted_per_order() {
+        let pairs = count_pairs([ids(&["a", "b"]), ids(&["a", "b", "a"]), ids(&["b", "c"])]);
+
+        let count = |x: &str, y: &str| {
+            pairs
+                .iter()
+                .find(|p| p.product_id == x && p.related_id == y)
+                .map(|p| p.count)
+        };
+        assert_eq!(pairs.len(), 4);
+        assert_eq!(count("a", "b"), Some(2));
+        assert_eq!(count("c", "b"), Some(1));
+        assert_eq!(count("a", "c"), None);
+    }
+}
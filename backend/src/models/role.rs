@@ -8,3 +8,34 @@ pub enum Role {
     Admin,
     User,
 }
+
+/// Access beyond a user's own data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Read orders placed by any customer
+    OrderReadAll,
+}
+
+impl Role {
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::Admin => &[Permission::OrderReadAll],
+            Role::User => &[],
+        }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_admins_read_every_order() {
+        assert!(Role::Admin.has_permission(Permission::OrderReadAll));
+        assert!(!Role::User.has_permission(Permission::OrderReadAll));
+    }
+}
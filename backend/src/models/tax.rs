@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::money::Money;
+
+/// Whether catalog prices already include tax
+#[derive(TS, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema, Default)]
+#[ts(export, export_to = "../../db_types/TaxMode.d.ts")]
+pub enum TaxMode {
+    /// Tax is added on top of the prices
+    #[default]
+    Exclusive,
+    /// Tax is part of the prices and only broken out
+    Inclusive,
+}
+
+/// Rate charged in a country, optionally narrowed to one region and one
+/// product category. The most specific rule matching a line wins.
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/TaxRule.d.ts")]
+pub struct TaxRule {
+    #[ts(type = "string")]
+    #[schema(value_type = String)]
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    pub _id: Uuid,
+    /// ISO 3166-1 alpha-2 code, upper case
+    #[schema(example = "US")]
+    pub country: String,
+    /// State or province code, upper case; `null` covers the whole country
+    #[schema(example = "CA")]
+    pub region: Option<String>,
+    /// `null` covers every category and bundles
+    pub category: Option<u8>,
+    /// Shown on the tax line
+    #[schema(example = "Sales tax")]
+    pub name: String,
+    /// Basis points, 725 is 7.25%
+    #[schema(example = 725)]
+    pub rate: u32,
+    /// Unix timestamp (seconds)
+    #[ts(type = "number")]
+    pub updated_at: i64,
+    /// ID of the admin who last set the rule
+    pub updated_by: String,
+}
+
+impl TaxRule {
+    /// Whether the rule applies to a line of `category` shipped to
+    /// `country` and `region`. Bundles have no category.
+    pub fn matches(&self, country: &str, region: Option<&str>, category: Option<u8>) -> bool {
+        self.country == country
+            && self.region.as_deref().is_none_or(|r| Some(r) == region)
+            && self.category.is_none_or(|c| Some(c) == category)
+    }
+
+    /// A region is more specific than a category
+    pub fn specificity(&self) -> u8 {
+        (self.region.is_some() as u8) * 2 + self.category.is_some() as u8
+    }
+
+    /// Tax on `net`, rounded half up
+    pub fn tax_on_net(&self, net: Money) -> Money {
+        let tax = (u128::from(net.amount) * u128::from(self.rate) + 5_000) / 10_000;
+        Money::new(tax as u64, net.currency)
+    }
+
+    /// Tax contained in `gross`, rounded half up
+    pub fn tax_in_gross(&self, gross: Money) -> Money {
+        let divisor = 10_000 + u128::from(self.rate);
+        let tax = (u128::from(gross.amount) * u128::from(self.rate) + divisor / 2) / divisor;
+        Money::new(tax as u64, gross.currency)
+    }
+}
+
+/// Tax charged under one rule, summed over the lines it applies to
+#[derive(TS, Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+#[ts(export, export_to = "../../db_types/TaxLine.d.ts")]
+pub struct TaxLine {
+    pub rule_id: String,
+    #[schema(example = "Sales tax")]
+    pub name: String,
+    pub country: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub category: Option<u8>,
+    /// Basis points at purchase time
+    pub rate: u32,
+    /// Amount the rate was applied to, without tax
+    pub taxable: Money,
+    pub amount: Money,
+}
+
+#[cfg(test)]
+impl TaxRule {
+    /// US sales tax for tests; `rate` in basis points
+    pub fn us(region: Option<&str>, category: Option<u8>, rate: u32) -> TaxRule {
+        TaxRule {
+            _id: Uuid::new_v4(),
+            country: "US".to_string(),
+            region: region.map(str::to_string),
+            category,
+            name: "Sales tax".to_string(),
+            rate,
+            updated_at: 0,
+            updated_by: "admin".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::money::Currency;
+
+    #[test]
+    fn test_rules_match_their_scope_only() {
+        let country = TaxRule::us(None, None, 500);
+        let state = TaxRule::us(Some("CA"), None, 725);
+        let bikes = TaxRule::us(Some("CA"), Some(0), 0);
+
+        assert!(country.matches("US", Some("NY"), Some(1)));
+        assert!(country.matches("US", None, None));
+        assert!(!country.matches("DE", None, None));
+        assert!(state.matches("US", Some("CA"), None));
+        assert!(!state.matches("US", None, None));
+        assert!(bikes.matches("US", Some("CA"), Some(0)));
+        assert!(!bikes.matches("US", Some("CA"), None));
+
+        assert!(bikes.specificity() > state.specificity());
+        assert!(state.specificity() > TaxRule::us(None, Some(0), 0).specificity());
+    }
+
+    #[test]
+    fn test_tax_is_rounded_half_up() {
+        let rule = TaxRule::us(None, None, 2_000);
+
+        assert_eq!(
+            rule.tax_on_net(Money::new(1_003, Currency::Eur)),
+            Money::new(201, Currency::Eur)
+        );
+        assert_eq!(
+            rule.tax_in_gross(Money::new(12_000, Currency::Eur)),
+            Money::new(2_000, Currency::Eur)
+        );
+        assert_eq!(
+            rule.tax_in_gross(Money::new(1_003, Currency::Eur)),
+            Money::new(167, Currency::Eur)
+        );
+    }
+}
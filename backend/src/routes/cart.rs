@@ -0,0 +1,38 @@
+use actix_web::web;
+
+use crate::{
+    controllers::cart_controller,
+    middleware::{auth::JwtMiddleware, idempotency::Idempotency},
+};
+
+/// Guests and signed-in users share the cart routes, only checkout needs an
+/// account
+pub fn init() -> impl actix_web::dev::HttpServiceFactory {
+    web::scope("/cart")
+        .service(
+            web::resource("/checkout")
+                .wrap(Idempotency)
+                .wrap(JwtMiddleware)
+                .route(web::post().to(cart_controller::checkout)),
+        )
+        .route("", web::get().to(cart_controller::get_cart))
+        .route("", web::delete().to(cart_controller::clear_cart))
+        .route("/items", web::post().to(cart_controller::add_cart_item))
+        .route(
+            "/items/{product_id}",
+            web::put().to(cart_controller::update_cart_item),
+        )
+        .route(
+            "/items/{product_id}",
+            web::delete().to(cart_controller::remove_cart_item),
+        )
+        .route("/bundles", web::post().to(cart_controller::add_cart_bundle))
+        .route(
+            "/bundles/{bundle_id}",
+            web::put().to(cart_controller::update_cart_bundle),
+        )
+        .route(
+            "/bundles/{bundle_id}",
+            web::delete().to(cart_controller::remove_cart_bundle),
+        )
+}
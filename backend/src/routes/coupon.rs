@@ -0,0 +1,33 @@
+use actix_web::web;
+
+use crate::{
+    controllers::coupon_controller,
+    middleware::{auth::JwtMiddleware, permissions::PermissionCheck},
+    models::role::Role,
+};
+
+pub fn init() -> impl actix_web::dev::HttpServiceFactory {
+    web::scope("/coupon")
+        .service(
+            web::scope("/admin")
+                .wrap(PermissionCheck::new(Role::Admin))
+                .wrap(JwtMiddleware)
+                .route("/create", web::post().to(coupon_controller::create_coupon))
+                .route(
+                    "/coupons",
+                    web::get().to(coupon_controller::get_all_coupons),
+                )
+                .route(
+                    "/delete/{id}",
+                    web::delete().to(coupon_controller::delete_coupon),
+                )
+                .route(
+                    "/redemptions/{id}",
+                    web::get().to(coupon_controller::get_redemptions),
+                ),
+        )
+        .service(web::scope("").wrap(JwtMiddleware).route(
+            "/validate",
+            web::post().to(coupon_controller::validate_coupon),
+        ))
+}
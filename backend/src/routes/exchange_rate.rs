@@ -0,0 +1,25 @@
+use actix_web::web;
+
+use crate::{
+    controllers::exchange_rate_controller,
+    middleware::{auth::JwtMiddleware, permissions::PermissionCheck},
+    models::role::Role,
+};
+
+pub fn init() -> impl actix_web::dev::HttpServiceFactory {
+    web::scope("/exchange_rate")
+        .service(
+            web::scope("/admin")
+                .wrap(PermissionCheck::new(Role::Admin))
+                .wrap(JwtMiddleware)
+                .route("/set", web::post().to(exchange_rate_controller::set_rate))
+                .route(
+                    "/history",
+                    web::get().to(exchange_rate_controller::get_rate_history),
+                ),
+        )
+        .service(web::scope("").route(
+            "/rates",
+            web::get().to(exchange_rate_controller::get_current_rates),
+        ))
+}
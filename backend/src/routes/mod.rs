@@ -1,13 +1,23 @@
 use actix_web::web;
 
 pub mod auth;
+pub mod cart;
+pub mod coupon;
+pub mod exchange_rate;
 pub mod order;
+pub mod payment;
 pub mod product;
+pub mod tax;
 pub mod user;
 
 pub fn init(cfg: &mut web::ServiceConfig) {
     cfg.service(product::init());
     cfg.service(order::init());
+    cfg.service(cart::init());
+    cfg.service(coupon::init());
+    cfg.service(payment::init());
     cfg.service(auth::init());
     cfg.service(user::init());
+    cfg.service(exchange_rate::init());
+    cfg.service(tax::init());
 }
@@ -1,6 +1,6 @@
 use crate::{
     controllers::order_controller,
-    middleware::{auth::JwtMiddleware, permissions::PermissionCheck},
+    middleware::{auth::JwtMiddleware, idempotency::Idempotency, permissions::PermissionCheck},
     models::role::Role,
 };
 use actix_web::web;
@@ -9,10 +9,27 @@ pub fn init() -> impl actix_web::dev::HttpServiceFactory {
     web::scope("/order")
         .service(
             web::scope("/admin")
+                .wrap(Idempotency)
                 .wrap(PermissionCheck::new(Role::Admin))
                 .wrap(JwtMiddleware)
                 .route("/orders", web::get().to(order_controller::get_all_orders))
-                .route("/update", web::put().to(order_controller::update_order))
+                .route(
+                    "/status/{id}",
+                    web::put().to(order_controller::change_order_status),
+                )
+                .route(
+                    "/refund/{id}",
+                    web::post().to(order_controller::refund_order),
+                )
+                .route(
+                    "/refunds",
+                    web::get().to(order_controller::get_refund_ledger),
+                )
+                .route(
+                    "/refunds/export",
+                    web::get().to(order_controller::export_refund_ledger),
+                )
+                .route("/export", web::get().to(order_controller::export_orders))
                 .route(
                     "/delete/{id}",
                     web::delete().to(order_controller::delete_order),
@@ -20,8 +37,13 @@ pub fn init() -> impl actix_web::dev::HttpServiceFactory {
         )
         .service(
             web::scope("")
+                .wrap(Idempotency)
                 .wrap(JwtMiddleware)
                 .route("/create", web::post().to(order_controller::create_order))
+                .route(
+                    "/cancel/{id}",
+                    web::post().to(order_controller::cancel_order),
+                )
                 .route("/{id}", web::get().to(order_controller::get_order)),
         )
 }
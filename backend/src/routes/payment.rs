@@ -0,0 +1,23 @@
+use actix_web::web;
+
+use crate::{
+    controllers::payment_controller,
+    middleware::{auth::JwtMiddleware, idempotency::Idempotency, permissions::PermissionCheck},
+    models::role::Role,
+};
+
+pub fn init() -> impl actix_web::dev::HttpServiceFactory {
+    web::scope("/payment")
+        // Called by the provider, authenticated by its signature
+        .route("/webhook", web::post().to(payment_controller::webhook))
+        .service(
+            web::scope("/admin")
+                .wrap(PermissionCheck::new(Role::Admin))
+                .wrap(JwtMiddleware)
+                .route("/reconcile", web::post().to(payment_controller::reconcile)),
+        )
+        .service(web::scope("").wrap(Idempotency).wrap(JwtMiddleware).route(
+            "/intent/{order_id}",
+            web::post().to(payment_controller::create_intent),
+        ))
+}
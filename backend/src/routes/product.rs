@@ -1,10 +1,15 @@
 use crate::{
-    controllers::product_controller,
+    controllers::{
+        attribute_controller, bundle_controller, campaign_controller, product_controller,
+    },
     middleware::{auth::JwtMiddleware, permissions::PermissionCheck},
     models::role::Role,
 };
 use actix_web::{web, Scope};
 
+/// Catalog uploads are far larger than regular JSON bodies
+const IMPORT_PAYLOAD_LIMIT: usize = 10 * 1024 * 1024;
+
 pub fn init() -> Scope {
     web::scope("/product")
         .service(
@@ -19,6 +24,77 @@ pub fn init() -> Scope {
                 .route(
                     "/delete/{id}",
                     web::delete().to(product_controller::delete_product),
+                )
+                .route(
+                    "/products",
+                    web::get().to(product_controller::get_admin_products),
+                )
+                .route(
+                    "/analytics",
+                    web::get().to(product_controller::get_product_analytics),
+                )
+                .route(
+                    "/status/{id}",
+                    web::put().to(product_controller::change_product_status),
+                )
+                .route("/stock/{id}", web::put().to(product_controller::set_stock))
+                .route(
+                    "/translations/{id}/{locale}",
+                    web::put().to(product_controller::set_translation),
+                )
+                .route(
+                    "/translations/{id}/{locale}",
+                    web::delete().to(product_controller::delete_translation),
+                )
+                .route(
+                    "/restore/{id}",
+                    web::post().to(product_controller::restore_product),
+                )
+                .service(
+                    web::resource("/import")
+                        .app_data(web::PayloadConfig::new(IMPORT_PAYLOAD_LIMIT))
+                        .route(web::post().to(product_controller::import_products)),
+                )
+                .route(
+                    "/export",
+                    web::get().to(product_controller::export_products),
+                )
+                .route(
+                    "/attributes/{category}",
+                    web::put().to(attribute_controller::set_schema),
+                )
+                .route(
+                    "/related/rebuild",
+                    web::post().to(product_controller::rebuild_related),
+                )
+                .route(
+                    "/price_history/{id}",
+                    web::get().to(product_controller::get_price_history),
+                )
+                .route(
+                    "/bundles",
+                    web::get().to(bundle_controller::get_admin_bundles),
+                )
+                .route("/bundles", web::post().to(bundle_controller::create_bundle))
+                .route(
+                    "/bundles/{id}",
+                    web::put().to(bundle_controller::update_bundle),
+                )
+                .route(
+                    "/bundles/{id}",
+                    web::delete().to(bundle_controller::delete_bundle),
+                )
+                .route(
+                    "/campaigns",
+                    web::get().to(campaign_controller::get_all_campaigns),
+                )
+                .route(
+                    "/campaigns/create",
+                    web::post().to(campaign_controller::create_campaign),
+                )
+                .route(
+                    "/campaigns/delete/{id}",
+                    web::delete().to(campaign_controller::delete_campaign),
                 ),
         )
         .service(
@@ -32,6 +108,25 @@ pub fn init() -> Scope {
                     "/products",
                     web::get().to(product_controller::get_all_products),
                 )
+                .route("/deals", web::get().to(product_controller::get_deals))
+                .route("/bundles", web::get().to(bundle_controller::get_bundles))
+                .route(
+                    "/bundles/{id}",
+                    web::get().to(bundle_controller::get_bundle),
+                )
+                .route(
+                    "/compare",
+                    web::get().to(product_controller::compare_products),
+                )
+                .route("/facets", web::get().to(attribute_controller::get_facets))
+                .route(
+                    "/attributes/{category}",
+                    web::get().to(attribute_controller::get_schema),
+                )
+                .route(
+                    "/{id}/related",
+                    web::get().to(product_controller::get_related_products),
+                )
                 .route("/{id}", web::get().to(product_controller::get_product)),
         )
 }
@@ -0,0 +1,21 @@
+use actix_web::web;
+
+use crate::{
+    controllers::tax_controller,
+    middleware::{auth::JwtMiddleware, permissions::PermissionCheck},
+    models::role::Role,
+};
+
+pub fn init() -> impl actix_web::dev::HttpServiceFactory {
+    web::scope("/tax").service(
+        web::scope("/admin")
+            .wrap(PermissionCheck::new(Role::Admin))
+            .wrap(JwtMiddleware)
+            .route("/rules", web::get().to(tax_controller::get_tax_rules))
+            .route("/rules/set", web::post().to(tax_controller::set_tax_rule))
+            .route(
+                "/rules/delete/{id}",
+                web::delete().to(tax_controller::delete_tax_rule),
+            ),
+    )
+}
@@ -1,7 +1,7 @@
 use actix_web::web;
 
 use crate::{
-    controllers::user_controller,
+    controllers::{alert_controller, user_controller, wishlist_controller},
     middleware::{auth::JwtMiddleware, permissions::PermissionCheck},
     models::role::Role,
 };
@@ -20,6 +20,36 @@ pub fn init() -> impl actix_web::dev::HttpServiceFactory {
                 .route("/me", web::get().to(user_controller::me))
                 .route("/update", web::put().to(user_controller::update_user))
                 .route("/delete", web::delete().to(user_controller::delete_user))
-                .route("/my_orders", web::get().to(user_controller::get_my_orders)),
+                .route("/my_orders", web::get().to(user_controller::get_my_orders))
+                .route(
+                    "/wishlist",
+                    web::get().to(wishlist_controller::get_wishlist),
+                )
+                .route(
+                    "/wishlist/{product_id}",
+                    web::post().to(wishlist_controller::add_to_wishlist),
+                )
+                .route(
+                    "/wishlist/{product_id}",
+                    web::delete().to(wishlist_controller::remove_from_wishlist),
+                )
+                .route(
+                    "/wishlist/{product_id}/move_to_cart",
+                    web::post().to(wishlist_controller::move_to_cart),
+                )
+                .route("/alerts", web::get().to(alert_controller::get_alerts))
+                .route("/alerts", web::post().to(alert_controller::create_alert))
+                .route(
+                    "/alerts/{id}",
+                    web::delete().to(alert_controller::delete_alert),
+                )
+                .route(
+                    "/notifications",
+                    web::get().to(alert_controller::get_notifications),
+                )
+                .route(
+                    "/notifications/{id}/read",
+                    web::post().to(alert_controller::mark_notification_read),
+                ),
         )
 }
@@ -0,0 +1,189 @@
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, Bson};
+use mongodb::Database;
+use uuid::Uuid;
+
+use crate::{
+    dto::alert::CreateAlertDto,
+    errors::AppErrors,
+    models::{
+        alert::{AlertCondition, ProductAlert},
+        product::Product,
+    },
+    services::{exchange_rate_service, notification_service, product_service},
+};
+
+/// Subscribes the user to a published product. A second alert of the same
+/// kind replaces the pending one.
+pub async fn create_alert(
+    db: &Database,
+    user_id: &str,
+    data: CreateAlertDto,
+) -> Result<ProductAlert, AppErrors> {
+    let uuid = Uuid::parse_str(&data.product_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let product = product_service::get_product(db, &uuid.to_string()).await?;
+
+    // Thresholds are compared in the currency the product is priced in
+    let condition = match data.condition {
+        AlertCondition::PriceBelow { price } => AlertCondition::PriceBelow {
+            price: exchange_rate_service::get_rate_table(db)
+                .await?
+                .convert(price, product.price.currency)?,
+        },
+        condition => condition,
+    };
+
+    if condition.is_met(&product) {
+        return Err(AppErrors::BadRequest(match condition {
+            AlertCondition::BackInStock => "The product is in stock".to_string(),
+            AlertCondition::PriceBelow { .. } => {
+                "The price is already at or below the threshold".to_string()
+            }
+        }));
+    }
+
+    let collection = db.collection::<ProductAlert>("product_alerts");
+    let filter = doc! {
+        "user_id": user_id,
+        "product_id": uuid.to_string(),
+        "condition.kind": condition.kind(),
+        "triggered_at": Bson::Null,
+    };
+
+    let alert = ProductAlert {
+        _id: collection
+            .find_one(filter.clone())
+            .await?
+            .map_or_else(Uuid::new_v4, |existing| existing._id),
+        user_id: user_id.to_string(),
+        product_id: uuid.to_string(),
+        condition,
+        created_at: Utc::now().timestamp(),
+        triggered_at: None,
+    };
+
+    collection.replace_one(filter, &alert).upsert(true).await?;
+
+    Ok(alert)
+}
+
+pub async fn get_alerts(db: &Database, user_id: &str) -> Result<Vec<ProductAlert>, AppErrors> {
+    let alerts = db
+        .collection::<ProductAlert>("product_alerts")
+        .find(doc! { "user_id": user_id })
+        .sort(doc! { "created_at": -1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(alerts)
+}
+
+pub async fn delete_alert(
+    db: &Database,
+    user_id: &str,
+    alert_id: &str,
+) -> Result<String, AppErrors> {
+    let uuid = Uuid::parse_str(alert_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let result = db
+        .collection::<ProductAlert>("product_alerts")
+        .delete_one(doc! { "_id": uuid, "user_id": user_id })
+        .await?;
+
+    if result.deleted_count == 0 {
+        return Err(AppErrors::NotFound("Alert".to_string()));
+    }
+
+    Ok(String::from("Alert deleted successfully"))
+}
+
+/// Removes the alerts of a deleted account
+pub async fn delete_alerts(db: &Database, user_id: &str) -> Result<(), AppErrors> {
+    db.collection::<ProductAlert>("product_alerts")
+        .delete_many(doc! { "user_id": user_id })
+        .await?;
+
+    Ok(())
+}
+
+fn message(condition: &AlertCondition, product: &Product) -> notification_service::Message {
+    let (title, body) = match condition {
+        AlertCondition::BackInStock => (
+            format!("{} is back in stock", product.name),
+            format!(
+                "{} is available again. Order it before it sells out.",
+                product.name
+            ),
+        ),
+        AlertCondition::PriceBelow { price } => {
+            let current = product.price.apply_discount(product.discount);
+            (
+                format!("{} dropped to {}", product.name, current),
+                format!(
+                    "{} now costs {}, at or below your target of {}.",
+                    product.name, current, price
+                ),
+            )
+        }
+    };
+
+    notification_service::Message {
+        title,
+        body,
+        product_id: Some(product._id.to_string()),
+    }
+}
+
+/// Fires every pending alert of the product whose condition is now met and
+/// returns how many fired. Unpublished products fire nothing.
+pub async fn evaluate_product(db: &Database, product_id: Uuid) -> Result<usize, AppErrors> {
+    let product = match product_service::get_product(db, &product_id.to_string()).await {
+        Err(AppErrors::NotFound(_)) => return Ok(0),
+        result => result?,
+    };
+
+    let collection = db.collection::<ProductAlert>("product_alerts");
+
+    let pending: Vec<ProductAlert> = collection
+        .find(doc! { "product_id": product_id.to_string(), "triggered_at": Bson::Null })
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut fired = 0;
+    for alert in pending.iter().filter(|a| a.condition.is_met(&product)) {
+        // Claim the alert first so concurrent evaluations notify only once
+        let claimed = collection
+            .update_one(
+                doc! { "_id": alert._id, "triggered_at": Bson::Null },
+                doc! { "$set": { "triggered_at": Utc::now().timestamp() } },
+            )
+            .await?;
+
+        if claimed.modified_count == 1 {
+            notification_service::notify_user(
+                db,
+                &alert.user_id,
+                &message(&alert.condition, &product),
+            )
+            .await?;
+            fired += 1;
+        }
+    }
+
+    Ok(fired)
+}
+
+/// Evaluates the product's alerts in the background after a change
+pub fn spawn_evaluation(db: &Database, product_id: Uuid) {
+    let db = db.clone();
+
+    tokio::spawn(async move {
+        if let Err(e) = evaluate_product(&db, product_id).await {
+            eprintln!("❌ Failed to evaluate alerts for {}: {:?}", product_id, e);
+        }
+    });
+}
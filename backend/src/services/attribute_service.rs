@@ -0,0 +1,238 @@
+use std::collections::{BTreeMap, HashMap};
+
+use actix_web::web;
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::Database;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    dto::attribute::{Facet, FacetValue, FacetsResponse, ProductFilter, SetCategorySchemaDto},
+    errors::AppErrors,
+    models::attribute::{AttributeValue, CategorySchema},
+    services::product_service,
+};
+
+pub async fn find_schema(db: &Database, category: u8) -> Result<Option<CategorySchema>, AppErrors> {
+    let collection = db.collection::<CategorySchema>("category_schemas");
+
+    let schema = collection
+        .find_one(doc! { "category": i32::from(category) })
+        .await?;
+
+    Ok(schema)
+}
+
+pub async fn get_schema(db: &Database, category: u8) -> Result<CategorySchema, AppErrors> {
+    find_schema(db, category)
+        .await?
+        .ok_or_else(|| AppErrors::NotFound("Attribute schema".to_string()))
+}
+
+pub async fn get_all_schemas(db: &Database) -> Result<HashMap<u8, CategorySchema>, AppErrors> {
+    let collection = db.collection::<CategorySchema>("category_schemas");
+
+    let schemas: Vec<CategorySchema> = collection.find(doc! {}).await?.try_collect().await?;
+
+    Ok(schemas
+        .into_iter()
+        .map(|schema| (schema.category, schema))
+        .collect())
+}
+
+/// Replaces the schema of a category. Products already in the category are
+/// not re-checked; they are validated again on their next update.
+pub async fn set_schema(
+    db: &Database,
+    category: u8,
+    data: web::Json<SetCategorySchemaDto>,
+) -> Result<String, AppErrors> {
+    let collection = db.collection::<CategorySchema>("category_schemas");
+
+    let schema = CategorySchema {
+        _id: find_schema(db, category)
+            .await?
+            .map_or_else(Uuid::new_v4, |existing| existing._id),
+        category,
+        attributes: data.into_inner().attributes,
+        updated_at: Utc::now().timestamp(),
+    };
+
+    collection
+        .replace_one(doc! { "category": i32::from(category) }, &schema)
+        .upsert(true)
+        .await?;
+
+    Ok(String::from("Attribute schema saved successfully"))
+}
+
+/// Checks values against the schema of their category, if any. Categories
+/// without a schema only accept products without attributes.
+pub fn check_with(
+    schema: Option<&CategorySchema>,
+    category: u8,
+    values: &BTreeMap<String, AttributeValue>,
+) -> Result<(), String> {
+    match schema {
+        Some(schema) => schema.check(values),
+        None if values.is_empty() => Ok(()),
+        None => Err(format!("Category {} has no attribute schema", category)),
+    }
+}
+
+pub async fn check_attributes(
+    db: &Database,
+    category: u8,
+    values: &BTreeMap<String, AttributeValue>,
+) -> Result<(), AppErrors> {
+    let schema = find_schema(db, category).await?;
+
+    check_with(schema.as_ref(), category, values).map_err(AppErrors::BadRequest)
+}
+
+/// Every way a raw query value may have been stored
+fn value_candidates(raw: &str) -> Vec<Bson> {
+    let mut candidates = vec![Bson::String(raw.to_string())];
+
+    if let Ok(number) = raw.parse::<f64>() {
+        candidates.push(Bson::Double(number));
+    }
+    if let Ok(flag) = raw.parse::<bool>() {
+        candidates.push(Bson::Boolean(flag));
+    }
+
+    candidates
+}
+
+/// Published products matching the category, the search words and every
+/// attribute filter
+pub fn filter_document(filter: &ProductFilter) -> Document {
+    let mut document = product_service::published_filter();
+
+    if let Some(category) = filter.category {
+        document.insert("category", i32::from(category));
+    }
+
+    if let Some(search) = &filter.search {
+        document.insert("$text", doc! { "$search": search });
+    }
+
+    for (key, values) in &filter.attributes {
+        let candidates: Vec<Bson> = values.iter().flat_map(|v| value_candidates(v)).collect();
+        document.insert(format!("attributes.{}", key), doc! { "$in": candidates });
+    }
+
+    document
+}
+
+#[derive(Deserialize)]
+struct FacetKey {
+    key: String,
+    value: AttributeValue,
+}
+
+#[derive(Deserialize)]
+struct FacetGroup {
+    _id: FacetKey,
+    count: u32,
+}
+
+pub async fn get_facets(
+    db: &Database,
+    filter: &ProductFilter,
+) -> Result<FacetsResponse, AppErrors> {
+    let collection = db.collection::<Document>("products");
+    let matching = filter_document(filter);
+
+    let total = collection.count_documents(matching.clone()).await?;
+
+    let pipeline = vec![
+        doc! { "$match": matching },
+        doc! { "$project": { "attribute": { "$objectToArray": { "$ifNull": ["$attributes", {}] } } } },
+        doc! { "$unwind": "$attribute" },
+        doc! {
+            "$group": {
+                "_id": { "key": "$attribute.k", "value": "$attribute.v" },
+                "count": { "$sum": 1 },
+            }
+        },
+        doc! { "$sort": { "_id.key": 1, "_id.value": 1 } },
+    ];
+
+    let groups: Vec<FacetGroup> = collection
+        .aggregate(pipeline)
+        .with_type::<FacetGroup>()
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut values: BTreeMap<String, Vec<FacetValue>> = BTreeMap::new();
+    for group in groups {
+        values.entry(group._id.key).or_default().push(FacetValue {
+            value: group._id.value,
+            count: group.count,
+        });
+    }
+
+    let schema = match filter.category {
+        Some(category) => find_schema(db, category).await?,
+        None => None,
+    };
+
+    let mut facets: Vec<Facet> = values
+        .into_iter()
+        .map(|(key, values)| {
+            let definition = schema.as_ref().and_then(|s| s.definition(&key));
+            Facet {
+                label: definition.map_or_else(|| key.clone(), |d| d.label.clone()),
+                unit: definition.and_then(|d| d.unit.clone()),
+                key,
+                values,
+            }
+        })
+        .collect();
+
+    // Keep the order the schema lists attributes in
+    if let Some(schema) = &schema {
+        facets.sort_by_key(|facet| {
+            schema
+                .attributes
+                .iter()
+                .position(|d| d.key == facet.key)
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    Ok(FacetsResponse { total, facets })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers_and_flags_match_their_stored_type() {
+        assert_eq!(
+            value_candidates("29"),
+            vec![Bson::String("29".to_string()), Bson::Double(29.0)]
+        );
+        assert_eq!(
+            value_candidates("true"),
+            vec![Bson::String("true".to_string()), Bson::Boolean(true)]
+        );
+    }
+
+    #[test]
+    fn test_attributes_require_a_schema() {
+        let mut values = BTreeMap::new();
+        assert!(check_with(None, 0, &values).is_ok());
+
+        values.insert("size".to_string(), AttributeValue::Text("M".to_string()));
+        assert_eq!(
+            check_with(None, 0, &values),
+            Err("Category 0 has no attribute schema".to_string())
+        );
+    }
+}
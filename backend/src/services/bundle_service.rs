@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+
+use actix_web::web;
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, to_bson, Bson};
+use mongodb::Database;
+use uuid::Uuid;
+
+use crate::{
+    dto::bundle::{BundleComponent, BundleDto, BundleView},
+    errors::AppErrors,
+    models::{
+        bundle::{Bundle, BundleItem, BundlePricing},
+        money::{Currency, Money},
+        product::{Product, ProductStatus},
+    },
+    services::{campaign_service, exchange_rate_service, product_service},
+    utils::currency::get_base_currency,
+};
+
+fn parse_ids(items: &[BundleItem]) -> Result<Vec<Uuid>, AppErrors> {
+    items
+        .iter()
+        .map(|item| Uuid::parse_str(&item.product_id).map_err(|_| AppErrors::InvalidUUID))
+        .collect()
+}
+
+/// Every component must exist and not be deleted; fixed prices are stored in
+/// the base currency like product prices
+async fn check_bundle(db: &Database, data: &BundleDto) -> Result<(), AppErrors> {
+    if let BundlePricing::Price { price } = &data.pricing {
+        product_service::ensure_base_currency(price)?;
+    }
+
+    let ids: Vec<Bson> = parse_ids(&data.items)?
+        .into_iter()
+        .map(Bson::from)
+        .collect();
+
+    let found = db
+        .collection::<Product>("products")
+        .count_documents(doc! { "_id": { "$in": &ids }, "deleted_at": Bson::Null })
+        .await?;
+
+    if found as usize != ids.len() {
+        return Err(AppErrors::NotFound("Some products".to_string()));
+    }
+
+    Ok(())
+}
+
+pub async fn create_bundle(db: &Database, data: web::Json<BundleDto>) -> Result<String, AppErrors> {
+    check_bundle(db, &data).await?;
+
+    let data = data.into_inner();
+    let bundle = Bundle {
+        _id: Uuid::new_v4(),
+        name: data.name,
+        description: data.description,
+        images: data.images,
+        items: data.items,
+        pricing: data.pricing,
+        status: data.status.unwrap_or_default(),
+        version: 1,
+    };
+
+    db.collection::<Bundle>("bundles")
+        .insert_one(&bundle)
+        .await?;
+
+    Ok(String::from("Bundle created successfully"))
+}
+
+pub async fn update_bundle(
+    db: &Database,
+    bundle_id: &str,
+    data: web::Json<BundleDto>,
+) -> Result<String, AppErrors> {
+    let uuid = Uuid::parse_str(bundle_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    check_bundle(db, &data).await?;
+
+    let mut update = doc! {
+        "name": &data.name,
+        "description": &data.description,
+        "images": &data.images,
+        "items": to_bson(&data.items)?,
+        "pricing": to_bson(&data.pricing)?,
+    };
+    if let Some(status) = data.status {
+        update.insert("status", to_bson(&status)?);
+    }
+
+    let result = db
+        .collection::<Bundle>("bundles")
+        .update_one(
+            doc! { "_id": uuid },
+            doc! { "$set": update, "$inc": { "version": 1 } },
+        )
+        .await?;
+
+    if result.matched_count == 0 {
+        return Err(AppErrors::NotFound("Bundle".to_string()));
+    }
+
+    Ok(String::from("Bundle updated successfully"))
+}
+
+/// Past orders keep their expanded products, so bundles can be removed for good
+pub async fn delete_bundle(db: &Database, bundle_id: &str) -> Result<String, AppErrors> {
+    let uuid = Uuid::parse_str(bundle_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let result = db
+        .collection::<Bundle>("bundles")
+        .delete_one(doc! { "_id": uuid })
+        .await?;
+
+    if result.deleted_count == 0 {
+        return Err(AppErrors::NotFound("Bundle".to_string()));
+    }
+
+    Ok(String::from("Bundle deleted successfully"))
+}
+
+pub async fn get_admin_bundles(db: &Database) -> Result<Vec<Bundle>, AppErrors> {
+    let bundles = db
+        .collection::<Bundle>("bundles")
+        .find(doc! {})
+        .sort(doc! { "name": 1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(bundles)
+}
+
+/// Prices and availability of a bundle from its current components.
+/// Components that no longer exist are left out and make the bundle
+/// unavailable.
+fn build_view(
+    bundle: Bundle,
+    products: &HashMap<String, Product>,
+) -> Result<BundleView, AppErrors> {
+    let overflow = || AppErrors::BadRequest("Bundle price is out of range".to_string());
+
+    let mut components = Vec::new();
+    let mut components_price: Option<Money> = None;
+    let mut available = true;
+    let mut stock: Option<u32> = None;
+
+    for item in &bundle.items {
+        let Some(product) = products.get(&item.product_id) else {
+            available = false;
+            continue;
+        };
+
+        available &= product.is_available();
+
+        if let Some(units) = product.stock {
+            let kits = units / item.quantity;
+            stock = Some(stock.map_or(kits, |s| s.min(kits)));
+        }
+
+        let line = product
+            .price
+            .apply_discount(product.discount)
+            .checked_mul(item.quantity)
+            .ok_or_else(overflow)?;
+        components_price = Some(match components_price {
+            Some(sum) => sum.checked_add(line).ok_or_else(overflow)?,
+            None => line,
+        });
+
+        components.push(BundleComponent {
+            product: product.clone(),
+            quantity: item.quantity,
+        });
+    }
+
+    let components_price = components_price.unwrap_or_else(|| match bundle.pricing {
+        BundlePricing::Price { price } => Money::zero(price.currency),
+        BundlePricing::Discount { .. } => Money::zero(get_base_currency()),
+    });
+    let price = bundle.pricing.apply(components_price);
+
+    Ok(BundleView {
+        savings: Money::new(
+            components_price.amount.saturating_sub(price.amount),
+            price.currency,
+        ),
+        available: available && bundle.status == ProductStatus::Published && stock != Some(0),
+        bundle,
+        components,
+        components_price,
+        price,
+        stock,
+    })
+}
+
+async fn build_views(db: &Database, bundles: Vec<Bundle>) -> Result<Vec<BundleView>, AppErrors> {
+    let ids: Vec<Bson> = bundles
+        .iter()
+        .flat_map(|bundle| parse_ids(&bundle.items).unwrap_or_default())
+        .map(Bson::from)
+        .collect();
+
+    let mut products: Vec<Product> = db
+        .collection::<Product>("products")
+        .find(doc! { "_id": { "$in": ids } })
+        .await?
+        .try_collect()
+        .await?;
+
+    campaign_service::apply_active_campaigns(db, &mut products).await?;
+
+    let products: HashMap<String, Product> = products
+        .into_iter()
+        .map(|product| (product._id.to_string(), product))
+        .collect();
+
+    bundles
+        .into_iter()
+        .map(|bundle| build_view(bundle, &products))
+        .collect()
+}
+
+/// Published bundles, including ones a component makes unavailable
+pub async fn get_bundles(
+    db: &Database,
+    currency: Option<Currency>,
+) -> Result<Vec<BundleView>, AppErrors> {
+    let bundles: Vec<Bundle> = db
+        .collection::<Bundle>("bundles")
+        .find(doc! { "status": to_bson(&ProductStatus::Published)? })
+        .sort(doc! { "name": 1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut views = build_views(db, bundles).await?;
+    exchange_rate_service::convert_bundles(db, &mut views, currency).await?;
+
+    Ok(views)
+}
+
+async fn find_published(db: &Database, bundle_id: &str) -> Result<Bundle, AppErrors> {
+    let uuid = Uuid::parse_str(bundle_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    db.collection::<Bundle>("bundles")
+        .find_one(doc! { "_id": uuid, "status": to_bson(&ProductStatus::Published)? })
+        .await?
+        .ok_or_else(|| AppErrors::NotFound("Bundle".to_string()))
+}
+
+pub async fn get_bundle(
+    db: &Database,
+    bundle_id: &str,
+    currency: Option<Currency>,
+) -> Result<BundleView, AppErrors> {
+    let bundle = find_published(db, bundle_id).await?;
+
+    let mut views = build_views(db, vec![bundle]).await?;
+    exchange_rate_service::convert_bundles(db, &mut views, currency).await?;
+
+    views
+        .pop()
+        .ok_or_else(|| AppErrors::NotFound("Bundle".to_string()))
+}
+
+/// Views of the ordered bundles, in the base currency, one per id. Fails if
+/// any of them cannot be ordered right now.
+pub async fn orderable_bundles(
+    db: &Database,
+    bundle_ids: &[String],
+) -> Result<Vec<BundleView>, AppErrors> {
+    let mut views = Vec::new();
+
+    for bundle_id in bundle_ids {
+        let bundle = find_published(db, bundle_id).await?;
+        let view = build_views(db, vec![bundle])
+            .await?
+            .pop()
+            .ok_or_else(|| AppErrors::NotFound("Bundle".to_string()))?;
+
+        if !view.available {
+            return Err(AppErrors::BadRequest(format!(
+                "Bundle {} is not available",
+                view.bundle.name
+            )));
+        }
+
+        views.push(view);
+    }
+
+    Ok(views)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::product::ProductBuilder;
+
+    fn kit(
+        parts: &[(&Product, u32)],
+        pricing: BundlePricing,
+    ) -> (Bundle, HashMap<String, Product>) {
+        let bundle = Bundle {
+            _id: Uuid::new_v4(),
+            name: "Commuter kit".to_string(),
+            description: String::new(),
+            images: vec![],
+            items: parts
+                .iter()
+                .map(|(product, quantity)| BundleItem {
+                    product_id: product._id.to_string(),
+                    quantity: *quantity,
+                })
+                .collect(),
+            pricing,
+            status: ProductStatus::Published,
+            version: 1,
+        };
+        let products = parts
+            .iter()
+            .map(|(product, _)| (product._id.to_string(), (*product).clone()))
+            .collect();
+
+        (bundle, products)
+    }
+
+    #[test]
+    fn test_discount_applies_to_discounted_component_prices() {
+        let bike = ProductBuilder::new().price(100_000).discount(10).build();
+        let lock = ProductBuilder::new().price(2_000).build();
+        let (bundle, products) = kit(
+            &[(&bike, 1), (&lock, 2)],
+            BundlePricing::Discount { percent: 5 },
+        );
+
+        let view = build_view(bundle, &products).unwrap();
+
+        assert_eq!(view.components_price.amount, 94_000);
+        assert_eq!(view.price.amount, 89_300);
+        assert_eq!(view.savings.amount, 4_700);
+        assert!(view.available);
+        assert_eq!(view.stock, None);
+    }
+
+    #[test]
+    fn test_stock_is_limited_by_the_scarcest_component() {
+        let bike = ProductBuilder::new().price(100_000).stock(Some(5)).build();
+        let lock = ProductBuilder::new().price(2_000).stock(Some(3)).build();
+        let price = BundlePricing::Price {
+            price: Money::new(99_000, Currency::Usd),
+        };
+
+        let (bundle, products) = kit(&[(&bike, 1), (&lock, 2)], price);
+        let view = build_view(bundle, &products).unwrap();
+        assert_eq!(view.stock, Some(1));
+        assert_eq!(view.savings.amount, 5_000);
+
+        let lock = ProductBuilder::new().price(2_000).stock(Some(1)).build();
+        let (bundle, products) = kit(&[(&bike, 1), (&lock, 2)], price);
+        let view = build_view(bundle, &products).unwrap();
+        assert_eq!(view.stock, Some(0));
+        assert!(!view.available);
+    }
+
+    #[test]
+    fn test_archived_or_missing_components_make_the_bundle_unavailable() {
+        let bike = ProductBuilder::new().price(100_000).build();
+        let mut helmet = ProductBuilder::new().price(5_000).build();
+        helmet.status = ProductStatus::Archived;
+
+        let (bundle, products) = kit(
+            &[(&bike, 1), (&helmet, 1)],
+            BundlePricing::Discount { percent: 5 },
+        );
+        assert!(!build_view(bundle, &products).unwrap().available);
+
+        let (bundle, mut products) = kit(
+            &[(&bike, 1), (&helmet, 1)],
+            BundlePricing::Discount { percent: 5 },
+        );
+        products.remove(&helmet._id.to_string());
+        let view = build_view(bundle, &products).unwrap();
+        assert!(!view.available);
+        assert_eq!(view.components.len(), 1);
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use actix_web::web;
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::Database;
+use redis::aio::ConnectionManager;
+use uuid::Uuid;
+
+use crate::{
+    dto::campaign::CreateCampaignDto,
+    errors::AppErrors,
+    models::{campaign::DiscountCampaign, price_history::PriceChangeSource, product::Product},
+    services::{price_history_service, product_service},
+};
+
+/// Loads a product with its own discount, ignoring running campaigns
+async fn find_base_product(db: &Database, product_id: &str) -> Result<Option<Product>, AppErrors> {
+    let uuid = Uuid::parse_str(product_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let product = db
+        .collection::<Product>("products")
+        .find_one(doc! {"_id": uuid})
+        .await?;
+
+    Ok(product)
+}
+
+fn overlap_error() -> AppErrors {
+    AppErrors::BadRequest("The campaign overlaps an existing campaign for this product".to_string())
+}
+
+/// Whether another campaign of the product runs at some point of `campaign`
+async fn has_overlap(db: &Database, campaign: &DiscountCampaign) -> Result<bool, AppErrors> {
+    let others: Vec<DiscountCampaign> = db
+        .collection::<DiscountCampaign>("campaigns")
+        .find(doc! {
+            "product_id": &campaign.product_id,
+            "_id": { "$ne": campaign._id },
+        })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(others.iter().any(|other| other.overlaps(campaign)))
+}
+
+pub async fn create_campaign(
+    db: &Database,
+    mut redis: ConnectionManager,
+    data: web::Json<CreateCampaignDto>,
+) -> Result<String, AppErrors> {
+    let product = find_base_product(db, &data.product_id)
+        .await?
+        .ok_or_else(|| AppErrors::NotFound("Product".to_string()))?;
+    let collection = db.collection::<DiscountCampaign>("campaigns");
+
+    let campaign = DiscountCampaign {
+        _id: Uuid::new_v4(),
+        product_id: product._id.to_string(),
+        discount: data.discount,
+        starts_at: data.starts_at,
+        ends_at: data.ends_at,
+    };
+
+    if has_overlap(db, &campaign).await? {
+        return Err(overlap_error());
+    }
+
+    collection.insert_one(&campaign).await?;
+
+    // A concurrent request may have inserted an overlapping campaign after
+    // the check above. Whoever sees the other backs out, so at worst both
+    // requests fail and are retried.
+    if has_overlap(db, &campaign).await? {
+        collection.delete_one(doc! {"_id": campaign._id}).await?;
+        return Err(overlap_error());
+    }
+
+    price_history_service::record_price_change(
+        db,
+        &campaign.product_id,
+        product.price,
+        campaign.discount,
+        PriceChangeSource::Campaign,
+        Some(campaign._id.to_string()),
+        campaign.starts_at,
+    )
+    .await?;
+    price_history_service::record_price_change(
+        db,
+        &campaign.product_id,
+        product.price,
+        product.discount,
+        PriceChangeSource::Campaign,
+        Some(campaign._id.to_string()),
+        campaign.ends_at,
+    )
+    .await?;
+
+    product_service::invalidate_product_caches(&mut redis).await;
+
+    Ok(String::from("Campaign created successfully"))
+}
+
+pub async fn get_all_campaigns(db: &Database) -> Result<Vec<DiscountCampaign>, AppErrors> {
+    let collection = db.collection::<DiscountCampaign>("campaigns");
+
+    let cursor = collection
+        .find(doc! {})
+        .sort(doc! { "starts_at": 1 })
+        .await?;
+
+    let campaigns: Vec<DiscountCampaign> = cursor.try_collect().await?;
+
+    Ok(campaigns)
+}
+
+pub async fn delete_campaign(
+    db: &Database,
+    mut redis: ConnectionManager,
+    campaign_id: &str,
+) -> Result<String, AppErrors> {
+    let collection = db.collection::<DiscountCampaign>("campaigns");
+
+    let uuid = Uuid::parse_str(campaign_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let campaign = collection
+        .find_one_and_delete(doc! {"_id": uuid})
+        .await?
+        .ok_or_else(|| AppErrors::NotFound("Campaign".to_string()))?;
+
+    let now = Utc::now().timestamp();
+
+    // Entries that have not taken effect yet are no longer going to happen
+    db.collection::<mongodb::bson::Document>("price_history")
+        .delete_many(doc! {
+            "campaign_id": campaign._id.to_string(),
+            "effective_at": { "$gt": now },
+        })
+        .await?;
+
+    if campaign.is_active_at(now) {
+        if let Some(product) = find_base_product(db, &campaign.product_id).await? {
+            price_history_service::record_price_change(
+                db,
+                &campaign.product_id,
+                product.price,
+                product.discount,
+                PriceChangeSource::Campaign,
+                Some(campaign._id.to_string()),
+                now,
+            )
+            .await?;
+        }
+    }
+
+    product_service::invalidate_product_caches(&mut redis).await;
+
+    Ok(String::from("Campaign deleted successfully"))
+}
+
+pub async fn get_active_campaigns(
+    db: &Database,
+    timestamp: i64,
+) -> Result<Vec<DiscountCampaign>, AppErrors> {
+    let collection = db.collection::<DiscountCampaign>("campaigns");
+
+    let cursor = collection
+        .find(doc! {
+            "starts_at": { "$lte": timestamp },
+            "ends_at": { "$gt": timestamp },
+        })
+        .sort(doc! { "discount": -1 })
+        .await?;
+
+    let campaigns: Vec<DiscountCampaign> = cursor.try_collect().await?;
+
+    Ok(campaigns)
+}
+
+/// Replaces the stored discount with the one from a running campaign
+pub async fn apply_active_campaigns(
+    db: &Database,
+    products: &mut [Product],
+) -> Result<(), AppErrors> {
+    if products.is_empty() {
+        return Ok(());
+    }
+
+    let active = get_active_campaigns(db, Utc::now().timestamp()).await?;
+    apply_campaigns(products, active);
+
+    Ok(())
+}
+
+fn apply_campaigns(products: &mut [Product], active: Vec<DiscountCampaign>) {
+    let discounts: HashMap<String, u8> = active
+        .into_iter()
+        .map(|campaign| (campaign.product_id, campaign.discount))
+        .collect();
+
+    for product in products.iter_mut() {
+        if let Some(discount) = discounts.get(&product._id.to_string()) {
+            product.discount = *discount;
+        }
+    }
+}
+
+/// Seconds until the nearest campaign starts or ends, if any is scheduled
+pub async fn seconds_until_next_boundary(
+    db: &Database,
+    timestamp: i64,
+) -> Result<Option<i64>, AppErrors> {
+    let collection = db.collection::<DiscountCampaign>("campaigns");
+
+    let next_start = collection
+        .find_one(doc! { "starts_at": { "$gt": timestamp } })
+        .sort(doc! { "starts_at": 1 })
+        .await?
+        .map(|campaign| campaign.starts_at);
+
+    let next_end = collection
+        .find_one(doc! { "ends_at": { "$gt": timestamp } })
+        .sort(doc! { "ends_at": 1 })
+        .await?
+        .map(|campaign| campaign.ends_at);
+
+    let next_boundary = match (next_start, next_end) {
+        (Some(start), Some(end)) => Some(start.min(end)),
+        (start, end) => start.or(end),
+    };
+
+    Ok(next_boundary.map(|boundary| boundary - timestamp))
+}
+
+/// Most recent campaign start or end at or before `timestamp`, i.e. the last
+/// time campaign prices changed without anyone editing the catalog
+pub async fn last_boundary_until(db: &Database, timestamp: i64) -> Result<Option<i64>, AppErrors> {
+    let collection = db.collection::<DiscountCampaign>("campaigns");
+
+    let last_start = collection
+        .find_one(doc! { "starts_at": { "$lte": timestamp } })
+        .sort(doc! { "starts_at": -1 })
+        .await?
+        .map(|campaign| campaign.starts_at);
+
+    let last_end = collection
+        .find_one(doc! { "ends_at": { "$lte": timestamp } })
+        .sort(doc! { "ends_at": -1 })
+        .await?
+        .map(|campaign| campaign.ends_at);
+
+    Ok(last_start.max(last_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::product::ProductBuilder;
+
+    fn campaign(product_id: &str, discount: u8, starts_at: i64, ends_at: i64) -> DiscountCampaign {
+        DiscountCampaign {
+            _id: Uuid::new_v4(),
+            product_id: product_id.to_string(),
+            discount,
+            starts_at,
+            ends_at,
+        }
+    }
+
+    #[test]
+    fn test_campaigns_overlap_when_their_windows_intersect() {
+        let running = campaign("p", 10, 100, 200);
+
+        assert!(running.overlaps(&campaign("p", 20, 150, 250)));
+        assert!(running.overlaps(&campaign("p", 20, 120, 180)));
+        assert!(running.overlaps(&campaign("p", 20, 50, 300)));
+        // The end is exclusive, so back-to-back campaigns do not overlap
+        assert!(!running.overlaps(&campaign("p", 20, 200, 300)));
+        assert!(!running.overlaps(&campaign("p", 20, 0, 100)));
+    }
+
+    #[test]
+    fn test_running_campaign_replaces_the_product_discount() {
+        let mut products = vec![
+            ProductBuilder::new().discount(5).build(),
+            ProductBuilder::new().discount(5).build(),
+        ];
+        let active = vec![campaign(&products[0]._id.to_string(), 30, 0, 100)];
+
+        apply_campaigns(&mut products, active);
+
+        assert_eq!(products[0].discount, 30);
+        assert_eq!(products[1].discount, 5);
+    }
+}
@@ -0,0 +1,310 @@
+use std::{collections::HashMap, env};
+
+use actix_web::web;
+use bson::{doc, Bson};
+use futures_util::stream::TryStreamExt;
+use mongodb::Database;
+use redis::{aio::ConnectionManager, AsyncCommands};
+use uuid::Uuid;
+
+use crate::{
+    dto::{
+        bundle::BundleView,
+        cart::{CartView, CheckoutQuery},
+        order::{CreateOrderDto, CreateOrderResponse, OrderBundleDto, OrderItemDto},
+    },
+    errors::AppErrors,
+    models::{
+        cart::{Cart, CartLine, CartOwner},
+        money::Currency,
+        product::Product,
+    },
+    services::{
+        bundle_service, campaign_service, exchange_rate_service, order_service, product_service,
+    },
+    utils::currency::get_base_currency,
+};
+
+/// Most units of one product or bundle a cart line can hold, as in an order
+const MAX_QUANTITY: u32 = 100;
+
+/// Carts nobody touched for this long are dropped
+fn get_cart_ttl_days() -> i64 {
+    env::var("CART_TTL_DAYS")
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(30)
+}
+
+async fn touch(redis: &mut ConnectionManager, key: &str) -> Result<(), AppErrors> {
+    let _: bool = redis
+        .expire(key, get_cart_ttl_days() * 24 * 60 * 60)
+        .await?;
+    Ok(())
+}
+
+/// Adds `quantity` units to the line, keeping it within `MAX_QUANTITY`
+async fn add_line(
+    redis: &mut ConnectionManager,
+    key: &str,
+    field: &str,
+    quantity: u32,
+) -> Result<(), AppErrors> {
+    let total: i64 = redis.hincr(key, field, quantity).await?;
+
+    if total > i64::from(MAX_QUANTITY) {
+        let _: i64 = redis.hset(key, field, MAX_QUANTITY).await?;
+    }
+
+    touch(redis, key).await
+}
+
+pub async fn get_cart(redis: &mut ConnectionManager, owner: &CartOwner) -> Result<Cart, AppErrors> {
+    let fields: HashMap<String, u32> = redis.hgetall(owner.key()).await?;
+
+    Ok(Cart::from_fields(fields))
+}
+
+pub async fn add_item(
+    db: &Database,
+    mut redis: ConnectionManager,
+    owner: &CartOwner,
+    data: OrderItemDto,
+) -> Result<(), AppErrors> {
+    // No product is sold in variants yet
+    if let Some(variant_id) = data.variant_id {
+        return Err(AppErrors::NotFound(format!("Variant {}", variant_id)));
+    }
+
+    let uuid = Uuid::parse_str(&data.product_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let mut filter = product_service::published_filter();
+    filter.insert("_id", uuid);
+
+    if db
+        .collection::<Product>("products")
+        .count_documents(filter)
+        .await?
+        == 0
+    {
+        return Err(AppErrors::NotFound("Product".to_string()));
+    }
+
+    let line = CartLine::Product(uuid.to_string());
+    add_line(&mut redis, &owner.key(), &line.field(), data.quantity).await
+}
+
+pub async fn add_bundle(
+    db: &Database,
+    mut redis: ConnectionManager,
+    owner: &CartOwner,
+    data: OrderBundleDto,
+) -> Result<(), AppErrors> {
+    let uuid = Uuid::parse_str(&data.bundle_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    bundle_service::orderable_bundles(db, &[uuid.to_string()]).await?;
+
+    let line = CartLine::Bundle(uuid.to_string());
+    add_line(&mut redis, &owner.key(), &line.field(), data.quantity).await
+}
+
+/// Sets the quantity of a line already in the cart; 0 removes it
+pub async fn set_quantity(
+    mut redis: ConnectionManager,
+    owner: &CartOwner,
+    line: CartLine,
+    quantity: u32,
+) -> Result<(), AppErrors> {
+    if quantity == 0 {
+        return remove_line(redis, owner, line).await;
+    }
+
+    let key = owner.key();
+    let field = line.field();
+
+    if !redis.hexists::<_, _, bool>(&key, &field).await? {
+        return Err(AppErrors::NotFound("Cart item".to_string()));
+    }
+
+    let _: i64 = redis.hset(&key, &field, quantity).await?;
+
+    touch(&mut redis, &key).await
+}
+
+pub async fn remove_line(
+    mut redis: ConnectionManager,
+    owner: &CartOwner,
+    line: CartLine,
+) -> Result<(), AppErrors> {
+    let removed: i64 = redis.hdel(owner.key(), line.field()).await?;
+
+    if removed == 0 {
+        return Err(AppErrors::NotFound("Cart item".to_string()));
+    }
+
+    Ok(())
+}
+
+pub async fn clear_cart(mut redis: ConnectionManager, owner: &CartOwner) -> Result<(), AppErrors> {
+    let _: i64 = redis.del(owner.key()).await?;
+
+    Ok(())
+}
+
+/// Moves a guest cart into the user's cart, adding up the quantities of
+/// lines both carts have
+pub async fn merge_guest_cart(
+    mut redis: ConnectionManager,
+    token: Uuid,
+    user_id: &str,
+) -> Result<(), AppErrors> {
+    let guest = CartOwner::Guest(token).key();
+    let fields: HashMap<String, u32> = redis.hgetall(&guest).await?;
+
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    let user = CartOwner::User(user_id.to_string()).key();
+    for (field, quantity) in fields {
+        add_line(&mut redis, &user, &field, quantity).await?;
+    }
+
+    let _: i64 = redis.del(&guest).await?;
+
+    Ok(())
+}
+
+/// Prices the cart like an order in the requested currency, or the base
+/// currency when none is requested. Lines that could not be ordered are
+/// listed instead of failing the whole cart.
+pub async fn view_cart(
+    db: &Database,
+    mut redis: ConnectionManager,
+    owner: &CartOwner,
+    currency: Option<Currency>,
+) -> Result<CartView, AppErrors> {
+    let cart = get_cart(&mut redis, owner).await?;
+
+    if !cart.is_empty() {
+        touch(&mut redis, &owner.key()).await?;
+    }
+
+    let uuids: Vec<Bson> = cart
+        .items
+        .iter()
+        .filter_map(|(id, _)| Uuid::parse_str(id).ok())
+        .map(Bson::from)
+        .collect();
+
+    let mut filter = product_service::published_filter();
+    filter.insert("_id", doc! {"$in": uuids});
+
+    let mut products: Vec<Product> = db
+        .collection::<Product>("products")
+        .find(filter)
+        .await?
+        .try_collect()
+        .await?;
+
+    campaign_service::apply_active_campaigns(db, &mut products).await?;
+
+    let mut products: HashMap<String, Product> = products
+        .into_iter()
+        .map(|product| (product._id.to_string(), product))
+        .collect();
+
+    let mut unavailable = Vec::new();
+
+    let mut product_lines: Vec<(Product, u32)> = Vec::new();
+    for (id, quantity) in cart.items {
+        match products.remove(&id) {
+            Some(product) if product.stock.is_none_or(|stock| stock >= quantity) => {
+                product_lines.push((product, quantity))
+            }
+            _ => unavailable.push(id),
+        }
+    }
+
+    let mut bundle_lines: Vec<(BundleView, u32)> = Vec::new();
+    for (id, quantity) in cart.bundles {
+        match bundle_service::orderable_bundles(db, std::slice::from_ref(&id)).await {
+            Ok(mut views) => match views.pop() {
+                Some(view) if view.stock.is_none_or(|stock| stock >= quantity) => {
+                    bundle_lines.push((view, quantity))
+                }
+                _ => unavailable.push(id),
+            },
+            Err(AppErrors::NotFound(_) | AppErrors::BadRequest(_) | AppErrors::InvalidUUID) => {
+                unavailable.push(id)
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let currency = currency.unwrap_or_else(get_base_currency);
+    let table = exchange_rate_service::get_rate_table(db).await?;
+
+    let pricing = order_service::price_order(&product_lines, &bundle_lines, &table, currency)?;
+
+    let mut products: Vec<Product> = product_lines
+        .into_iter()
+        .map(|(product, _)| product)
+        .collect();
+    exchange_rate_service::convert_products(db, &mut products, Some(currency)).await?;
+
+    Ok(CartView {
+        pricing,
+        products,
+        unavailable,
+    })
+}
+
+/// Places an order for everything in the user's cart and empties it
+pub async fn checkout(
+    db: &Database,
+    mut redis: ConnectionManager,
+    user_id: String,
+    currency: Option<Currency>,
+    query: CheckoutQuery,
+) -> Result<CreateOrderResponse, AppErrors> {
+    let owner = CartOwner::User(user_id.clone());
+    let cart = get_cart(&mut redis, &owner).await?;
+
+    if cart.is_empty() {
+        return Err(AppErrors::BadRequest("The cart is empty".to_string()));
+    }
+
+    let data = CreateOrderDto {
+        items: cart
+            .items
+            .into_iter()
+            .map(|(product_id, quantity)| OrderItemDto {
+                product_id,
+                variant_id: None,
+                quantity,
+            })
+            .collect(),
+        bundles: cart
+            .bundles
+            .into_iter()
+            .map(|(bundle_id, quantity)| OrderBundleDto {
+                bundle_id,
+                quantity,
+            })
+            .collect(),
+        coupon_code: query.coupon,
+        country: query.country,
+        region: query.region,
+    };
+
+    let created =
+        order_service::create_order(db, redis.clone(), web::Json(data), user_id, currency).await?;
+
+    // The order is placed either way, a cart left behind can be cleared by hand
+    if let Err(e) = redis.del::<_, i64>(owner.key()).await {
+        eprintln!("❌ Failed to clear the cart after checkout: {:?}", e);
+    }
+
+    Ok(created)
+}
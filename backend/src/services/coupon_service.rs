@@ -0,0 +1,436 @@
+use std::collections::HashMap;
+
+use actix_web::web;
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use mongodb::{bson::doc, error::ErrorKind, error::WriteFailure, Database};
+use uuid::Uuid;
+
+use crate::{
+    dto::{coupon::CreateCouponDto, order::PriceBreakdown},
+    errors::AppErrors,
+    models::{
+        coupon::{AppliedCoupon, Coupon, CouponDiscount, CouponRedemption},
+        exchange_rate::RateTable,
+        money::Money,
+    },
+};
+
+/// Redemptions of one customer, kept apart from the coupon so its document
+/// does not grow with every customer
+fn usage_id(coupon_id: Uuid, user_id: &str) -> String {
+    format!("{}:{}", coupon_id, user_id)
+}
+
+fn used_up() -> AppErrors {
+    AppErrors::BadRequest("The coupon has been used up".to_string())
+}
+
+fn used_by_customer() -> AppErrors {
+    AppErrors::BadRequest("You have already used this coupon".to_string())
+}
+
+pub async fn create_coupon(
+    db: &Database,
+    data: web::Json<CreateCouponDto>,
+) -> Result<String, AppErrors> {
+    let data = data.into_inner();
+    let collection = db.collection::<Coupon>("coupons");
+    let code = Coupon::normalize_code(&data.code);
+
+    if collection.find_one(doc! {"code": &code}).await?.is_some() {
+        return Err(AppErrors::Conflict(format!(
+            "The coupon {} already exists",
+            code
+        )));
+    }
+
+    let coupon = Coupon {
+        _id: Uuid::new_v4(),
+        code,
+        discount: data.discount,
+        min_order_value: data.min_order_value,
+        categories: data.categories,
+        max_uses: data.max_uses,
+        max_uses_per_user: data.max_uses_per_user,
+        uses: 0,
+        starts_at: data.starts_at,
+        ends_at: data.ends_at,
+        created_at: Utc::now().timestamp(),
+    };
+
+    collection.insert_one(&coupon).await?;
+
+    Ok(String::from("Coupon created successfully"))
+}
+
+pub async fn get_all_coupons(db: &Database) -> Result<Vec<Coupon>, AppErrors> {
+    let coupons: Vec<Coupon> = db
+        .collection::<Coupon>("coupons")
+        .find(doc! {})
+        .sort(doc! {"starts_at": -1})
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(coupons)
+}
+
+/// Deletes the coupon; orders that used it keep their discount and the
+/// redemptions stay for reporting
+pub async fn delete_coupon(db: &Database, coupon_id: &str) -> Result<String, AppErrors> {
+    let uuid = Uuid::parse_str(coupon_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let result = db
+        .collection::<Coupon>("coupons")
+        .delete_one(doc! {"_id": uuid})
+        .await?;
+
+    if result.deleted_count == 0 {
+        return Err(AppErrors::NotFound("Coupon".to_string()));
+    }
+
+    Ok(String::from("Coupon deleted successfully"))
+}
+
+/// Every use of the coupon, newest first
+pub async fn get_redemptions(
+    db: &Database,
+    coupon_id: &str,
+) -> Result<Vec<CouponRedemption>, AppErrors> {
+    let uuid = Uuid::parse_str(coupon_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let redemptions: Vec<CouponRedemption> = db
+        .collection::<CouponRedemption>("coupon_redemptions")
+        .find(doc! {"coupon_id": uuid.to_string()})
+        .sort(doc! {"redeemed_at": -1})
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(redemptions)
+}
+
+/// The coupon with this code if it can be used right now
+pub async fn find_active(db: &Database, code: &str, now: i64) -> Result<Coupon, AppErrors> {
+    let coupon = db
+        .collection::<Coupon>("coupons")
+        .find_one(doc! {"code": Coupon::normalize_code(code)})
+        .await?
+        .ok_or_else(|| AppErrors::NotFound("Coupon".to_string()))?;
+
+    if !coupon.is_active_at(now) {
+        return Err(AppErrors::BadRequest(
+            "The coupon is not valid at this time".to_string(),
+        ));
+    }
+
+    Ok(coupon)
+}
+
+/// Tells the customer early that a limit is reached. Only `redeem` is
+/// authoritative, a concurrent order may take the last use in between.
+pub async fn check_usage(db: &Database, coupon: &Coupon, user_id: &str) -> Result<(), AppErrors> {
+    if coupon.max_uses.is_some_and(|max| coupon.uses >= max) {
+        return Err(used_up());
+    }
+
+    if let Some(max) = coupon.max_uses_per_user {
+        let used = db
+            .collection::<mongodb::bson::Document>("coupon_usages")
+            .find_one(doc! {"_id": usage_id(coupon._id, user_id)})
+            .await?
+            .and_then(|usage| usage.get_i64("count").ok())
+            .unwrap_or(0);
+
+        if used >= i64::from(max) {
+            return Err(used_by_customer());
+        }
+    }
+
+    Ok(())
+}
+
+/// Which lines of `pricing` the coupon covers, products first, then bundles
+pub fn covered_lines(
+    coupon: &Coupon,
+    pricing: &PriceBreakdown,
+    categories: &HashMap<String, u8>,
+) -> Vec<bool> {
+    let items = pricing.items.iter().map(|item| {
+        categories
+            .get(&item.product_id)
+            .is_some_and(|category| coupon.covers_category(*category))
+    });
+    let bundles = pricing.bundles.iter().map(|_| coupon.covers_bundles());
+
+    items.chain(bundles).collect()
+}
+
+/// Takes the discount of `coupon` off the lines it covers. `categories` maps
+/// the ordered product ids to their category; bundles are only covered by
+/// coupons without category restrictions.
+pub fn apply_coupon(
+    coupon: &Coupon,
+    pricing: &mut PriceBreakdown,
+    categories: &HashMap<String, u8>,
+    table: &RateTable,
+) -> Result<(), AppErrors> {
+    let currency = pricing.total.currency;
+
+    if let Some(min) = coupon.min_order_value {
+        let min = table.convert(min, currency)?;
+
+        if pricing.total.amount < min.amount {
+            return Err(AppErrors::BadRequest(format!(
+                "The coupon needs an order of at least {}",
+                min
+            )));
+        }
+    }
+
+    let eligible = pricing
+        .items
+        .iter()
+        .map(|item| item.total.amount)
+        .chain(pricing.bundles.iter().map(|bundle| bundle.total.amount))
+        .zip(covered_lines(coupon, pricing, categories))
+        .filter(|(_, covered)| *covered)
+        .map(|(amount, _)| amount)
+        .sum();
+    let eligible = Money::new(eligible, currency);
+
+    if eligible.amount == 0 {
+        return Err(AppErrors::BadRequest(
+            "The coupon does not apply to anything in the order".to_string(),
+        ));
+    }
+
+    let discount = match coupon.discount {
+        CouponDiscount::Percentage { percent } => eligible.discount_amount(percent),
+        CouponDiscount::Fixed { amount } => {
+            let amount = table.convert(amount, currency)?;
+            Money::new(amount.amount.min(eligible.amount), currency)
+        }
+    };
+
+    pricing.total = Money::new(pricing.total.amount - discount.amount, currency);
+    pricing.coupon = Some(AppliedCoupon {
+        coupon_id: coupon._id.to_string(),
+        code: coupon.code.clone(),
+        discount,
+    });
+
+    Ok(())
+}
+
+/// Counts one use of the coupon by the customer. Both limits are checked and
+/// counted in single conditional updates, so concurrent orders cannot
+/// redeem more than allowed.
+pub async fn redeem(db: &Database, coupon: &Coupon, user_id: &str) -> Result<(), AppErrors> {
+    let usages = db.collection::<mongodb::bson::Document>("coupon_usages");
+    let usage_id = usage_id(coupon._id, user_id);
+
+    if let Some(max) = coupon.max_uses_per_user {
+        // A customer at the limit has a document the filter skips, so the
+        // upsert tries to insert a second one and fails on the _id
+        let counted = usages
+            .update_one(
+                doc! {"_id": &usage_id, "count": {"$lt": i64::from(max)}},
+                doc! {"$inc": {"count": 1_i64}},
+            )
+            .upsert(true)
+            .await;
+
+        match counted {
+            Ok(_) => {}
+            Err(e) => match &*e.kind {
+                ErrorKind::Write(WriteFailure::WriteError(write_err))
+                    if write_err.code == 11000 =>
+                {
+                    return Err(used_by_customer());
+                }
+                _ => return Err(e.into()),
+            },
+        }
+    }
+
+    let mut filter = doc! {"_id": coupon._id};
+    if let Some(max) = coupon.max_uses {
+        filter.insert("uses", doc! {"$lt": max});
+    }
+
+    let counted = db
+        .collection::<Coupon>("coupons")
+        .update_one(filter, doc! {"$inc": {"uses": 1}})
+        .await?;
+
+    if counted.matched_count == 0 {
+        if coupon.max_uses_per_user.is_some() {
+            usages
+                .update_one(doc! {"_id": &usage_id}, doc! {"$inc": {"count": -1_i64}})
+                .await?;
+        }
+        return Err(used_up());
+    }
+
+    Ok(())
+}
+
+/// Gives back a use counted by `redeem`, for an order that was not placed or
+/// was cancelled. Nothing happens if the coupon is gone.
+pub async fn release(db: &Database, coupon_id: Uuid, user_id: &str) -> Result<(), AppErrors> {
+    db.collection::<Coupon>("coupons")
+        .update_one(
+            doc! {"_id": coupon_id, "uses": {"$gt": 0}},
+            doc! {"$inc": {"uses": -1}},
+        )
+        .await?;
+
+    // Only coupons with a per-customer limit have usage documents
+    db.collection::<mongodb::bson::Document>("coupon_usages")
+        .update_one(
+            doc! {"_id": usage_id(coupon_id, user_id), "count": {"$gt": 0_i64}},
+            doc! {"$inc": {"count": -1_i64}},
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Records the use of the coupon by a placed order
+pub async fn record_redemption(
+    db: &Database,
+    applied: &AppliedCoupon,
+    order_id: &str,
+    user_id: &str,
+) -> Result<(), AppErrors> {
+    let redemption = CouponRedemption {
+        _id: Uuid::new_v4(),
+        coupon_id: applied.coupon_id.clone(),
+        code: applied.code.clone(),
+        order_id: order_id.to_string(),
+        customer_id: user_id.to_string(),
+        discount: applied.discount,
+        redeemed_at: Utc::now().timestamp(),
+    };
+
+    db.collection::<CouponRedemption>("coupon_redemptions")
+        .insert_one(&redemption)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{money::Currency, order::OrderItem, tax::TaxMode};
+
+    fn table() -> RateTable {
+        RateTable {
+            base: Currency::Usd,
+            rates: HashMap::from([(Currency::Uah, 40.0)]),
+        }
+    }
+
+    fn pricing() -> PriceBreakdown {
+        PriceBreakdown {
+            items: vec![
+                OrderItem::single("bike", 100_000),
+                OrderItem::single("helmet", 5_000),
+            ],
+            bundles: vec![],
+            subtotal: Money::usd(105_000),
+            discount: Money::usd(0),
+            coupon: None,
+            tax_mode: TaxMode::Exclusive,
+            taxes: vec![],
+            tax: Money::usd(0),
+            total: Money::usd(105_000),
+        }
+    }
+
+    fn categories() -> HashMap<String, u8> {
+        HashMap::from([("bike".to_string(), 0), ("helmet".to_string(), 1)])
+    }
+
+    #[test]
+    fn test_percentage_only_covers_listed_categories() {
+        let coupon = Coupon::unlimited(CouponDiscount::Percentage { percent: 10 }, vec![1]);
+        let mut pricing = pricing();
+
+        apply_coupon(&coupon, &mut pricing, &categories(), &table()).unwrap();
+
+        assert_eq!(pricing.coupon.unwrap().discount, Money::usd(500));
+        assert_eq!(pricing.total, Money::usd(104_500));
+    }
+
+    #[test]
+    fn test_fixed_discount_is_capped_at_covered_lines() {
+        let coupon = Coupon::unlimited(
+            CouponDiscount::Fixed {
+                amount: Money::usd(10_000),
+            },
+            vec![1],
+        );
+        let mut pricing = pricing();
+
+        apply_coupon(&coupon, &mut pricing, &categories(), &table()).unwrap();
+
+        assert_eq!(pricing.coupon.unwrap().discount, Money::usd(5_000));
+        assert_eq!(pricing.total, Money::usd(100_000));
+    }
+
+    #[test]
+    fn test_fixed_discount_is_converted_to_the_order_currency() {
+        let coupon = Coupon::unlimited(
+            CouponDiscount::Fixed {
+                amount: Money::usd(1_000),
+            },
+            vec![],
+        );
+        let mut pricing = PriceBreakdown {
+            items: vec![OrderItem {
+                unit_price: Money::new(400_000, Currency::Uah),
+                discount: Money::zero(Currency::Uah),
+                total: Money::new(400_000, Currency::Uah),
+                ..OrderItem::single("helmet", 0)
+            }],
+            bundles: vec![],
+            subtotal: Money::new(400_000, Currency::Uah),
+            discount: Money::zero(Currency::Uah),
+            coupon: None,
+            tax_mode: TaxMode::Exclusive,
+            taxes: vec![],
+            tax: Money::zero(Currency::Uah),
+            total: Money::new(400_000, Currency::Uah),
+        };
+
+        apply_coupon(&coupon, &mut pricing, &categories(), &table()).unwrap();
+
+        assert_eq!(pricing.total, Money::new(360_000, Currency::Uah));
+    }
+
+    #[test]
+    fn test_min_order_value() {
+        let mut coupon = Coupon::unlimited(CouponDiscount::Percentage { percent: 10 }, vec![]);
+        coupon.min_order_value = Some(Money::usd(200_000));
+
+        assert!(apply_coupon(&coupon, &mut pricing(), &categories(), &table()).is_err());
+
+        coupon.min_order_value = Some(Money::usd(105_000));
+
+        assert!(apply_coupon(&coupon, &mut pricing(), &categories(), &table()).is_ok());
+    }
+
+    #[test]
+    fn test_coupon_must_cover_something() {
+        let coupon = Coupon::unlimited(CouponDiscount::Percentage { percent: 10 }, vec![1]);
+        let mut pricing = pricing();
+        pricing.items.truncate(1);
+
+        assert!(apply_coupon(&coupon, &mut pricing, &categories(), &table()).is_err());
+        assert_eq!(pricing.coupon, None);
+    }
+}
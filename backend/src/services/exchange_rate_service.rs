@@ -0,0 +1,346 @@
+use actix_web::web;
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::Database;
+use redis::aio::ConnectionManager;
+use uuid::Uuid;
+
+use crate::{
+    dto::{
+        bundle::BundleView,
+        exchange_rate::SetExchangeRateDto,
+        product::{Deal, DealsResponse},
+        wishlist::WishlistEntry,
+    },
+    errors::AppErrors,
+    models::{
+        exchange_rate::{ExchangeRate, RateTable},
+        money::{Currency, Money},
+        order::Order,
+        product::Product,
+    },
+    utils::{currency::get_base_currency, http_cache},
+};
+
+pub async fn set_rate(
+    db: &Database,
+    mut redis: ConnectionManager,
+    admin_id: String,
+    data: web::Json<SetExchangeRateDto>,
+) -> Result<String, AppErrors> {
+    let base = get_base_currency();
+
+    if data.currency == base {
+        return Err(AppErrors::BadRequest(format!(
+            "{} is the base currency and always has a rate of 1",
+            base
+        )));
+    }
+
+    let rate = ExchangeRate {
+        _id: Uuid::new_v4(),
+        currency: data.currency,
+        rate: data.rate,
+        created_at: Utc::now().timestamp(),
+        created_by: admin_id,
+    };
+
+    db.collection::<ExchangeRate>("exchange_rates")
+        .insert_one(rate)
+        .await?;
+
+    // Converted catalog prices change with the rate
+    http_cache::bump_catalog_version(&mut redis).await;
+
+    Ok(String::from("Exchange rate updated successfully"))
+}
+
+/// The most recent rate for every currency that has one
+pub async fn get_current_rates(db: &Database) -> Result<Vec<ExchangeRate>, AppErrors> {
+    let collection = db.collection::<ExchangeRate>("exchange_rates");
+
+    let cursor = collection
+        .aggregate(vec![
+            doc! { "$sort": { "created_at": -1 } },
+            doc! { "$group": { "_id": "$currency", "latest": { "$first": "$$ROOT" } } },
+            doc! { "$replaceRoot": { "newRoot": "$latest" } },
+            doc! { "$sort": { "currency": 1 } },
+        ])
+        .with_type::<ExchangeRate>()
+        .await?;
+
+    let rates: Vec<ExchangeRate> = cursor.try_collect().await?;
+
+    Ok(rates)
+}
+
+pub async fn get_rate_history(
+    db: &Database,
+    currency: Option<Currency>,
+) -> Result<Vec<ExchangeRate>, AppErrors> {
+    let collection = db.collection::<ExchangeRate>("exchange_rates");
+
+    let filter = match currency {
+        Some(currency) => doc! { "currency": currency.code() },
+        None => doc! {},
+    };
+
+    let cursor = collection
+        .find(filter)
+        .sort(doc! { "created_at": -1 })
+        .await?;
+
+    let history: Vec<ExchangeRate> = cursor.try_collect().await?;
+
+    Ok(history)
+}
+
+pub async fn get_rate_table(db: &Database) -> Result<RateTable, AppErrors> {
+    let rates = get_current_rates(db)
+        .await?
+        .into_iter()
+        .map(|rate| (rate.currency, rate.rate))
+        .collect();
+
+    Ok(RateTable {
+        base: get_base_currency(),
+        rates,
+    })
+}
+
+pub async fn convert_products(
+    db: &Database,
+    products: &mut [Product],
+    currency: Option<Currency>,
+) -> Result<(), AppErrors> {
+    let Some(currency) = currency else {
+        return Ok(());
+    };
+
+    if products.iter().all(|p| p.price.currency == currency) {
+        return Ok(());
+    }
+
+    let table = get_rate_table(db).await?;
+
+    for product in products.iter_mut() {
+        product.price = table.convert(product.price, currency)?;
+    }
+
+    Ok(())
+}
+
+/// Converts the product prices and recomputes savings from the converted price
+/// so each deal stays internally consistent
+pub async fn convert_deals(
+    db: &Database,
+    deals: &mut DealsResponse,
+    currency: Option<Currency>,
+) -> Result<(), AppErrors> {
+    let Some(currency) = currency else {
+        return Ok(());
+    };
+
+    let in_currency = |deal: &Deal| deal.product.price.currency == currency;
+
+    if deals.overall.iter().all(in_currency)
+        && deals
+            .by_category
+            .iter()
+            .all(|c| c.deals.iter().all(in_currency))
+    {
+        return Ok(());
+    }
+
+    let table = get_rate_table(db).await?;
+
+    let all_deals = deals.overall.iter_mut().chain(
+        deals
+            .by_category
+            .iter_mut()
+            .flat_map(|c| c.deals.iter_mut()),
+    );
+
+    for deal in all_deals {
+        let mut product = deal.product.clone();
+        product.price = table.convert(product.price, currency)?;
+        *deal = Deal::from(product);
+    }
+
+    Ok(())
+}
+
+pub async fn convert_wishlist(
+    db: &Database,
+    entries: &mut [WishlistEntry],
+    currency: Option<Currency>,
+) -> Result<(), AppErrors> {
+    let Some(currency) = currency else {
+        return Ok(());
+    };
+
+    let mut products: Vec<&mut Product> = entries
+        .iter_mut()
+        .filter_map(|entry| entry.product.as_mut())
+        .filter(|product| product.price.currency != currency)
+        .collect();
+
+    if products.is_empty() {
+        return Ok(());
+    }
+
+    let table = get_rate_table(db).await?;
+
+    for product in products.iter_mut() {
+        product.price = table.convert(product.price, currency)?;
+    }
+
+    Ok(())
+}
+
+/// Converts component prices and the bundle totals; savings are recomputed
+/// from the converted totals
+pub async fn convert_bundles(
+    db: &Database,
+    views: &mut [BundleView],
+    currency: Option<Currency>,
+) -> Result<(), AppErrors> {
+    let Some(currency) = currency else {
+        return Ok(());
+    };
+
+    if views.iter().all(|v| v.price.currency == currency) {
+        return Ok(());
+    }
+
+    let table = get_rate_table(db).await?;
+
+    for view in views.iter_mut() {
+        for component in view.components.iter_mut() {
+            component.product.price = table.convert(component.product.price, currency)?;
+        }
+        view.components_price = table.convert(view.components_price, currency)?;
+        view.price = table.convert(view.price, currency)?;
+        view.savings = Money::new(
+            view.components_price
+                .amount
+                .saturating_sub(view.price.amount),
+            currency,
+        );
+    }
+
+    Ok(())
+}
+
+/// Converts every amount of the orders. Lines are converted one by one, so
+/// their sum may be a minor unit off the converted total.
+pub async fn convert_orders(
+    db: &Database,
+    orders: &mut [Order],
+    currency: Option<Currency>,
+) -> Result<(), AppErrors> {
+    let Some(currency) = currency else {
+        return Ok(());
+    };
+
+    if orders.iter().all(|o| o.total_price.currency == currency) {
+        return Ok(());
+    }
+
+    let table = get_rate_table(db).await?;
+
+    for order in orders.iter_mut() {
+        convert_order(order, &table, currency)?;
+    }
+
+    Ok(())
+}
+
+/// Goes back to the base currency at the rate the order was paid at, so
+/// amounts in the base currency are exactly what was charged, and only the
+/// step to another currency uses today's rate
+fn convert_order(order: &mut Order, table: &RateTable, to: Currency) -> Result<(), AppErrors> {
+    if order.total_price.currency == to {
+        return Ok(());
+    }
+
+    let rate = table.rate(to)? / order.exchange_rate;
+    let convert = |money: Money| money.convert(to, rate);
+
+    for item in order.items.iter_mut() {
+        item.unit_price = convert(item.unit_price);
+        item.discount = convert(item.discount);
+        item.total = convert(item.total);
+    }
+    for bundle in order.bundles.iter_mut() {
+        bundle.unit_price = convert(bundle.unit_price);
+        bundle.discount = convert(bundle.discount);
+        bundle.total = convert(bundle.total);
+    }
+    for refund in order.refunds.iter_mut() {
+        refund.amount = convert(refund.amount);
+    }
+    order.subtotal = convert(order.subtotal);
+    order.discount = convert(order.discount);
+    if let Some(coupon) = order.coupon.as_mut() {
+        coupon.discount = convert(coupon.discount);
+    }
+    for tax in order.taxes.iter_mut() {
+        tax.taxable = convert(tax.taxable);
+        tax.amount = convert(tax.amount);
+    }
+    if let Some(tax) = order.tax.as_mut() {
+        *tax = convert(*tax);
+    }
+    order.total_price = convert(order.total_price);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::models::{order::OrderStatus, tax::TaxMode};
+
+    fn uah(amount: u64) -> Money {
+        Money::new(amount, Currency::Uah)
+    }
+
+    #[test]
+    fn test_order_is_converted_at_the_rate_it_was_paid_at() {
+        let mut order = Order {
+            _id: Uuid::new_v4(),
+            items: vec![],
+            bundles: vec![],
+            subtotal: uah(400_000),
+            discount: uah(0),
+            coupon: None,
+            tax_mode: TaxMode::Exclusive,
+            taxes: vec![],
+            tax: None,
+            total_price: uah(400_000),
+            exchange_rate: 40.0,
+            customer_id: "customer".to_string(),
+            status: OrderStatus::Paid,
+            history: vec![],
+            refunds: vec![],
+            version: 1,
+        };
+        // The hryvnia has weakened since the order was paid
+        let table = RateTable {
+            base: Currency::Usd,
+            rates: HashMap::from([(Currency::Uah, 50.0), (Currency::Eur, 0.8)]),
+        };
+
+        let mut in_base = order.clone();
+        convert_order(&mut in_base, &table, Currency::Usd).unwrap();
+        assert_eq!(in_base.total_price, Money::new(10_000, Currency::Usd));
+
+        convert_order(&mut order, &table, Currency::Eur).unwrap();
+        assert_eq!(order.total_price, Money::new(8_000, Currency::Eur));
+        assert_eq!(order.subtotal, Money::new(8_000, Currency::Eur));
+    }
+}
@@ -1,4 +1,18 @@
+pub mod alert_service;
+pub mod attribute_service;
 pub mod auth_service;
+pub mod bundle_service;
+pub mod campaign_service;
+pub mod cart_service;
+pub mod coupon_service;
+pub mod exchange_rate_service;
+pub mod notification_service;
 pub mod order_service;
+pub mod payment_service;
+pub mod price_history_service;
+pub mod product_bulk_service;
 pub mod product_service;
+pub mod recommendation_service;
+pub mod tax_service;
 pub mod user_service;
+pub mod wishlist_service;
@@ -0,0 +1,203 @@
+use std::env;
+
+use chrono::Utc;
+use futures_util::{future::BoxFuture, TryStreamExt};
+use mongodb::bson::doc;
+use mongodb::Database;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppErrors,
+    models::{
+        notification::{Notification, OutboxEmail},
+        user::User,
+    },
+};
+
+/// What to tell a user, independent of the channel it goes out on
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub title: String,
+    pub body: String,
+    pub product_id: Option<String>,
+}
+
+/// A channel notifications are delivered through
+pub trait Notifier: Send + Sync {
+    /// Name used in `NOTIFICATION_CHANNELS`
+    fn channel(&self) -> &'static str;
+
+    fn notify<'a>(
+        &'a self,
+        db: &'a Database,
+        user: &'a User,
+        message: &'a Message,
+    ) -> BoxFuture<'a, Result<(), AppErrors>>;
+}
+
+/// Stores the message in the user's in-app inbox
+pub struct InboxNotifier;
+
+impl Notifier for InboxNotifier {
+    fn channel(&self) -> &'static str {
+        "inbox"
+    }
+
+    fn notify<'a>(
+        &'a self,
+        db: &'a Database,
+        user: &'a User,
+        message: &'a Message,
+    ) -> BoxFuture<'a, Result<(), AppErrors>> {
+        Box::pin(async move {
+            let notification = Notification {
+                _id: Uuid::new_v4(),
+                user_id: user._id.to_string(),
+                title: message.title.clone(),
+                body: message.body.clone(),
+                product_id: message.product_id.clone(),
+                created_at: Utc::now().timestamp(),
+                read_at: None,
+            };
+
+            db.collection::<Notification>("notifications")
+                .insert_one(&notification)
+                .await?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Queues an email in the outbox; sending is left to the mailer
+pub struct EmailOutboxNotifier;
+
+impl Notifier for EmailOutboxNotifier {
+    fn channel(&self) -> &'static str {
+        "email"
+    }
+
+    fn notify<'a>(
+        &'a self,
+        db: &'a Database,
+        user: &'a User,
+        message: &'a Message,
+    ) -> BoxFuture<'a, Result<(), AppErrors>> {
+        Box::pin(async move {
+            let email = OutboxEmail {
+                _id: Uuid::new_v4(),
+                to: user.email.clone(),
+                subject: message.title.clone(),
+                body: format!("Hi {},\n\n{}", user.name, message.body),
+                created_at: Utc::now().timestamp(),
+                sent_at: None,
+            };
+
+            db.collection::<OutboxEmail>("email_outbox")
+                .insert_one(&email)
+                .await?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Notifiers for a comma-separated channel list; unknown names are skipped
+fn notifiers_for(channels: &str) -> Vec<Box<dyn Notifier>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+
+    for channel in channels.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        match channel.to_ascii_lowercase().as_str() {
+            "inbox" => notifiers.push(Box::new(InboxNotifier)),
+            "email" => notifiers.push(Box::new(EmailOutboxNotifier)),
+            other => eprintln!("❌ Unknown notification channel: {}", other),
+        }
+    }
+
+    notifiers
+}
+
+pub fn get_notifiers() -> Vec<Box<dyn Notifier>> {
+    notifiers_for(&env::var("NOTIFICATION_CHANNELS").unwrap_or_else(|_| "inbox,email".to_string()))
+}
+
+/// Sends the message through every configured channel. A failing channel
+/// is logged and does not stop the others.
+pub async fn notify_user(db: &Database, user_id: &str, message: &Message) -> Result<(), AppErrors> {
+    let uuid = Uuid::parse_str(user_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let Some(user) = db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": uuid })
+        .await?
+    else {
+        return Ok(());
+    };
+
+    for notifier in get_notifiers() {
+        if let Err(e) = notifier.notify(db, &user, message).await {
+            eprintln!("❌ Failed to notify via {}: {:?}", notifier.channel(), e);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_inbox(db: &Database, user_id: &str) -> Result<Vec<Notification>, AppErrors> {
+    let notifications = db
+        .collection::<Notification>("notifications")
+        .find(doc! { "user_id": user_id })
+        .sort(doc! { "created_at": -1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(notifications)
+}
+
+pub async fn mark_read(
+    db: &Database,
+    user_id: &str,
+    notification_id: &str,
+) -> Result<String, AppErrors> {
+    let uuid = Uuid::parse_str(notification_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let result = db
+        .collection::<Notification>("notifications")
+        .update_one(
+            doc! { "_id": uuid, "user_id": user_id },
+            doc! { "$set": { "read_at": Utc::now().timestamp() } },
+        )
+        .await?;
+
+    if result.matched_count == 0 {
+        return Err(AppErrors::NotFound("Notification".to_string()));
+    }
+
+    Ok(String::from("Notification marked as read"))
+}
+
+/// Removes the inbox of a deleted account
+pub async fn delete_inbox(db: &Database, user_id: &str) -> Result<(), AppErrors> {
+    db.collection::<Notification>("notifications")
+        .delete_many(doc! { "user_id": user_id })
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channels(list: &str) -> Vec<&'static str> {
+        notifiers_for(list).iter().map(|n| n.channel()).collect()
+    }
+
+    #[test]
+    fn test_builds_notifiers_from_channel_list() {
+        assert_eq!(channels("inbox,email"), vec!["inbox", "email"]);
+        assert_eq!(channels(" Email , sms"), vec!["email"]);
+        assert!(channels("").is_empty());
+    }
+}
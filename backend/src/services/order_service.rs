@@ -1,14 +1,39 @@
+use std::collections::HashMap;
+
 use actix_web::web;
-use bson::{to_document, Binary, Bson};
+use bson::{to_bson, Binary, Bson};
+use chrono::Utc;
 use futures_util::stream::TryStreamExt;
 use mongodb::bson::doc;
-use mongodb::Database;
+use mongodb::{options::ReturnDocument, Database};
+use redis::aio::ConnectionManager;
+use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::{
-    dto::order::{CreateOrderDto, UpdateOrderDto},
+    dto::{
+        bundle::BundleView,
+        order::{
+            ChangeOrderStatusDto, CreateOrderDto, CreateOrderResponse, OrderCsvRow, PriceBreakdown,
+            RefundCsvRow, RefundLedgerEntry, RefundOrderDto,
+        },
+    },
     errors::AppErrors,
-    models::{order::Order, product::Product},
+    models::{
+        coupon::Coupon,
+        exchange_rate::RateTable,
+        money::{Currency, Money},
+        order::{Order, OrderBundle, OrderItem, OrderRefund, OrderStatus, OrderStatusChange},
+        product::Product,
+        role::Permission,
+        tax::TaxMode,
+    },
+    services::{
+        bundle_service, campaign_service, coupon_service, exchange_rate_service,
+        payment_service::{self, PaymentProvider},
+        product_service, recommendation_service, tax_service,
+    },
+    utils::{currency::get_base_currency, jwt::Claims},
 };
 
 pub async fn get_all_orders(db: &Database) -> Result<Vec<Order>, AppErrors> {
@@ -23,18 +48,159 @@ pub async fn get_all_orders(db: &Database) -> Result<Vec<Order>, AppErrors> {
     Ok(orders)
 }
 
-pub async fn create_order(
+/// Distinct ids with their summed quantities, in first-seen order
+fn merge_lines<'a>(lines: impl IntoIterator<Item = (&'a String, u32)>) -> Vec<(String, u32)> {
+    let mut merged: Vec<(String, u32)> = Vec::new();
+
+    for (id, quantity) in lines {
+        match merged.iter_mut().find(|(seen, _)| seen == id) {
+            Some((_, total)) => *total = total.saturating_add(quantity),
+            None => merged.push((id.clone(), quantity)),
+        }
+    }
+
+    merged
+}
+
+/// Line discount and line total of `quantity` units
+fn line_amounts(
+    unit_price: Money,
+    unit_discount: Money,
+    quantity: u32,
+) -> Result<(Money, Money), AppErrors> {
+    let overflow = || AppErrors::BadRequest("Order total is out of range".to_string());
+
+    let regular = unit_price.checked_mul(quantity).ok_or_else(overflow)?;
+    let discount = unit_discount.checked_mul(quantity).ok_or_else(overflow)?;
+
+    Ok((
+        discount,
+        Money::new(regular.amount - discount.amount, regular.currency),
+    ))
+}
+
+/// Prices the order in `currency` from catalog data: products with their
+/// effective discount (campaigns applied) and bundle views in the base
+/// currency. Discounts are rounded per unit, as the catalog shows them.
+pub fn price_order(
+    products: &[(Product, u32)],
+    bundles: &[(BundleView, u32)],
+    table: &RateTable,
+    currency: Currency,
+) -> Result<PriceBreakdown, AppErrors> {
+    let overflow = || AppErrors::BadRequest("Order total is out of range".to_string());
+
+    let mut items = Vec::new();
+    for (product, quantity) in products {
+        let unit_price = table.convert(product.price, currency)?;
+        let (discount, total) = line_amounts(
+            unit_price,
+            unit_price.discount_amount(product.discount),
+            *quantity,
+        )?;
+
+        items.push(OrderItem {
+            product_id: product._id.to_string(),
+            variant_id: None,
+            name: product.name.clone(),
+            quantity: *quantity,
+            unit_price,
+            discount,
+            total,
+        });
+    }
+
+    let mut order_bundles = Vec::new();
+    for (view, quantity) in bundles {
+        let price = table.convert(view.price, currency)?;
+        // A fixed price above the parts is still what the bundle costs, it
+        // just comes without a discount
+        let components_price = table.convert(view.components_price, currency)?;
+        let unit_price = Money::new(components_price.amount.max(price.amount), currency);
+        let (discount, total) = line_amounts(
+            unit_price,
+            Money::new(unit_price.amount - price.amount, currency),
+            *quantity,
+        )?;
+
+        order_bundles.push(OrderBundle {
+            bundle_id: view.bundle._id.to_string(),
+            name: view.bundle.name.clone(),
+            quantity: *quantity,
+            unit_price,
+            discount,
+            total,
+            components: view.bundle.items.clone(),
+        });
+    }
+
+    let lines = items
+        .iter()
+        .map(|i| (i.discount, i.total))
+        .chain(order_bundles.iter().map(|b| (b.discount, b.total)));
+
+    let mut discount = Money::zero(currency);
+    let mut total = Money::zero(currency);
+    for (line_discount, line_total) in lines {
+        discount = discount.checked_add(line_discount).ok_or_else(overflow)?;
+        total = total.checked_add(line_total).ok_or_else(overflow)?;
+    }
+
+    Ok(PriceBreakdown {
+        items,
+        bundles: order_bundles,
+        subtotal: total.checked_add(discount).ok_or_else(overflow)?,
+        discount,
+        coupon: None,
+        tax_mode: TaxMode::default(),
+        taxes: vec![],
+        tax: Money::zero(currency),
+        total,
+    })
+}
+
+/// Order priced from the catalog, nothing reserved yet
+pub struct OrderQuote {
+    pub pricing: PriceBreakdown,
+    /// Units of the order currency per one unit of the base currency
+    pub exchange_rate: f64,
+    pub coupon: Option<Coupon>,
+}
+
+/// Prices what the customer asks for at current catalog prices in the
+/// requested currency, or the base currency when none is requested, with the
+/// coupon applied and taxed for the destination
+pub async fn quote_order(
     db: &Database,
-    new_order_data: web::Json<CreateOrderDto>,
-    user_id: String,
-) -> Result<String, AppErrors> {
-    let orders_collection = db.collection::<Order>("orders");
+    data: &CreateOrderDto,
+    user_id: &str,
+    currency: Option<Currency>,
+) -> Result<OrderQuote, AppErrors> {
     let products_collection = db.collection::<Product>("products");
 
-    let uuids: Vec<Bson> = new_order_data
-        .products_id
+    if data.items.is_empty() && data.bundles.is_empty() {
+        return Err(AppErrors::BadRequest("The order is empty".to_string()));
+    }
+
+    // No product is sold in variants yet
+    if let Some(variant_id) = data.items.iter().find_map(|item| item.variant_id.as_ref()) {
+        return Err(AppErrors::NotFound(format!("Variant {}", variant_id)));
+    }
+
+    let product_lines = merge_lines(
+        data.items
+            .iter()
+            .map(|item| (&item.product_id, item.quantity)),
+    );
+    let bundle_lines = merge_lines(
+        data.bundles
+            .iter()
+            .map(|bundle| (&bundle.bundle_id, bundle.quantity)),
+    );
+
+    let uuids: Vec<Bson> = product_lines
         .iter()
-        .filter_map(|id| Uuid::parse_str(id).ok())
+        .filter_map(|(id, _)| Uuid::parse_str(id).ok())
         .map(|uuid| {
             Bson::Binary(Binary {
                 subtype: bson::spec::BinarySubtype::Uuid,
@@ -43,28 +209,189 @@ pub async fn create_order(
         })
         .collect();
 
-    let mut cursor = products_collection
-        .find(doc! { "_id": {"$in": uuids}})
+    let mut filter = product_service::published_filter();
+    filter.insert("_id", doc! {"$in": uuids});
+
+    let mut products: Vec<Product> = products_collection
+        .find(filter)
+        .await?
+        .try_collect()
         .await?;
 
-    let mut products = Vec::new();
-    while let Some(p) = cursor.try_next().await? {
-        products.push(p);
+    if products.len() != product_lines.len() {
+        return Err(AppErrors::NotFound("Some products".to_string()));
     }
 
-    if products.len() != new_order_data.products_id.len() {
-        return Err(AppErrors::NotFound("Some products".to_string()));
+    campaign_service::apply_active_campaigns(db, &mut products).await?;
+
+    let categories: HashMap<String, u8> = products
+        .iter()
+        .map(|product| (product._id.to_string(), product.category))
+        .collect();
+    let mut products: HashMap<String, Product> = products
+        .into_iter()
+        .map(|product| (product._id.to_string(), product))
+        .collect();
+    let product_lines: Vec<(Product, u32)> = product_lines
+        .into_iter()
+        .filter_map(|(id, quantity)| products.remove(&id).map(|p| (p, quantity)))
+        .collect();
+
+    let bundle_ids: Vec<String> = bundle_lines.iter().map(|(id, _)| id.clone()).collect();
+    let bundle_lines: Vec<(BundleView, u32)> = bundle_service::orderable_bundles(db, &bundle_ids)
+        .await?
+        .into_iter()
+        .zip(bundle_lines.iter().map(|(_, quantity)| *quantity))
+        .collect();
+
+    let currency = currency.unwrap_or_else(get_base_currency);
+    let table = exchange_rate_service::get_rate_table(db).await?;
+    let exchange_rate = table.rate(currency)?;
+
+    let mut pricing = price_order(&product_lines, &bundle_lines, &table, currency)?;
+
+    let coupon = match &data.coupon_code {
+        Some(code) => {
+            let coupon = coupon_service::find_active(db, code, Utc::now().timestamp()).await?;
+            coupon_service::check_usage(db, &coupon, user_id).await?;
+            coupon_service::apply_coupon(&coupon, &mut pricing, &categories, &table)?;
+            Some(coupon)
+        }
+        None => None,
+    };
+
+    let country = data
+        .country
+        .as_deref()
+        .map(tax_service::normalize)
+        .or_else(tax_service::get_shop_country);
+    let region = data.region.as_deref().map(tax_service::normalize);
+    let tax_mode = tax_service::get_tax_mode();
+    pricing.tax_mode = tax_mode;
+    if let Some(country) = country {
+        let rules = tax_service::get_rules(db, Some(&country)).await?;
+        tax_service::apply_taxes(
+            &mut pricing,
+            &rules,
+            &country,
+            region.as_deref(),
+            &categories,
+            coupon.as_ref(),
+            tax_mode,
+        )?;
     }
 
+    Ok(OrderQuote {
+        pricing,
+        exchange_rate,
+        coupon,
+    })
+}
+
+/// Places the order at current catalog prices in the requested currency, or
+/// the base currency when none is requested
+pub async fn create_order(
+    db: &Database,
+    mut redis: ConnectionManager,
+    new_order_data: web::Json<CreateOrderDto>,
+    user_id: String,
+    currency: Option<Currency>,
+) -> Result<CreateOrderResponse, AppErrors> {
+    let orders_collection = db.collection::<Order>("orders");
+
+    let OrderQuote {
+        pricing,
+        exchange_rate,
+        coupon,
+    } = quote_order(db, &new_order_data, &user_id, currency).await?;
+
     let order = Order {
         _id: Uuid::new_v4(),
-        products_id: new_order_data.products_id.clone(),
-        total_price: new_order_data.total_price,
+        status: OrderStatus::Pending,
+        history: vec![OrderStatusChange {
+            status: OrderStatus::Pending,
+            changed_at: Utc::now().timestamp(),
+            changed_by: user_id.clone(),
+            note: None,
+        }],
+        refunds: vec![],
+        items: pricing.items.clone(),
+        bundles: pricing.bundles.clone(),
+        subtotal: pricing.subtotal,
+        discount: pricing.discount,
+        coupon: pricing.coupon.clone(),
+        tax_mode: pricing.tax_mode,
+        taxes: pricing.taxes.clone(),
+        tax: Some(pricing.tax),
+        total_price: pricing.total,
         customer_id: user_id,
+        exchange_rate,
+        version: 1,
     };
 
-    orders_collection.insert_one(order).await?;
-    Ok(String::from("Order created successfully"))
+    let products_id = order.unit_product_ids();
+
+    product_service::reserve_stock(db, &mut redis, &products_id).await?;
+
+    if let Some(coupon) = &coupon {
+        if let Err(e) = coupon_service::redeem(db, coupon, &order.customer_id).await {
+            if let Err(e) = product_service::release_stock(db, &mut redis, &products_id).await {
+                eprintln!("❌ Failed to restock after a declined coupon: {:?}", e);
+            }
+            return Err(e);
+        }
+    }
+
+    if let Err(e) = orders_collection.insert_one(&order).await {
+        if let Err(e) = product_service::release_stock(db, &mut redis, &products_id).await {
+            eprintln!("❌ Failed to restock after a failed order: {:?}", e);
+        }
+        if let Some(coupon) = &coupon {
+            if let Err(e) = coupon_service::release(db, coupon._id, &order.customer_id).await {
+                eprintln!("❌ Failed to give back the coupon use: {:?}", e);
+            }
+        }
+        return Err(e.into());
+    }
+
+    if let Some(applied) = &order.coupon {
+        let order_id = order._id.to_string();
+        if let Err(e) =
+            coupon_service::record_redemption(db, applied, &order_id, &order.customer_id).await
+        {
+            eprintln!("❌ Failed to record coupon redemption: {:?}", e);
+        }
+    }
+
+    // Recommendations can be rebuilt later, a failure here must not fail the order
+    if let Err(e) = recommendation_service::record_order(db, &products_id).await {
+        eprintln!("❌ Failed to record product pairs: {:?}", e);
+    }
+
+    Ok(CreateOrderResponse {
+        message: String::from("Order created successfully"),
+        order_id: order._id.to_string(),
+        pricing,
+    })
+}
+
+/// The order if the caller placed it or may read every order. Other
+/// customers' orders are reported as missing so their ids leak nothing.
+pub async fn get_order_for(
+    db: &Database,
+    order_id: &str,
+    claims: &Claims,
+) -> Result<Order, AppErrors> {
+    if claims.role.has_permission(Permission::OrderReadAll) {
+        return get_order(db, order_id).await;
+    }
+
+    let uuid = Uuid::parse_str(order_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    db.collection::<Order>("orders")
+        .find_one(doc! {"_id": uuid, "customer_id": &claims.sub})
+        .await?
+        .ok_or_else(|| AppErrors::NotFound("Order".to_string()))
 }
 
 pub async fn get_order(db: &Database, order_id: &str) -> Result<Order, AppErrors> {
@@ -80,38 +407,625 @@ pub async fn get_order(db: &Database, order_id: &str) -> Result<Order, AppErrors
     Ok(order)
 }
 
-pub async fn update_order(
+/// Gives the money of `refund` back through the payment provider. If that
+/// fails, the order is put back the way it was before the refund was
+/// recorded, so the ledger only holds money that actually went back.
+async fn settle_refund(
     db: &Database,
-    new_order_data: web::Json<UpdateOrderDto>,
-) -> Result<String, AppErrors> {
+    payments: &dyn PaymentProvider,
+    before: &Order,
+    after: &Order,
+    refund: &OrderRefund,
+) -> Result<(), AppErrors> {
+    let Err(e) =
+        payment_service::refund_order_payment(db, payments, &before._id.to_string(), refund.amount)
+            .await
+    else {
+        return Ok(());
+    };
+
+    db.collection::<Order>("orders")
+        .update_one(
+            doc! {"_id": after._id, "version": after.version},
+            doc! {
+                "$set": {
+                    "status": to_bson(&before.status)?,
+                    "history": to_bson(&before.history)?,
+                    "refunds": to_bson(&before.refunds)?,
+                },
+                "$inc": {"version": 1},
+            },
+        )
+        .await?;
+
+    Err(e)
+}
+
+/// Gives back a payment that went through after its order was cancelled.
+/// The refund is put in the ledger before the money moves, so a repeated
+/// payment report finds it and does not refund twice; if the provider
+/// declines, the entry is taken out again.
+pub async fn refund_late_payment(
+    db: &Database,
+    payments: &dyn PaymentProvider,
+    order: Order,
+    intent_id: &str,
+    amount: Money,
+    provider: String,
+) -> Result<Order, AppErrors> {
     let collection = db.collection::<Order>("orders");
 
-    let uuid = Uuid::parse_str(&new_order_data._id).map_err(|_| AppErrors::InvalidUUID)?;
+    let refund = OrderRefund {
+        refund_id: Uuid::new_v4().to_string(),
+        amount,
+        reason: Some("Paid after the order was cancelled".to_string()),
+        refunded_at: Utc::now().timestamp(),
+        refunded_by: format!("payment:{}", provider),
+    };
 
-    let mut update_doc = to_document(&new_order_data)?;
-    update_doc.remove("_id");
+    let updated = collection
+        .find_one_and_update(
+            doc! {"_id": order._id, "version": order.version},
+            doc! {
+                "$push": {"refunds": to_bson(&refund)?},
+                "$inc": {"version": 1},
+            },
+        )
+        .return_document(ReturnDocument::After)
+        .await?
+        .ok_or_else(|| AppErrors::Conflict("The order was changed by someone else".to_string()))?;
 
-    let result = collection
-        .update_one(doc! {"_id": uuid}, doc! {"$set": update_doc})
+    if let Err(e) = payments.refund(intent_id, amount).await {
+        collection
+            .update_one(
+                doc! {"_id": updated._id, "version": updated.version},
+                doc! {
+                    "$pull": {"refunds": {"refund_id": &refund.refund_id}},
+                    "$inc": {"version": 1},
+                },
+            )
+            .await?;
+
+        return Err(e);
+    }
+
+    Ok(updated)
+}
+
+/// Moves `order` to `status` if nobody changed it since it was read.
+/// Cancelling puts the units back into stock, gives back the coupon use and
+/// refunds whatever is left of a paid order.
+async fn transition(
+    db: &Database,
+    redis: &mut ConnectionManager,
+    payments: &dyn PaymentProvider,
+    order: Order,
+    status: OrderStatus,
+    changed_by: String,
+    note: Option<String>,
+) -> Result<Order, AppErrors> {
+    let collection = db.collection::<Order>("orders");
+    let now = Utc::now().timestamp();
+
+    let change = OrderStatusChange {
+        status,
+        changed_at: now,
+        changed_by: changed_by.clone(),
+        note,
+    };
+    let mut push = doc! {"history": to_bson(&change)?};
+
+    let refundable = order.refundable();
+    let refund = (status == OrderStatus::Cancelled
+        && order.status != OrderStatus::Pending
+        && refundable.amount > 0)
+        .then(|| OrderRefund {
+            refund_id: Uuid::new_v4().to_string(),
+            amount: refundable,
+            reason: Some("Order cancelled".to_string()),
+            refunded_at: now,
+            refunded_by: changed_by,
+        });
+    if let Some(refund) = &refund {
+        push.insert("refunds", to_bson(refund)?);
+    }
+
+    // Matching the state read by the caller keeps concurrent transitions from both applying
+    let updated = collection
+        .find_one_and_update(
+            doc! {"_id": order._id, "version": order.version},
+            doc! {
+                "$set": {"status": to_bson(&status)?},
+                "$push": push,
+                "$inc": {"version": 1},
+            },
+        )
+        .return_document(ReturnDocument::After)
+        .await?
+        .ok_or_else(|| AppErrors::Conflict("The order was changed by someone else".to_string()))?;
+
+    if let Some(refund) = &refund {
+        settle_refund(db, payments, &order, &updated, refund).await?;
+    }
+
+    // The order is cancelled either way, a failure here is fixed by hand
+    if status == OrderStatus::Cancelled {
+        if let Err(e) = product_service::release_stock(db, redis, &updated.unit_product_ids()).await
+        {
+            eprintln!("❌ Failed to restock order {}: {:?}", updated._id, e);
+        }
+
+        let coupon_id = updated
+            .coupon
+            .as_ref()
+            .and_then(|applied| Uuid::parse_str(&applied.coupon_id).ok());
+        if let Some(coupon_id) = coupon_id {
+            if let Err(e) = coupon_service::release(db, coupon_id, &updated.customer_id).await {
+                eprintln!(
+                    "❌ Failed to give back the coupon of order {}: {:?}",
+                    updated._id, e
+                );
+            }
+        }
+    }
+
+    Ok(updated)
+}
+
+/// Marks a pending order as paid once its payment went through
+pub async fn mark_paid(
+    db: &Database,
+    mut redis: ConnectionManager,
+    payments: &dyn PaymentProvider,
+    order: Order,
+    provider: String,
+) -> Result<Order, AppErrors> {
+    transition(
+        db,
+        &mut redis,
+        payments,
+        order,
+        OrderStatus::Paid,
+        format!("payment:{}", provider),
+        None,
+    )
+    .await
+}
+
+/// Moves the order to `data.status` if the state machine allows it and the
+/// order is still at `expected_version` (when given), recording who did it
+pub async fn change_status(
+    db: &Database,
+    mut redis: ConnectionManager,
+    payments: &dyn PaymentProvider,
+    order_id: &str,
+    data: ChangeOrderStatusDto,
+    user_id: String,
+    expected_version: Option<u32>,
+) -> Result<Order, AppErrors> {
+    let order = get_order(db, order_id).await?;
+
+    if expected_version.is_some_and(|version| version != order.version) {
+        return Err(AppErrors::Conflict(
+            "The order was changed by someone else".to_string(),
+        ));
+    }
+
+    if !order.status.can_become(data.status) {
+        return Err(AppErrors::BadRequest(format!(
+            "A {:?} order cannot become {:?}",
+            order.status, data.status
+        )));
+    }
+
+    // Otherwise the refund ledger would miss the money
+    if data.status == OrderStatus::Refunded {
+        return Err(AppErrors::BadRequest(
+            "Orders are refunded by issuing a refund".to_string(),
+        ));
+    }
+
+    transition(
+        db,
+        &mut redis,
+        payments,
+        order,
+        data.status,
+        user_id,
+        data.note,
+    )
+    .await
+}
+
+/// Cancels one of the customer's own orders while it is still early enough
+pub async fn cancel_order(
+    db: &Database,
+    mut redis: ConnectionManager,
+    payments: &dyn PaymentProvider,
+    order_id: &str,
+    user_id: String,
+) -> Result<Order, AppErrors> {
+    let uuid = Uuid::parse_str(order_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let order = db
+        .collection::<Order>("orders")
+        .find_one(doc! {"_id": uuid, "customer_id": &user_id})
+        .await?
+        .ok_or_else(|| AppErrors::NotFound("Order".to_string()))?;
+
+    if !order.status.customer_can_cancel() {
+        return Err(AppErrors::BadRequest(format!(
+            "A {:?} order can no longer be cancelled",
+            order.status
+        )));
+    }
+
+    transition(
+        db,
+        &mut redis,
+        payments,
+        order,
+        OrderStatus::Cancelled,
+        user_id,
+        Some("Cancelled by the customer".to_string()),
+    )
+    .await
+}
+
+/// Refunds part of the order, or everything not refunded yet, through the
+/// payment provider. The order becomes Refunded once nothing is left.
+pub async fn refund_order(
+    db: &Database,
+    payments: &dyn PaymentProvider,
+    order_id: &str,
+    data: RefundOrderDto,
+    user_id: String,
+    expected_version: Option<u32>,
+) -> Result<Order, AppErrors> {
+    let collection = db.collection::<Order>("orders");
+
+    let order = get_order(db, order_id).await?;
+
+    if expected_version.is_some_and(|version| version != order.version) {
+        return Err(AppErrors::Conflict(
+            "The order was changed by someone else".to_string(),
+        ));
+    }
+
+    if !order.status.can_become(OrderStatus::Refunded) {
+        return Err(AppErrors::BadRequest(format!(
+            "A {:?} order cannot be refunded",
+            order.status
+        )));
+    }
+
+    let refundable = order.refundable();
+    let amount = data.amount.unwrap_or(refundable.amount);
+
+    if amount == 0 || amount > refundable.amount {
+        return Err(AppErrors::BadRequest(format!(
+            "At most {} can be refunded",
+            refundable
+        )));
+    }
+
+    let now = Utc::now().timestamp();
+    let refund = OrderRefund {
+        refund_id: Uuid::new_v4().to_string(),
+        amount: Money::new(amount, refundable.currency),
+        reason: data.reason.clone(),
+        refunded_at: now,
+        refunded_by: user_id.clone(),
+    };
+
+    let mut push = doc! {"refunds": to_bson(&refund)?};
+    let mut update = doc! {"$inc": {"version": 1}};
+
+    if amount == refundable.amount {
+        let change = OrderStatusChange {
+            status: OrderStatus::Refunded,
+            changed_at: now,
+            changed_by: user_id,
+            note: data.reason,
+        };
+        push.insert("history", to_bson(&change)?);
+        update.insert("$set", doc! {"status": to_bson(&OrderStatus::Refunded)?});
+    }
+
+    update.insert("$push", push);
+
+    let updated = collection
+        .find_one_and_update(doc! {"_id": order._id, "version": order.version}, update)
+        .return_document(ReturnDocument::After)
+        .await?
+        .ok_or_else(|| AppErrors::Conflict("The order was changed by someone else".to_string()))?;
+
+    settle_refund(db, payments, &order, &updated, &refund).await?;
+
+    Ok(updated)
+}
+
+/// Order of a refund, as left by unwinding the refunds of the orders
+#[derive(Deserialize)]
+struct UnwoundRefund {
+    #[serde(with = "bson::serde_helpers::uuid_1_as_binary")]
+    _id: Uuid,
+    customer_id: String,
+    refunds: OrderRefund,
+}
+
+/// Every refund issued in `[from, to)`, oldest first
+pub async fn get_refund_ledger(
+    db: &Database,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Vec<RefundLedgerEntry>, AppErrors> {
+    let mut range = doc! {};
+    if let Some(from) = from {
+        range.insert("$gte", from);
+    }
+    if let Some(to) = to {
+        range.insert("$lt", to);
+    }
+
+    let filter = if range.is_empty() {
+        doc! {"refunds.0": {"$exists": true}}
+    } else {
+        doc! {"refunds.refunded_at": range}
+    };
+
+    let pipeline = vec![
+        doc! {"$match": filter.clone()},
+        doc! {"$unwind": "$refunds"},
+        doc! {"$match": filter},
+        doc! {"$sort": {"refunds.refunded_at": 1}},
+        doc! {"$project": {"customer_id": 1, "refunds": 1}},
+    ];
+
+    let refunds: Vec<UnwoundRefund> = db
+        .collection::<Order>("orders")
+        .aggregate(pipeline)
+        .with_type::<UnwoundRefund>()
+        .await?
+        .try_collect()
         .await?;
 
-    if result.matched_count == 0 {
-        return Err(AppErrors::NotFound("Order".to_string()));
+    Ok(refunds
+        .into_iter()
+        .map(|unwound| RefundLedgerEntry {
+            order_id: unwound._id.to_string(),
+            customer_id: unwound.customer_id,
+            refund: unwound.refunds,
+        })
+        .collect())
+}
+
+/// The ledger as CSV, one row per refund
+pub fn refund_ledger_csv(entries: &[RefundLedgerEntry]) -> Result<Vec<u8>, AppErrors> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    for entry in entries {
+        writer.serialize(RefundCsvRow::from(entry))?;
     }
 
-    Ok(String::from("Order updated successfully"))
+    writer
+        .into_inner()
+        .map_err(|e| AppErrors::Csv(e.into_error().into()))
 }
 
+/// Every order placed in `[from, to)`, oldest first
+pub async fn get_orders_placed(
+    db: &Database,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Vec<Order>, AppErrors> {
+    let mut range = doc! {};
+    if let Some(from) = from {
+        range.insert("$gte", from);
+    }
+    if let Some(to) = to {
+        range.insert("$lt", to);
+    }
+
+    let filter = if range.is_empty() {
+        doc! {}
+    } else {
+        doc! {"history.0.changed_at": range}
+    };
+
+    let mut orders: Vec<Order> = db
+        .collection::<Order>("orders")
+        .find(filter)
+        .await?
+        .try_collect()
+        .await?;
+    orders.sort_by_key(|order| order.placed_at());
+
+    Ok(orders)
+}
+
+/// The orders as CSV, one row per order with its tax total
+pub fn orders_csv(orders: &[Order]) -> Result<Vec<u8>, AppErrors> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    for order in orders {
+        writer.serialize(OrderCsvRow::from(order))?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| AppErrors::Csv(e.into_error().into()))
+}
+
+/// Only cancelled orders that were never refunded can be removed: live
+/// orders still hold stock and coupon uses, and paid ones must keep their
+/// history and refund ledger. Cancel an order to take it out of play.
 pub async fn delete_order(db: &Database, order_id: &str) -> Result<String, AppErrors> {
     let collection = db.collection::<Order>("orders");
 
-    let uuid = Uuid::parse_str(order_id).map_err(|_| AppErrors::InvalidUUID)?;
+    let order = get_order(db, order_id).await?;
 
-    let result = collection.delete_one(doc! {"_id": uuid}).await?;
+    if order.status != OrderStatus::Cancelled || !order.refunds.is_empty() {
+        return Err(AppErrors::Conflict(
+            "Only cancelled orders without refunds can be deleted".to_string(),
+        ));
+    }
+
+    let result = collection
+        .delete_one(doc! {"_id": order._id, "version": order.version})
+        .await?;
 
     if result.deleted_count == 0 {
-        return Err(AppErrors::NotFound("Order".to_string()));
+        return Err(AppErrors::Conflict(
+            "The order was changed by someone else".to_string(),
+        ));
     }
 
     Ok(String::from("Order deleted successfully"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        bundle::{Bundle, BundlePricing},
+        product::{ProductBuilder, ProductStatus},
+    };
+
+    fn table() -> RateTable {
+        RateTable {
+            base: Currency::Usd,
+            rates: HashMap::from([(Currency::Uah, 40.0)]),
+        }
+    }
+
+    fn bundle_view(components_price: u64, price: u64) -> BundleView {
+        BundleView {
+            bundle: Bundle {
+                _id: Uuid::new_v4(),
+                name: "Commuter kit".to_string(),
+                description: String::new(),
+                images: vec![],
+                items: vec![],
+                pricing: BundlePricing::Price {
+                    price: Money::new(price, Currency::Usd),
+                },
+                status: ProductStatus::Published,
+                version: 1,
+            },
+            components: vec![],
+            components_price: Money::new(components_price, Currency::Usd),
+            price: Money::new(price, Currency::Usd),
+            savings: Money::new(components_price.saturating_sub(price), Currency::Usd),
+            available: true,
+            stock: None,
+        }
+    }
+
+    #[test]
+    fn test_merges_lines_in_first_seen_order() {
+        let ids = ["b", "a", "b"].map(String::from);
+
+        assert_eq!(
+            merge_lines(ids.iter().zip([1, 2, 3])),
+            vec![("b".to_string(), 4), ("a".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_prices_lines_from_the_catalog() {
+        let pricing = price_order(
+            &[(ProductBuilder::new().price(1_999).discount(10).build(), 3)],
+            &[(bundle_view(10_000, 9_000), 1)],
+            &table(),
+            Currency::Usd,
+        )
+        .unwrap();
+
+        let line = &pricing.items[0];
+        assert_eq!(line.name, "Helmet");
+        assert_eq!(line.unit_price, Money::new(1_999, Currency::Usd));
+        // 10% of 19.99 rounds to 2.00 per unit
+        assert_eq!(line.discount, Money::new(600, Currency::Usd));
+        assert_eq!(line.total, Money::new(5_397, Currency::Usd));
+
+        assert_eq!(
+            pricing.bundles[0].discount,
+            Money::new(1_000, Currency::Usd)
+        );
+        assert_eq!(pricing.subtotal, Money::new(15_997, Currency::Usd));
+        assert_eq!(pricing.discount, Money::new(1_600, Currency::Usd));
+        assert_eq!(pricing.total, Money::new(14_397, Currency::Usd));
+    }
+
+    #[test]
+    fn test_bundle_priced_above_its_parts_charges_its_price() {
+        let pricing = price_order(
+            &[],
+            &[(bundle_view(9_000, 10_000), 2)],
+            &table(),
+            Currency::Usd,
+        )
+        .unwrap();
+
+        let line = &pricing.bundles[0];
+        assert_eq!(line.unit_price, Money::new(10_000, Currency::Usd));
+        assert_eq!(line.discount, Money::zero(Currency::Usd));
+        assert_eq!(line.total, Money::new(20_000, Currency::Usd));
+        assert_eq!(pricing.total, Money::new(20_000, Currency::Usd));
+    }
+
+    #[test]
+    fn test_prices_in_the_order_currency() {
+        let pricing = price_order(
+            &[(ProductBuilder::new().price(1_000).discount(25).build(), 2)],
+            &[],
+            &table(),
+            Currency::Uah,
+        )
+        .unwrap();
+
+        assert_eq!(
+            pricing.items[0].unit_price,
+            Money::new(40_000, Currency::Uah)
+        );
+        assert_eq!(pricing.discount, Money::new(20_000, Currency::Uah));
+        assert_eq!(pricing.total, Money::new(60_000, Currency::Uah));
+    }
+
+    #[test]
+    fn test_refund_ledger_csv_has_one_row_per_refund() {
+        let entry = RefundLedgerEntry {
+            order_id: "order".to_string(),
+            customer_id: "customer".to_string(),
+            refund: OrderRefund {
+                refund_id: "refund".to_string(),
+                amount: Money::new(1_250, Currency::Uah),
+                reason: Some("Damaged, partly".to_string()),
+                refunded_at: 1_700_000_000,
+                refunded_by: "admin".to_string(),
+            },
+        };
+
+        let csv = String::from_utf8(refund_ledger_csv(&[entry]).unwrap()).unwrap();
+
+        assert_eq!(
+            csv,
+            "refund_id,order_id,customer_id,refunded_at,amount,currency,reason,refunded_by\n\
+             refund,order,customer,2023-11-14T22:13:20+00:00,1250,UAH,\"Damaged, partly\",admin\n"
+        );
+    }
+
+    #[test]
+    fn test_missing_rate_fails() {
+        assert!(matches!(
+            price_order(
+                &[(ProductBuilder::new().price(1_000).build(), 1)],
+                &[],
+                &table(),
+                Currency::Eur
+            ),
+            Err(AppErrors::NotFound(_))
+        ));
+    }
+}
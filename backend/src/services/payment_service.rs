@@ -0,0 +1,591 @@
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bson::to_bson;
+use chrono::Utc;
+use futures_util::{future::BoxFuture, TryStreamExt};
+use mongodb::{bson::doc, Database};
+use redis::aio::ConnectionManager;
+use uuid::Uuid;
+
+use crate::{
+    dto::payment::{PaymentIntentResponse, PaymentWebhookEvent, ReconcileReport},
+    errors::AppErrors,
+    models::{
+        money::Money,
+        order::{Order, OrderStatus},
+        payment::{Payment, PaymentStatus},
+    },
+    services::order_service,
+};
+
+/// A payment as the provider reports it
+#[derive(Debug, Clone)]
+pub struct PaymentIntent {
+    pub id: String,
+    pub client_secret: String,
+    pub status: PaymentStatus,
+}
+
+/// A payment service provider. Amounts are in the order currency.
+pub trait PaymentProvider: Send + Sync {
+    /// Name stored with every payment
+    fn name(&self) -> &'static str;
+
+    fn create_intent<'a>(
+        &'a self,
+        order_id: &'a str,
+        amount: Money,
+    ) -> BoxFuture<'a, Result<PaymentIntent, AppErrors>>;
+
+    /// Takes the money of an authorized payment
+    fn capture<'a>(&'a self, intent_id: &'a str)
+        -> BoxFuture<'a, Result<PaymentIntent, AppErrors>>;
+
+    /// Gives back part or all of a captured payment
+    fn refund<'a>(
+        &'a self,
+        intent_id: &'a str,
+        amount: Money,
+    ) -> BoxFuture<'a, Result<(), AppErrors>>;
+
+    /// Current state of a payment, for payments whose webhook never came
+    fn retrieve<'a>(
+        &'a self,
+        intent_id: &'a str,
+    ) -> BoxFuture<'a, Result<PaymentIntent, AppErrors>>;
+}
+
+#[derive(Debug, Clone)]
+struct MockIntent {
+    amount: Money,
+    refunded: u64,
+    status: PaymentStatus,
+}
+
+/// Keeps payments in memory and never talks to anyone. Tests move payments
+/// along with `authorize`, `succeed` and `fail`.
+#[derive(Default)]
+pub struct MockPaymentProvider {
+    intents: Mutex<HashMap<String, MockIntent>>,
+}
+
+impl MockPaymentProvider {
+    fn set_status(&self, intent_id: &str, status: PaymentStatus) {
+        if let Some(intent) = self.intents.lock().unwrap().get_mut(intent_id) {
+            intent.status = status;
+        }
+    }
+
+    /// The customer authorized the payment
+    pub fn authorize(&self, intent_id: &str) {
+        self.set_status(intent_id, PaymentStatus::RequiresCapture);
+    }
+
+    /// The customer paid
+    pub fn succeed(&self, intent_id: &str) {
+        self.set_status(intent_id, PaymentStatus::Succeeded);
+    }
+
+    /// The payment was declined
+    pub fn fail(&self, intent_id: &str) {
+        self.set_status(intent_id, PaymentStatus::Failed);
+    }
+
+    /// Amount refunded so far, in minor units
+    pub fn refunded(&self, intent_id: &str) -> Option<u64> {
+        self.intents
+            .lock()
+            .unwrap()
+            .get(intent_id)
+            .map(|intent| intent.refunded)
+    }
+
+    fn intent(&self, intent_id: &str) -> Result<PaymentIntent, AppErrors> {
+        let intents = self.intents.lock().unwrap();
+        let intent = intents
+            .get(intent_id)
+            .ok_or_else(|| AppErrors::NotFound("Payment intent".to_string()))?;
+
+        Ok(PaymentIntent {
+            id: intent_id.to_string(),
+            client_secret: format!("{}_secret", intent_id),
+            status: intent.status,
+        })
+    }
+}
+
+impl PaymentProvider for MockPaymentProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn create_intent<'a>(
+        &'a self,
+        _order_id: &'a str,
+        amount: Money,
+    ) -> BoxFuture<'a, Result<PaymentIntent, AppErrors>> {
+        Box::pin(async move {
+            let id = format!("mock_pi_{}", Uuid::new_v4().simple());
+            self.intents.lock().unwrap().insert(
+                id.clone(),
+                MockIntent {
+                    amount,
+                    refunded: 0,
+                    status: PaymentStatus::RequiresPayment,
+                },
+            );
+
+            self.intent(&id)
+        })
+    }
+
+    fn capture<'a>(
+        &'a self,
+        intent_id: &'a str,
+    ) -> BoxFuture<'a, Result<PaymentIntent, AppErrors>> {
+        Box::pin(async move {
+            if self.intent(intent_id)?.status != PaymentStatus::RequiresCapture {
+                return Err(AppErrors::BadRequest(
+                    "The payment is not authorized".to_string(),
+                ));
+            }
+
+            self.succeed(intent_id);
+            self.intent(intent_id)
+        })
+    }
+
+    fn refund<'a>(
+        &'a self,
+        intent_id: &'a str,
+        amount: Money,
+    ) -> BoxFuture<'a, Result<(), AppErrors>> {
+        Box::pin(async move {
+            let mut intents = self.intents.lock().unwrap();
+            let intent = intents
+                .get_mut(intent_id)
+                .ok_or_else(|| AppErrors::NotFound("Payment intent".to_string()))?;
+
+            if intent.status != PaymentStatus::Succeeded
+                || amount.currency != intent.amount.currency
+                || intent.refunded + amount.amount > intent.amount.amount
+            {
+                return Err(AppErrors::BadRequest(
+                    "The provider declined the refund".to_string(),
+                ));
+            }
+
+            intent.refunded += amount.amount;
+            Ok(())
+        })
+    }
+
+    fn retrieve<'a>(
+        &'a self,
+        intent_id: &'a str,
+    ) -> BoxFuture<'a, Result<PaymentIntent, AppErrors>> {
+        Box::pin(async move { self.intent(intent_id) })
+    }
+}
+
+/// Used when the shop takes no online payments. Orders can still be placed
+/// and are paid outside the provider.
+pub struct DisabledPaymentProvider;
+
+impl DisabledPaymentProvider {
+    fn unavailable<T: Send + 'static>() -> BoxFuture<'static, Result<T, AppErrors>> {
+        Box::pin(async {
+            Err(AppErrors::BadRequest(
+                "Online payments are not available".to_string(),
+            ))
+        })
+    }
+}
+
+impl PaymentProvider for DisabledPaymentProvider {
+    fn name(&self) -> &'static str {
+        "disabled"
+    }
+
+    fn create_intent<'a>(
+        &'a self,
+        _order_id: &'a str,
+        _amount: Money,
+    ) -> BoxFuture<'a, Result<PaymentIntent, AppErrors>> {
+        Self::unavailable()
+    }
+
+    fn capture<'a>(
+        &'a self,
+        _intent_id: &'a str,
+    ) -> BoxFuture<'a, Result<PaymentIntent, AppErrors>> {
+        Self::unavailable()
+    }
+
+    fn refund<'a>(
+        &'a self,
+        _intent_id: &'a str,
+        _amount: Money,
+    ) -> BoxFuture<'a, Result<(), AppErrors>> {
+        Self::unavailable()
+    }
+
+    fn retrieve<'a>(
+        &'a self,
+        _intent_id: &'a str,
+    ) -> BoxFuture<'a, Result<PaymentIntent, AppErrors>> {
+        Self::unavailable()
+    }
+}
+
+/// The provider named by `PAYMENT_PROVIDER`, which has to be set: `disabled`
+/// or, in debug builds only, `mock`, so a release server never takes orders
+/// it will not be paid for
+pub fn get_payment_provider() -> Arc<dyn PaymentProvider> {
+    let name = env::var("PAYMENT_PROVIDER").expect("PAYMENT_PROVIDER must be set");
+
+    match name.to_ascii_lowercase().as_str() {
+        "disabled" => Arc::new(DisabledPaymentProvider),
+        "mock" if cfg!(debug_assertions) => Arc::new(MockPaymentProvider::default()),
+        "mock" => panic!("The mock payment provider is only available in debug builds"),
+        other => panic!("Unknown payment provider: {}", other),
+    }
+}
+
+/// Payments left unsettled for this long are checked with the provider
+fn get_reconcile_after_minutes() -> i64 {
+    env::var("PAYMENT_RECONCILE_AFTER_MINUTES")
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(15)
+}
+
+/// Starts a payment for one of the customer's pending orders. Asking again
+/// while the payment is open returns the same payment.
+pub async fn create_intent(
+    db: &Database,
+    payments: &dyn PaymentProvider,
+    order_id: &str,
+    user_id: &str,
+) -> Result<PaymentIntentResponse, AppErrors> {
+    let collection = db.collection::<Payment>("payments");
+    let uuid = Uuid::parse_str(order_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let order = db
+        .collection::<Order>("orders")
+        .find_one(doc! {"_id": uuid, "customer_id": user_id})
+        .await?
+        .ok_or_else(|| AppErrors::NotFound("Order".to_string()))?;
+
+    if order.status != OrderStatus::Pending {
+        return Err(AppErrors::BadRequest(format!(
+            "A {:?} order cannot be paid",
+            order.status
+        )));
+    }
+
+    let open = collection
+        .find_one(doc! {
+            "order_id": order._id.to_string(),
+            "status": {"$in": [
+                to_bson(&PaymentStatus::RequiresPayment)?,
+                to_bson(&PaymentStatus::RequiresCapture)?,
+            ]},
+        })
+        .await?;
+
+    let payment = match open {
+        Some(payment) => payment,
+        None => {
+            let intent = payments
+                .create_intent(&order._id.to_string(), order.total_price)
+                .await?;
+            let now = Utc::now().timestamp();
+
+            let payment = Payment {
+                _id: Uuid::new_v4(),
+                order_id: order._id.to_string(),
+                provider: payments.name().to_string(),
+                intent_id: intent.id,
+                client_secret: intent.client_secret,
+                amount: order.total_price,
+                status: intent.status,
+                created_at: now,
+                updated_at: now,
+            };
+            collection.insert_one(&payment).await?;
+
+            payment
+        }
+    };
+
+    Ok(PaymentIntentResponse {
+        payment_id: payment._id.to_string(),
+        intent_id: payment.intent_id,
+        client_secret: payment.client_secret,
+        amount: payment.amount,
+        status: payment.status,
+    })
+}
+
+/// Brings the order in line with a payment that went through: a pending
+/// order becomes paid, and a cancelled one gets the money back
+async fn settle_order(
+    db: &Database,
+    redis: ConnectionManager,
+    payments: &dyn PaymentProvider,
+    payment: &Payment,
+) -> Result<(), AppErrors> {
+    let order = order_service::get_order(db, &payment.order_id).await?;
+
+    match order.status {
+        OrderStatus::Pending => {
+            order_service::mark_paid(db, redis, payments, order, payment.provider.clone()).await?;
+        }
+        // Paid for an order that was cancelled in the meantime
+        OrderStatus::Cancelled if order.refunds.is_empty() => {
+            order_service::refund_late_payment(
+                db,
+                payments,
+                order,
+                &payment.intent_id,
+                payment.amount,
+                payment.provider.clone(),
+            )
+            .await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Records what the provider says about a payment. Authorized payments are
+/// captured, and the order is settled before the payment is saved as
+/// succeeded: if that fails, the payment stays open and the next webhook or
+/// reconciliation run tries again. Repeated reports change nothing.
+async fn apply_status(
+    db: &Database,
+    redis: ConnectionManager,
+    payments: &dyn PaymentProvider,
+    payment: Payment,
+    mut status: PaymentStatus,
+) -> Result<PaymentStatus, AppErrors> {
+    match payment.status {
+        PaymentStatus::Failed => return Ok(payment.status),
+        // Payments settled before orders were updated first may have left
+        // their order behind
+        PaymentStatus::Succeeded => {
+            settle_order(db, redis, payments, &payment).await?;
+            return Ok(payment.status);
+        }
+        _ => {}
+    }
+
+    if status == PaymentStatus::RequiresCapture {
+        // A repeated report finds the payment captured already
+        status = match payments.retrieve(&payment.intent_id).await?.status {
+            PaymentStatus::RequiresCapture => payments.capture(&payment.intent_id).await?.status,
+            current => current,
+        };
+    }
+
+    if status == payment.status {
+        return Ok(status);
+    }
+
+    if status == PaymentStatus::Succeeded {
+        settle_order(db, redis, payments, &payment).await?;
+    }
+
+    db.collection::<Payment>("payments")
+        .update_one(
+            doc! {"_id": payment._id, "status": to_bson(&payment.status)?},
+            doc! {"$set": {
+                "status": to_bson(&status)?,
+                "updated_at": Utc::now().timestamp(),
+            }},
+        )
+        .await?;
+
+    Ok(status)
+}
+
+/// Applies a verified webhook event
+pub async fn handle_webhook(
+    db: &Database,
+    redis: ConnectionManager,
+    payments: &dyn PaymentProvider,
+    event: PaymentWebhookEvent,
+) -> Result<(), AppErrors> {
+    let payment = db
+        .collection::<Payment>("payments")
+        .find_one(doc! {"intent_id": &event.intent_id})
+        .await?
+        .ok_or_else(|| AppErrors::NotFound("Payment".to_string()))?;
+
+    apply_status(db, redis, payments, payment, event.status).await?;
+
+    Ok(())
+}
+
+/// Asks the provider about payments that stayed unsettled for a while,
+/// in case their webhook got lost
+pub async fn reconcile(
+    db: &Database,
+    redis: ConnectionManager,
+    payments: &dyn PaymentProvider,
+) -> Result<ReconcileReport, AppErrors> {
+    let stale_before = Utc::now().timestamp() - get_reconcile_after_minutes() * 60;
+
+    let unsettled: Vec<Payment> = db
+        .collection::<Payment>("payments")
+        .find(doc! {
+            "provider": payments.name(),
+            "status": {"$in": [
+                to_bson(&PaymentStatus::RequiresPayment)?,
+                to_bson(&PaymentStatus::RequiresCapture)?,
+            ]},
+            "updated_at": {"$lt": stale_before},
+        })
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut report = ReconcileReport::default();
+
+    for payment in unsettled {
+        report.checked += 1;
+
+        let id = payment._id;
+        let result = match payments.retrieve(&payment.intent_id).await {
+            Ok(intent) => apply_status(db, redis.clone(), payments, payment, intent.status).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(PaymentStatus::Succeeded) => report.succeeded += 1,
+            Ok(PaymentStatus::Failed) => report.failed += 1,
+            Ok(_) => report.pending += 1,
+            Err(e) => {
+                report.pending += 1;
+                eprintln!("❌ Failed to reconcile payment {}: {:?}", id, e);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Reconciles payments every `PAYMENT_RECONCILE_INTERVAL_MINUTES`
+pub fn spawn_reconciliation(
+    db: Database,
+    redis: ConnectionManager,
+    payments: Arc<dyn PaymentProvider>,
+) {
+    let minutes = env::var("PAYMENT_RECONCILE_INTERVAL_MINUTES")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(10);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(minutes.max(1) * 60));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = reconcile(&db, redis.clone(), payments.as_ref()).await {
+                eprintln!("❌ Payment reconciliation failed: {:?}", e);
+            }
+        }
+    });
+}
+
+/// Gives `amount` of the order's captured payment back to the customer.
+/// Orders paid outside the provider have nothing to give back here.
+pub async fn refund_order_payment(
+    db: &Database,
+    payments: &dyn PaymentProvider,
+    order_id: &str,
+    amount: Money,
+) -> Result<(), AppErrors> {
+    let payment = db
+        .collection::<Payment>("payments")
+        .find_one(doc! {
+            "order_id": order_id,
+            "status": to_bson(&PaymentStatus::Succeeded)?,
+        })
+        .await?;
+
+    match payment {
+        Some(payment) => payments.refund(&payment.intent_id, amount).await,
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::money::Currency;
+
+    #[tokio::test]
+    async fn test_mock_captures_authorized_payments_only() {
+        let mock = MockPaymentProvider::default();
+        let intent = mock
+            .create_intent("order", Money::new(1_000, Currency::Usd))
+            .await
+            .unwrap();
+
+        assert_eq!(intent.status, PaymentStatus::RequiresPayment);
+        assert!(mock.capture(&intent.id).await.is_err());
+
+        mock.authorize(&intent.id);
+
+        assert_eq!(
+            mock.capture(&intent.id).await.unwrap().status,
+            PaymentStatus::Succeeded
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_refunds_up_to_the_captured_amount() {
+        let mock = MockPaymentProvider::default();
+        let intent = mock
+            .create_intent("order", Money::new(1_000, Currency::Usd))
+            .await
+            .unwrap();
+
+        assert!(mock
+            .refund(&intent.id, Money::new(100, Currency::Usd))
+            .await
+            .is_err());
+
+        mock.succeed(&intent.id);
+
+        mock.refund(&intent.id, Money::new(600, Currency::Usd))
+            .await
+            .unwrap();
+        assert!(mock
+            .refund(&intent.id, Money::new(500, Currency::Usd))
+            .await
+            .is_err());
+        assert_eq!(mock.refunded(&intent.id), Some(600));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_provider_takes_no_payments() {
+        let disabled = DisabledPaymentProvider;
+
+        assert!(matches!(
+            disabled
+                .create_intent("order", Money::new(1_000, Currency::Usd))
+                .await,
+            Err(AppErrors::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,113 @@
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, to_bson};
+use mongodb::Database;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppErrors,
+    models::{
+        campaign::DiscountCampaign,
+        money::Money,
+        price_history::{PriceChangeSource, PriceHistory},
+    },
+};
+
+pub async fn record_price_change(
+    db: &Database,
+    product_id: &str,
+    price: Money,
+    discount: u8,
+    source: PriceChangeSource,
+    campaign_id: Option<String>,
+    effective_at: i64,
+) -> Result<(), AppErrors> {
+    let collection = db.collection::<PriceHistory>("price_history");
+
+    let entry = PriceHistory {
+        _id: Uuid::new_v4(),
+        product_id: product_id.to_string(),
+        price,
+        discount,
+        source,
+        campaign_id,
+        effective_at,
+    };
+
+    collection.insert_one(entry).await?;
+
+    Ok(())
+}
+
+/// Records an edit of the product's own price or discount. Scheduled
+/// campaign entries were written with the price of their time, so they move
+/// to the new price, and campaign ends fall back to the new discount.
+pub async fn record_manual_change(
+    db: &Database,
+    product_id: &str,
+    price: Money,
+    discount: u8,
+    effective_at: i64,
+) -> Result<(), AppErrors> {
+    record_price_change(
+        db,
+        product_id,
+        price,
+        discount,
+        PriceChangeSource::Manual,
+        None,
+        effective_at,
+    )
+    .await?;
+
+    let collection = db.collection::<PriceHistory>("price_history");
+
+    collection
+        .update_many(
+            doc! {
+                "product_id": product_id,
+                "source": to_bson(&PriceChangeSource::Campaign)?,
+                "effective_at": { "$gt": effective_at },
+            },
+            doc! {"$set": {"price": to_bson(&price)?}},
+        )
+        .await?;
+
+    let ending: Vec<DiscountCampaign> = db
+        .collection::<DiscountCampaign>("campaigns")
+        .find(doc! {"product_id": product_id, "ends_at": { "$gt": effective_at }})
+        .await?
+        .try_collect()
+        .await?;
+
+    for campaign in ending {
+        collection
+            .update_one(
+                doc! {
+                    "campaign_id": campaign._id.to_string(),
+                    "effective_at": campaign.ends_at,
+                },
+                doc! {"$set": {"discount": i32::from(discount)}},
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn get_price_history(
+    db: &Database,
+    product_id: &str,
+) -> Result<Vec<PriceHistory>, AppErrors> {
+    let collection = db.collection::<PriceHistory>("price_history");
+
+    Uuid::parse_str(product_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let cursor = collection
+        .find(doc! { "product_id": product_id })
+        .sort(doc! { "effective_at": 1 })
+        .await?;
+
+    let history: Vec<PriceHistory> = cursor.try_collect().await?;
+
+    Ok(history)
+}
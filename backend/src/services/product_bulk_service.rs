@@ -0,0 +1,383 @@
+use std::collections::{HashMap, HashSet};
+
+use bson::to_document;
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::{Collection, Database};
+use redis::aio::ConnectionManager;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    dto::product::{
+        BulkFormat, CreateProductDto, ImportMode, ImportReport, ImportRowError, ProductCsvRow,
+    },
+    errors::AppErrors,
+    models::{attribute::CategorySchema, price_history::PriceChangeSource, product::Product},
+    services::{alert_service, attribute_service, price_history_service, product_service},
+    utils::currency::get_base_currency,
+};
+
+/// Parses every row of the upload, keeping the 1-based line number of each one
+pub fn parse_rows(
+    format: BulkFormat,
+    body: &[u8],
+) -> Vec<(usize, Result<CreateProductDto, String>)> {
+    match format {
+        BulkFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(body);
+
+            reader
+                .deserialize::<ProductCsvRow>()
+                .enumerate()
+                .map(|(index, record)| {
+                    let line = record
+                        .as_ref()
+                        .err()
+                        .and_then(|e| e.position())
+                        .map_or(index + 2, |position| position.line() as usize);
+
+                    (
+                        line,
+                        record
+                            .map_err(|e| e.to_string())
+                            .and_then(CreateProductDto::try_from),
+                    )
+                })
+                .collect()
+        }
+        BulkFormat::Jsonl => String::from_utf8_lossy(body)
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                (
+                    index + 1,
+                    serde_json::from_str::<CreateProductDto>(line).map_err(|e| e.to_string()),
+                )
+            })
+            .collect(),
+    }
+}
+
+enum RowOutcome {
+    Created,
+    Updated,
+}
+
+fn check_row(
+    data: &CreateProductDto,
+    mode: ImportMode,
+    schemas: &HashMap<u8, CategorySchema>,
+    seen_skus: &mut HashSet<String>,
+) -> Result<(), String> {
+    data.validate()
+        .map_err(|e| format!("Validation failed: {}", e))?;
+
+    attribute_service::check_with(schemas.get(&data.category), data.category, &data.attributes)?;
+
+    let base = get_base_currency();
+    if data.price.currency != base {
+        return Err(format!(
+            "Product prices must be in the base currency ({})",
+            base
+        ));
+    }
+
+    match &data.sku {
+        Some(sku) if !seen_skus.insert(sku.clone()) => {
+            Err(format!("SKU {} appears more than once in the file", sku))
+        }
+        None if mode == ImportMode::Upsert => Err("A SKU is required in upsert mode".to_string()),
+        _ => Ok(()),
+    }
+}
+
+async fn import_row(
+    db: &Database,
+    collection: &Collection<Product>,
+    data: CreateProductDto,
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<Result<RowOutcome, String>, AppErrors> {
+    let existing = match &data.sku {
+        Some(sku) => collection.find_one(doc! { "sku": sku }).await?,
+        None => None,
+    };
+
+    let now = Utc::now().timestamp();
+
+    match (mode, existing) {
+        // Updating it would leave the product deleted and invisible
+        (_, Some(existing)) if existing.deleted_at.is_some() => Ok(Err(
+            "SKU belongs to a deleted product; restore it first".to_string(),
+        )),
+        (ImportMode::Insert, Some(_)) => {
+            Ok(Err("A product with this SKU already exists".to_string()))
+        }
+        (_, Some(existing)) => {
+            if !dry_run {
+                let update_doc = to_document(&data)?;
+                collection
+                    .update_one(
+                        doc! {"_id": existing._id},
+                        doc! {"$set": update_doc, "$inc": {"version": 1}},
+                    )
+                    .await?;
+
+                if data.price != existing.price || data.discount != existing.discount {
+                    price_history_service::record_manual_change(
+                        db,
+                        &existing._id.to_string(),
+                        data.price,
+                        data.discount,
+                        now,
+                    )
+                    .await?;
+                }
+
+                alert_service::spawn_evaluation(db, existing._id);
+            }
+
+            Ok(Ok(RowOutcome::Updated))
+        }
+        (_, None) => {
+            if !dry_run {
+                let product = Product {
+                    _id: Uuid::new_v4(),
+                    sku: data.sku,
+                    name: data.name,
+                    price: data.price,
+                    description: data.description,
+                    translations: vec![],
+                    images: data.images,
+                    discount: data.discount,
+                    category: data.category,
+                    attributes: data.attributes,
+                    stock: data.stock,
+                    status: data.status.unwrap_or_default(),
+                    deleted_at: None,
+                    version: 1,
+                };
+
+                collection.insert_one(&product).await?;
+
+                price_history_service::record_price_change(
+                    db,
+                    &product._id.to_string(),
+                    product.price,
+                    product.discount,
+                    PriceChangeSource::Manual,
+                    None,
+                    now,
+                )
+                .await?;
+            }
+
+            Ok(Ok(RowOutcome::Created))
+        }
+    }
+}
+
+/// Saves the rows one by one. The import is not atomic: a row that fails,
+/// even on a database error, is listed in the report and the other rows are
+/// still saved.
+pub async fn import_products(
+    db: &Database,
+    mut redis: ConnectionManager,
+    body: &[u8],
+    format: BulkFormat,
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<ImportReport, AppErrors> {
+    let collection = db.collection::<Product>("products");
+
+    let mut report = ImportReport {
+        dry_run,
+        ..Default::default()
+    };
+    let schemas = attribute_service::get_all_schemas(db).await?;
+    let mut seen_skus = HashSet::new();
+
+    for (row, parsed) in parse_rows(format, body) {
+        report.total += 1;
+
+        let sku = parsed.as_ref().ok().and_then(|data| data.sku.clone());
+
+        let checked = parsed.and_then(|data| {
+            check_row(&data, mode, &schemas, &mut seen_skus)?;
+            Ok(data)
+        });
+
+        let outcome = match checked {
+            Ok(data) => import_row(db, &collection, data, mode, dry_run)
+                .await
+                .unwrap_or_else(|e| Err(e.to_string())),
+            Err(message) => Err(message),
+        };
+
+        match outcome {
+            Ok(RowOutcome::Created) => report.created += 1,
+            Ok(RowOutcome::Updated) => report.updated += 1,
+            Err(message) => {
+                report.failed += 1;
+                report.errors.push(ImportRowError { row, sku, message });
+            }
+        }
+    }
+
+    if !dry_run && report.created + report.updated > 0 {
+        product_service::invalidate_product_caches(&mut redis).await;
+    }
+
+    Ok(report)
+}
+
+pub async fn export_products(db: &Database, format: BulkFormat) -> Result<Vec<u8>, AppErrors> {
+    let collection = db.collection::<Product>("products");
+
+    let cursor = collection
+        .find(doc! { "deleted_at": null })
+        .sort(doc! { "name": 1 })
+        .await?;
+    let products: Vec<Product> = cursor.try_collect().await?;
+
+    match format {
+        BulkFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+
+            for product in &products {
+                writer.serialize(ProductCsvRow::from(product))?;
+            }
+
+            writer
+                .into_inner()
+                .map_err(|e| AppErrors::Csv(e.into_error().into()))
+        }
+        BulkFormat::Jsonl => {
+            let mut out = Vec::new();
+
+            for product in &products {
+                let data = CreateProductDto {
+                    sku: product.sku.clone(),
+                    name: product.name.clone(),
+                    price: product.price,
+                    description: product.description.clone(),
+                    images: product.images.clone(),
+                    discount: product.discount,
+                    category: product.category,
+                    attributes: product.attributes.clone(),
+                    stock: product.stock,
+                    status: Some(product.status),
+                };
+
+                if let Ok(json) = serde_json::to_string(&data) {
+                    out.extend(json.into_bytes());
+                    out.push(b'\n');
+                }
+            }
+
+            Ok(out)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        attribute::AttributeValue,
+        money::{Currency, Money},
+    };
+    use serial_test::serial;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_parse_csv_rows() {
+        let body = b"sku,name,price_amount,price_currency,description,images,discount,category
+BIKE-1,Trail bike,129900,USD,Full suspension,a.jpg|b.jpg,10,1
+,Helmet,4999,USD,Road helmet,,0,0
+BIKE-2,Broken,not-a-number,USD,x,,0,1
+";
+
+        let rows = parse_rows(BulkFormat::Csv, body);
+
+        assert_eq!(rows.len(), 3);
+
+        let (line, first) = &rows[0];
+        let first = first.as_ref().unwrap();
+        assert_eq!(*line, 2);
+        assert_eq!(first.sku.as_deref(), Some("BIKE-1"));
+        assert_eq!(first.price, Money::new(129_900, Currency::Usd));
+        assert_eq!(first.images, vec!["a.jpg", "b.jpg"]);
+
+        let second = rows[1].1.as_ref().unwrap();
+        assert_eq!(second.sku, None);
+        assert!(second.images.is_empty());
+
+        assert_eq!(rows[2].0, 4);
+        assert!(rows[2].1.is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_attributes_column() {
+        let body = br#"sku,name,price_amount,price_currency,description,images,discount,category,attributes
+BIKE-1,Trail bike,129900,USD,Full suspension,,10,1,"{""wheel_size"":29,""frame_material"":""steel""}"
+BIKE-2,Broken,129900,USD,Full suspension,,10,1,{not json}
+"#;
+
+        let rows = parse_rows(BulkFormat::Csv, body);
+
+        let first = rows[0].1.as_ref().unwrap();
+        assert_eq!(
+            first.attributes.get("wheel_size"),
+            Some(&AttributeValue::Number(29.0))
+        );
+        assert!(matches!(&rows[1].1, Err(message) if message.starts_with("Invalid attributes")));
+    }
+
+    #[test]
+    fn test_parse_jsonl_rows_skips_blank_lines() {
+        let body = br#"{"sku":"H-1","name":"Helmet","price":{"amount":4999,"currency":"USD"},"description":"Road helmet","images":[],"discount":0,"category":0}
+
+{"name":"Broken"}
+"#;
+
+        let rows = parse_rows(BulkFormat::Jsonl, body);
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].0, 1);
+        assert_eq!(rows[0].1.as_ref().unwrap().name, "Helmet");
+        assert_eq!(rows[1].0, 3);
+        assert!(rows[1].1.is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_check_row_rejects_duplicates_and_missing_sku() {
+        let data = CreateProductDto {
+            sku: Some("BIKE-1".to_string()),
+            name: "Trail bike".to_string(),
+            price: Money::new(129_900, get_base_currency()),
+            description: "Full suspension".to_string(),
+            images: vec![],
+            discount: 10,
+            category: 1,
+            attributes: BTreeMap::new(),
+            stock: None,
+            status: None,
+        };
+        let schemas = HashMap::new();
+        let mut seen = HashSet::new();
+
+        assert!(check_row(&data, ImportMode::Insert, &schemas, &mut seen).is_ok());
+        assert!(check_row(&data, ImportMode::Insert, &schemas, &mut seen).is_err());
+
+        let without_sku = CreateProductDto { sku: None, ..data };
+        assert!(check_row(&without_sku, ImportMode::Insert, &schemas, &mut seen).is_ok());
+        assert!(check_row(&without_sku, ImportMode::Upsert, &schemas, &mut seen).is_err());
+    }
+}
@@ -1,25 +1,88 @@
+use std::collections::{BTreeSet, HashMap};
+
 use actix_web::web;
-use bson::to_document;
+use bson::{to_document, Bson};
+use chrono::Utc;
 use futures_util::stream::TryStreamExt;
-use mongodb::bson::doc;
+use mongodb::bson::{doc, Document};
 use mongodb::{options::FindOneOptions, Database};
 use redis::{aio::ConnectionManager, AsyncCommands};
 use uuid::Uuid;
 
-use crate::dto::product::{CreateProductDto, UpdateProductDto};
+use crate::dto::attribute::ProductFilter;
+use crate::dto::product::{
+    AdminProductsQuery, CategoryDeals, CompareQuery, ComparedAttribute, CreateProductDto, Deal,
+    DealRanking, DealsQuery, DealsResponse, ProductAnalytics, ProductComparison, TranslationDto,
+    UpdateProductDto,
+};
 use crate::errors::AppErrors;
-use crate::models::product::Product;
+use crate::models::attribute::{AttributeValue, CategorySchema};
+use crate::models::locale::Locale;
+use crate::models::money::{Currency, Money};
+use crate::models::price_history::PriceChangeSource;
+use crate::models::product::{Product, ProductStatus, ProductTranslation};
+use crate::services::{
+    alert_service, attribute_service, campaign_service, exchange_rate_service,
+    price_history_service, wishlist_service,
+};
+use crate::utils::{currency::get_base_currency, http_cache, locale::get_default_locale};
+
+pub fn ensure_base_currency(price: &Money) -> Result<(), AppErrors> {
+    let base = get_base_currency();
+
+    if price.currency != base {
+        return Err(AppErrors::BadRequest(format!(
+            "Product prices must be in the base currency ({})",
+            base
+        )));
+    }
+
+    Ok(())
+}
+
+const DEFAULT_DEALS_LIMIT: usize = 10;
+
+/// Drops every cached view of the catalog. Call after anything that changes
+/// which products are visible or what they cost.
+pub async fn invalidate_product_caches(redis: &mut ConnectionManager) {
+    http_cache::bump_catalog_version(redis).await;
+
+    let _: std::result::Result<(), _> = redis.del("most_advantageous").await;
+
+    let mut keys: Vec<String> = Vec::new();
+    if let Ok(mut iter) = redis.scan_match::<_, String>("deals:*").await {
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+    }
+
+    if !keys.is_empty() {
+        let _: std::result::Result<(), _> = redis.del(keys).await;
+    }
+}
+
+/// Products customers are allowed to see
+pub fn published_filter() -> Document {
+    doc! { "status": "Published", "deleted_at": null }
+}
 
-pub async fn get_all_products(db: &Database) -> Result<Vec<Product>, AppErrors> {
+pub async fn get_all_products(
+    db: &Database,
+    filter: &ProductFilter,
+) -> Result<Vec<Product>, AppErrors> {
     let collection = db.collection::<Product>("products");
 
-    let mut cursor = collection.find(doc! {}).await?;
+    let mut cursor = collection
+        .find(attribute_service::filter_document(filter))
+        .await?;
     let mut products = Vec::new();
 
     while let Some(result) = cursor.try_next().await? {
         products.push(result);
     }
 
+    campaign_service::apply_active_campaigns(db, &mut products).await?;
+
     Ok(products)
 }
 
@@ -28,20 +91,46 @@ pub async fn create_product(
     mut redis: ConnectionManager,
     new_product_data: web::Json<CreateProductDto>,
 ) -> Result<String, AppErrors> {
+    ensure_base_currency(&new_product_data.price)?;
+    attribute_service::check_attributes(
+        db,
+        new_product_data.category,
+        &new_product_data.attributes,
+    )
+    .await?;
+
     let new_product = Product {
         _id: Uuid::new_v4(),
+        sku: new_product_data.sku.clone(),
         name: new_product_data.name.to_string(),
         category: new_product_data.category,
+        attributes: new_product_data.attributes.clone(),
         description: new_product_data.description.to_string(),
+        translations: vec![],
         discount: new_product_data.discount,
         images: new_product_data.images.clone(),
         price: new_product_data.price,
+        stock: new_product_data.stock,
+        status: new_product_data.status.unwrap_or_default(),
+        deleted_at: None,
+        version: 1,
     };
 
     let collection = db.collection::<Product>("products");
     collection.insert_one(&new_product).await?;
 
-    let _: std::result::Result<(), _> = redis.del("most_advantageous").await;
+    price_history_service::record_price_change(
+        db,
+        &new_product._id.to_string(),
+        new_product.price,
+        new_product.discount,
+        PriceChangeSource::Manual,
+        None,
+        Utc::now().timestamp(),
+    )
+    .await?;
+
+    invalidate_product_caches(&mut redis).await;
 
     Ok(String::from("Product created successfully"))
 }
@@ -52,33 +141,316 @@ pub async fn get_product(db: &Database, product_id: &str) -> Result<Product, App
     let uuid = Uuid::parse_str(product_id)
         .map_err(|e| mongodb::error::Error::custom(format!("Invalid UUID: {}", e)))?;
 
-    let product = collection
-        .find_one(doc! {"_id": uuid})
+    let mut filter = published_filter();
+    filter.insert("_id", uuid);
+
+    let mut product = collection
+        .find_one(filter)
         .await?
         .ok_or_else(|| AppErrors::NotFound(product_id.to_string()))?;
+
+    campaign_service::apply_active_campaigns(db, std::slice::from_mut(&mut product)).await?;
+
     Ok(product)
 }
 
+/// Applies the update if the product is still at `expected_version` (when
+/// given) and returns the new version
 pub async fn update_product(
     db: &Database,
     mut redis: ConnectionManager,
     new_product_data: web::Json<UpdateProductDto>,
-) -> Result<String, AppErrors> {
+    expected_version: Option<u32>,
+) -> Result<u32, AppErrors> {
     let collection = db.collection::<Product>("products");
 
     let uuid = Uuid::parse_str(&new_product_data._id)
         .map_err(|e| mongodb::error::Error::custom(format!("Invalid UUID: {}", e)))?;
 
+    if let Some(price) = &new_product_data.price {
+        ensure_base_currency(price)?;
+    }
+
+    // A new category may not accept the attributes the product already has
+    if new_product_data.category.is_some() || new_product_data.attributes.is_some() {
+        let existing = collection
+            .find_one(doc! {"_id": uuid})
+            .await?
+            .ok_or_else(|| AppErrors::NotFound("Product".to_string()))?;
+
+        attribute_service::check_attributes(
+            db,
+            new_product_data.category.unwrap_or(existing.category),
+            new_product_data
+                .attributes
+                .as_ref()
+                .unwrap_or(&existing.attributes),
+        )
+        .await?;
+    }
+
     let mut update_doc = to_document(&new_product_data)?;
     update_doc.remove("_id");
 
-    collection
-        .update_one(doc! {"_id": uuid}, doc! {"$set": update_doc})
+    let mut filter = doc! {"_id": uuid};
+    if let Some(version) = expected_version {
+        filter.insert("version", version);
+    }
+
+    let Some(previous) = collection
+        .find_one_and_update(filter, doc! {"$set": update_doc, "$inc": {"version": 1}})
+        .await?
+    else {
+        let exists = collection.count_documents(doc! {"_id": uuid}).await? > 0;
+        return Err(if exists {
+            AppErrors::Conflict("The product was changed by someone else".to_string())
+        } else {
+            AppErrors::NotFound("Product".to_string())
+        });
+    };
+
+    let price = new_product_data.price.unwrap_or(previous.price);
+    let discount = new_product_data.discount.unwrap_or(previous.discount);
+
+    if price != previous.price || discount != previous.discount {
+        price_history_service::record_manual_change(
+            db,
+            &uuid.to_string(),
+            price,
+            discount,
+            Utc::now().timestamp(),
+        )
         .await?;
+    }
 
-    let _: std::result::Result<(), _> = redis.del("most_advantageous").await;
+    invalidate_product_caches(&mut redis).await;
+
+    if new_product_data.price.is_some()
+        || new_product_data.discount.is_some()
+        || new_product_data.stock.is_some()
+    {
+        alert_service::spawn_evaluation(db, uuid);
+    }
+
+    Ok(previous.version + 1)
+}
+
+pub async fn set_stock(
+    db: &Database,
+    mut redis: ConnectionManager,
+    product_id: &str,
+    stock: Option<u32>,
+) -> Result<String, AppErrors> {
+    let collection = db.collection::<Product>("products");
 
-    Ok(String::from("Product updated successfully"))
+    let uuid = Uuid::parse_str(product_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let result = collection
+        .update_one(
+            doc! {"_id": uuid},
+            doc! {"$set": {"stock": stock.map(i64::from)}, "$inc": {"version": 1}},
+        )
+        .await?;
+
+    if result.matched_count == 0 {
+        return Err(AppErrors::NotFound("Product".to_string()));
+    }
+
+    invalidate_product_caches(&mut redis).await;
+    alert_service::spawn_evaluation(db, uuid);
+
+    Ok(String::from("Stock updated successfully"))
+}
+
+/// Parses a locale from the path; the default locale lives in the product
+/// itself and has no translation
+fn translation_locale(code: &str) -> Result<Locale, AppErrors> {
+    let locale: Locale = code.parse().map_err(AppErrors::BadRequest)?;
+
+    if locale == get_default_locale() {
+        return Err(AppErrors::BadRequest(format!(
+            "{} is the default locale; update the product name and description instead",
+            locale
+        )));
+    }
+
+    Ok(locale)
+}
+
+/// Adds the translation or replaces the existing one for the same locale
+pub async fn set_translation(
+    db: &Database,
+    mut redis: ConnectionManager,
+    product_id: &str,
+    locale: &str,
+    data: TranslationDto,
+) -> Result<String, AppErrors> {
+    let collection = db.collection::<Product>("products");
+
+    let uuid = Uuid::parse_str(product_id).map_err(|_| AppErrors::InvalidUUID)?;
+    let locale = translation_locale(locale)?;
+
+    let translation = bson::to_bson(&ProductTranslation {
+        locale,
+        name: data.name,
+        description: data.description,
+    })?;
+
+    let replaced = collection
+        .update_one(
+            doc! {"_id": uuid, "translations.locale": locale.code()},
+            doc! {"$set": {"translations.$": &translation}, "$inc": {"version": 1}},
+        )
+        .await?;
+
+    if replaced.matched_count == 0 {
+        let added = collection
+            .update_one(
+                doc! {"_id": uuid, "translations.locale": {"$ne": locale.code()}},
+                doc! {"$push": {"translations": &translation}, "$inc": {"version": 1}},
+            )
+            .await?;
+
+        if added.matched_count == 0 {
+            return Err(AppErrors::NotFound("Product".to_string()));
+        }
+    }
+
+    invalidate_product_caches(&mut redis).await;
+
+    Ok(String::from("Translation saved successfully"))
+}
+
+pub async fn delete_translation(
+    db: &Database,
+    mut redis: ConnectionManager,
+    product_id: &str,
+    locale: &str,
+) -> Result<String, AppErrors> {
+    let collection = db.collection::<Product>("products");
+
+    let uuid = Uuid::parse_str(product_id).map_err(|_| AppErrors::InvalidUUID)?;
+    let locale = translation_locale(locale)?;
+
+    let result = collection
+        .update_one(
+            doc! {"_id": uuid, "translations.locale": locale.code()},
+            doc! {
+                "$pull": {"translations": {"locale": locale.code()}},
+                "$inc": {"version": 1},
+            },
+        )
+        .await?;
+
+    if result.matched_count == 0 {
+        let exists = collection.count_documents(doc! {"_id": uuid}).await? > 0;
+        let resource = if exists { "Translation" } else { "Product" };
+        return Err(AppErrors::NotFound(resource.to_string()));
+    }
+
+    invalidate_product_caches(&mut redis).await;
+
+    Ok(String::from("Translation deleted successfully"))
+}
+
+/// Distinct product ids with the number of times each occurs
+fn count_units(products_id: &[String]) -> Result<Vec<(Uuid, u32)>, AppErrors> {
+    let mut units: Vec<(Uuid, u32)> = Vec::new();
+    for id in products_id {
+        let uuid = Uuid::parse_str(id).map_err(|_| AppErrors::InvalidUUID)?;
+        match units.iter_mut().find(|(u, _)| *u == uuid) {
+            Some((_, count)) => *count += 1,
+            None => units.push((uuid, 1)),
+        }
+    }
+
+    Ok(units)
+}
+
+/// Takes the ordered units out of stock. Products whose stock is not tracked
+/// are skipped; if any product runs short, nothing is taken.
+pub async fn reserve_stock(
+    db: &Database,
+    redis: &mut ConnectionManager,
+    products_id: &[String],
+) -> Result<(), AppErrors> {
+    let collection = db.collection::<Product>("products");
+
+    let units = count_units(products_id)?;
+
+    let mut reserved: Vec<(Uuid, u32)> = Vec::new();
+    for (uuid, count) in units {
+        let result = collection
+            .update_one(
+                doc! {"_id": uuid, "stock": {"$gte": i64::from(count)}},
+                doc! {"$inc": {"stock": -i64::from(count), "version": 1}},
+            )
+            .await?;
+
+        if result.modified_count == 1 {
+            reserved.push((uuid, count));
+            continue;
+        }
+
+        let product = collection.find_one(doc! {"_id": uuid}).await?;
+        if let Some(product) = product.filter(|p| p.stock.is_some()) {
+            for (uuid, count) in reserved {
+                collection
+                    .update_one(
+                        doc! {"_id": uuid},
+                        doc! {"$inc": {"stock": i64::from(count), "version": 1}},
+                    )
+                    .await?;
+            }
+
+            return Err(AppErrors::BadRequest(format!(
+                "Not enough stock for {}",
+                product.name
+            )));
+        }
+    }
+
+    if !reserved.is_empty() {
+        invalidate_product_caches(redis).await;
+    }
+
+    Ok(())
+}
+
+/// Puts the units of a cancelled order back into stock. Products whose stock
+/// is not tracked are skipped, and back-in-stock alerts fire for products
+/// that were sold out.
+pub async fn release_stock(
+    db: &Database,
+    redis: &mut ConnectionManager,
+    products_id: &[String],
+) -> Result<(), AppErrors> {
+    let collection = db.collection::<Product>("products");
+
+    let units = count_units(products_id)?;
+
+    let mut released = false;
+    for (uuid, count) in units {
+        let previous = collection
+            .find_one_and_update(
+                doc! {"_id": uuid, "stock": {"$ne": null}},
+                doc! {"$inc": {"stock": i64::from(count), "version": 1}},
+            )
+            .await?;
+
+        if let Some(previous) = previous {
+            released = true;
+            if previous.stock == Some(0) {
+                alert_service::spawn_evaluation(db, uuid);
+            }
+        }
+    }
+
+    if released {
+        invalidate_product_caches(redis).await;
+    }
+
+    Ok(())
 }
 
 pub async fn delete_product(
@@ -90,13 +462,169 @@ pub async fn delete_product(
 
     let uuid = Uuid::parse_str(product_id).map_err(|_| AppErrors::InvalidUUID)?;
 
-    collection.delete_one(doc! {"_id": uuid}).await?;
+    // Orders keep referencing the product, so it is only hidden
+    let result = collection
+        .update_one(
+            doc! {"_id": uuid, "deleted_at": null},
+            doc! {"$set": {"deleted_at": Utc::now().timestamp()}, "$inc": {"version": 1}},
+        )
+        .await?;
 
-    let _: std::result::Result<(), _> = redis.del("most_advantageous").await;
+    if result.matched_count == 0 {
+        return Err(AppErrors::NotFound("Product".to_string()));
+    }
+
+    invalidate_product_caches(&mut redis).await;
 
     Ok(String::from("Product deleted successfully"))
 }
 
+pub async fn restore_product(
+    db: &Database,
+    mut redis: ConnectionManager,
+    product_id: &str,
+) -> Result<String, AppErrors> {
+    let collection = db.collection::<Product>("products");
+
+    let uuid = Uuid::parse_str(product_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let result = collection
+        .update_one(
+            doc! {"_id": uuid, "deleted_at": {"$ne": null}},
+            doc! {"$set": {"deleted_at": null}, "$inc": {"version": 1}},
+        )
+        .await?;
+
+    if result.matched_count == 0 {
+        return Err(AppErrors::NotFound("Deleted product".to_string()));
+    }
+
+    invalidate_product_caches(&mut redis).await;
+
+    Ok(String::from("Product restored successfully"))
+}
+
+pub async fn change_status(
+    db: &Database,
+    mut redis: ConnectionManager,
+    product_id: &str,
+    status: ProductStatus,
+) -> Result<String, AppErrors> {
+    let collection = db.collection::<Product>("products");
+
+    let uuid = Uuid::parse_str(product_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let result = collection
+        .update_one(
+            doc! {"_id": uuid, "deleted_at": null},
+            doc! {"$set": {"status": bson::to_bson(&status)?}, "$inc": {"version": 1}},
+        )
+        .await?;
+
+    if result.matched_count == 0 {
+        return Err(AppErrors::NotFound("Product".to_string()));
+    }
+
+    invalidate_product_caches(&mut redis).await;
+
+    Ok(format!("Product status changed to {:?}", status))
+}
+
+/// Every product regardless of status, for the admin panel
+pub async fn get_admin_products(
+    db: &Database,
+    query: &AdminProductsQuery,
+) -> Result<Vec<Product>, AppErrors> {
+    let collection = db.collection::<Product>("products");
+
+    let mut filter = doc! {};
+    if let Some(status) = query.status {
+        filter.insert("status", bson::to_bson(&status)?);
+    }
+    if !query.include_deleted {
+        filter.insert("deleted_at", Bson::Null);
+    }
+
+    let cursor = collection.find(filter).sort(doc! { "name": 1 }).await?;
+    let products: Vec<Product> = cursor.try_collect().await?;
+
+    Ok(products)
+}
+
+#[derive(serde::Deserialize)]
+struct OrderCount {
+    _id: String,
+    count: u32,
+}
+
+/// Wishlist and order counts of every product, including archived and
+/// deleted ones, most wished-for first
+pub async fn get_analytics(db: &Database) -> Result<Vec<ProductAnalytics>, AppErrors> {
+    let products: Vec<Product> = db
+        .collection::<Product>("products")
+        .find(doc! {})
+        .await?
+        .try_collect()
+        .await?;
+
+    let wishlist_counts = wishlist_service::count_by_product(db).await?;
+
+    // A product may be on several lines of one order, so count distinct orders
+    let pipeline = vec![
+        doc! {
+            "$project": {
+                "products_id": {
+                    "$setUnion": [
+                        "$items.product_id",
+                        {
+                            "$reduce": {
+                                "input": "$bundles.components.product_id",
+                                "initialValue": [],
+                                "in": { "$concatArrays": ["$$value", "$$this"] },
+                            }
+                        },
+                    ]
+                }
+            }
+        },
+        doc! { "$unwind": "$products_id" },
+        doc! { "$group": { "_id": "$products_id", "count": { "$sum": 1 } } },
+    ];
+    let order_counts: HashMap<String, u32> = db
+        .collection::<Document>("orders")
+        .aggregate(pipeline)
+        .with_type::<OrderCount>()
+        .await?
+        .try_collect::<Vec<OrderCount>>()
+        .await?
+        .into_iter()
+        .map(|c| (c._id, c.count))
+        .collect();
+
+    let mut analytics: Vec<ProductAnalytics> = products
+        .into_iter()
+        .map(|product| {
+            let product_id = product._id.to_string();
+            ProductAnalytics {
+                wishlist_count: wishlist_counts.get(&product_id).copied().unwrap_or(0),
+                order_count: order_counts.get(&product_id).copied().unwrap_or(0),
+                product_id,
+                name: product.name,
+                status: product.status,
+                deleted: product.deleted_at.is_some(),
+            }
+        })
+        .collect();
+
+    analytics.sort_by(|a, b| {
+        b.wishlist_count
+            .cmp(&a.wishlist_count)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(analytics)
+}
+
 pub async fn get_most_advantageous(
     db: &Database,
     mut redis: ConnectionManager,
@@ -108,20 +636,404 @@ pub async fn get_most_advantageous(
     }
 
     let collection = db.collection::<Product>("products");
+    let now = Utc::now().timestamp();
+
+    let active_campaigns = campaign_service::get_active_campaigns(db, now).await?;
+
+    // Products under a running campaign are ranked by the campaign discount instead
+    let campaign_product_ids: Vec<Uuid> = active_campaigns
+        .iter()
+        .filter_map(|campaign| Uuid::parse_str(&campaign.product_id).ok())
+        .collect();
 
     let options = FindOneOptions::builder()
         .sort(doc! { "discount": -1 })
         .build();
 
-    let best_product = collection
-        .find_one(doc! {})
-        .with_options(options)
+    let mut filter = published_filter();
+    filter.insert("_id", doc! { "$nin": &campaign_product_ids });
+
+    let best_regular = collection.find_one(filter).with_options(options).await?;
+
+    let mut best_on_campaign = None;
+    for campaign in &active_campaigns {
+        let Ok(uuid) = Uuid::parse_str(&campaign.product_id) else {
+            continue;
+        };
+
+        let mut filter = published_filter();
+        filter.insert("_id", uuid);
+
+        if let Some(mut product) = collection.find_one(filter).await? {
+            product.discount = campaign.discount;
+            best_on_campaign = Some(product);
+            break;
+        }
+    }
+
+    let best_product = match (best_regular, best_on_campaign) {
+        (Some(regular), Some(on_campaign)) if on_campaign.discount > regular.discount => {
+            on_campaign
+        }
+        (Some(regular), _) => regular,
+        (None, on_campaign) => on_campaign.ok_or(AppErrors::NotFound("Product".to_string()))?,
+    };
+
+    // Expire together with the nearest campaign boundary so the result never goes stale
+    let ttl = campaign_service::seconds_until_next_boundary(db, now)
         .await?
-        .ok_or(AppErrors::NotFound("Product".to_string()))?;
+        .map_or(3600, |seconds| seconds.clamp(1, 3600)) as u64;
 
     if let Ok(json) = serde_json::to_string(&best_product) {
-        let _: std::result::Result<(), _> = redis.set_ex("most_advantageous", json, 3600).await;
+        let _: std::result::Result<(), _> = redis.set_ex("most_advantageous", json, ttl).await;
     }
 
     Ok(best_product)
 }
+
+/// Lines the products up attribute by attribute. Attributes only mean the
+/// same thing within a category, so mixed categories are rejected.
+fn build_comparison(
+    products: Vec<Product>,
+    schema: Option<&CategorySchema>,
+) -> Result<ProductComparison, AppErrors> {
+    let category = products.first().map_or(0, |p| p.category);
+
+    if products.iter().any(|p| p.category != category) {
+        return Err(AppErrors::BadRequest(
+            "Only products from the same category can be compared".to_string(),
+        ));
+    }
+
+    let present: BTreeSet<&String> = products.iter().flat_map(|p| p.attributes.keys()).collect();
+
+    let mut keys: Vec<&String> = schema
+        .map(|s| s.attributes.iter().map(|d| &d.key).collect())
+        .unwrap_or_default();
+    keys.retain(|key| present.contains(key));
+    let others: Vec<&String> = present
+        .iter()
+        .copied()
+        .filter(|key| !keys.contains(key))
+        .collect();
+    keys.extend(others);
+
+    let attributes = keys
+        .into_iter()
+        .map(|key| {
+            let definition = schema.and_then(|s| s.definition(key));
+            let values: Vec<Option<AttributeValue>> = products
+                .iter()
+                .map(|p| p.attributes.get(key).cloned())
+                .collect();
+
+            ComparedAttribute {
+                key: key.clone(),
+                label: definition.map_or_else(|| key.clone(), |d| d.label.clone()),
+                unit: definition.and_then(|d| d.unit.clone()),
+                differs: values.iter().any(|value| *value != values[0]),
+                values,
+            }
+        })
+        .collect();
+
+    Ok(ProductComparison {
+        category,
+        products: products.into_iter().map(Deal::from).collect(),
+        attributes,
+    })
+}
+
+pub async fn compare_products(
+    db: &Database,
+    query: &CompareQuery,
+    currency: Option<Currency>,
+) -> Result<ProductComparison, AppErrors> {
+    let ids = query.product_ids()?;
+
+    let mut filter = published_filter();
+    filter.insert(
+        "_id",
+        doc! { "$in": ids.iter().map(|id| Bson::from(*id)).collect::<Vec<Bson>>() },
+    );
+
+    let mut found: Vec<Product> = db
+        .collection::<Product>("products")
+        .find(filter)
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut products = ids
+        .iter()
+        .map(|id| {
+            let index = found
+                .iter()
+                .position(|p| p._id == *id)
+                .ok_or_else(|| AppErrors::NotFound(id.to_string()))?;
+            Ok(found.swap_remove(index))
+        })
+        .collect::<Result<Vec<Product>, AppErrors>>()?;
+
+    campaign_service::apply_active_campaigns(db, &mut products).await?;
+    exchange_rate_service::convert_products(db, &mut products, currency).await?;
+
+    let schema = attribute_service::find_schema(db, products[0].category).await?;
+
+    build_comparison(products, schema.as_ref())
+}
+
+fn deals_cache_key(query: &DealsQuery, limit: usize) -> String {
+    let rank_by = match query.rank_by {
+        DealRanking::Savings => "savings",
+        DealRanking::Percent => "percent",
+    };
+    let category = query
+        .category
+        .map_or_else(|| "all".to_string(), |c| c.to_string());
+
+    format!("deals:{}:{}:{}", rank_by, limit, category)
+}
+
+/// Orders discounted products best deal first and keeps the top `limit`
+/// overall and within every category
+fn rank_deals(products: Vec<Product>, rank_by: DealRanking, limit: usize) -> DealsResponse {
+    let mut deals: Vec<Deal> = products
+        .into_iter()
+        .filter(|product| product.discount > 0)
+        .map(Deal::from)
+        .collect();
+
+    deals.sort_by(|a, b| {
+        let by_savings = b.savings.amount.cmp(&a.savings.amount);
+        let by_percent = b.product.discount.cmp(&a.product.discount);
+
+        match rank_by {
+            DealRanking::Savings => by_savings.then(by_percent),
+            DealRanking::Percent => by_percent.then(by_savings),
+        }
+        .then_with(|| a.product.name.cmp(&b.product.name))
+    });
+
+    let mut by_category: Vec<CategoryDeals> = Vec::new();
+    for deal in &deals {
+        let category = deal.product.category;
+        match by_category.iter_mut().find(|c| c.category == category) {
+            Some(entry) if entry.deals.len() < limit => entry.deals.push(deal.clone()),
+            Some(_) => {}
+            None => by_category.push(CategoryDeals {
+                category,
+                deals: vec![deal.clone()],
+            }),
+        }
+    }
+    by_category.sort_by_key(|c| c.category);
+
+    deals.truncate(limit);
+
+    DealsResponse {
+        overall: deals,
+        by_category,
+    }
+}
+
+pub async fn get_deals(
+    db: &Database,
+    mut redis: ConnectionManager,
+    query: &DealsQuery,
+) -> Result<DealsResponse, AppErrors> {
+    let limit = query.limit.unwrap_or(DEFAULT_DEALS_LIMIT);
+    let cache_key = deals_cache_key(query, limit);
+
+    if let Ok(cached_json) = redis.get::<_, String>(&cache_key).await {
+        if let Ok(deals) = serde_json::from_str::<DealsResponse>(&cached_json) {
+            return Ok(deals);
+        }
+    }
+
+    let collection = db.collection::<Product>("products");
+    let now = Utc::now().timestamp();
+
+    let mut filter = published_filter();
+    if let Some(category) = query.category {
+        filter.insert("category", i32::from(category));
+    }
+
+    let mut products: Vec<Product> = collection.find(filter).await?.try_collect().await?;
+    campaign_service::apply_active_campaigns(db, &mut products).await?;
+
+    let deals = rank_deals(products, query.rank_by, limit);
+
+    let ttl = campaign_service::seconds_until_next_boundary(db, now)
+        .await?
+        .map_or(3600, |seconds| seconds.clamp(1, 3600)) as u64;
+
+    if let Ok(json) = serde_json::to_string(&deals) {
+        let _: std::result::Result<(), _> = redis.set_ex(&cache_key, json, ttl).await;
+    }
+
+    Ok(deals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        attribute::{AttributeDefinition, AttributeKind},
+        product::ProductBuilder,
+    };
+
+    fn names(deals: &[Deal]) -> Vec<&str> {
+        deals.iter().map(|d| d.product.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_ranks_by_savings_or_percent() {
+        let products = vec![
+            ProductBuilder::new()
+                .name("cheap")
+                .category(1)
+                .price(1_000)
+                .discount(50)
+                .build(),
+            ProductBuilder::new()
+                .name("pricey")
+                .category(1)
+                .price(100_000)
+                .discount(10)
+                .build(),
+            ProductBuilder::new()
+                .name("full")
+                .category(2)
+                .price(5_000)
+                .build(),
+        ];
+
+        let by_savings = rank_deals(products.clone(), DealRanking::Savings, 10);
+        assert_eq!(names(&by_savings.overall), vec!["pricey", "cheap"]);
+        assert_eq!(by_savings.overall[0].savings.amount, 10_000);
+        assert_eq!(by_savings.overall[0].final_price.amount, 90_000);
+
+        let by_percent = rank_deals(products, DealRanking::Percent, 10);
+        assert_eq!(names(&by_percent.overall), vec!["cheap", "pricey"]);
+    }
+
+    #[test]
+    fn test_limits_overall_and_each_category() {
+        let products = vec![
+            ProductBuilder::new()
+                .name("a")
+                .category(2)
+                .price(1_000)
+                .discount(10)
+                .build(),
+            ProductBuilder::new()
+                .name("b")
+                .category(1)
+                .price(2_000)
+                .discount(10)
+                .build(),
+            ProductBuilder::new()
+                .name("c")
+                .category(1)
+                .price(3_000)
+                .discount(10)
+                .build(),
+            ProductBuilder::new()
+                .name("d")
+                .category(1)
+                .price(4_000)
+                .discount(10)
+                .build(),
+        ];
+
+        let deals = rank_deals(products, DealRanking::Savings, 2);
+
+        assert_eq!(names(&deals.overall), vec!["d", "c"]);
+        assert_eq!(deals.by_category.len(), 2);
+        assert_eq!(deals.by_category[0].category, 1);
+        assert_eq!(names(&deals.by_category[0].deals), vec!["d", "c"]);
+        assert_eq!(names(&deals.by_category[1].deals), vec!["a"]);
+    }
+
+    fn with_attributes(mut product: Product, json: &str) -> Product {
+        product.attributes = serde_json::from_str(json).unwrap();
+        product
+    }
+
+    #[test]
+    fn test_comparison_aligns_attributes_and_flags_differences() {
+        let schema = CategorySchema {
+            _id: Uuid::new_v4(),
+            category: 1,
+            attributes: vec![AttributeDefinition {
+                key: "wheel_size".to_string(),
+                label: "Wheel size".to_string(),
+                kind: AttributeKind::Number,
+                options: vec![],
+                unit: Some("in".to_string()),
+                required: false,
+            }],
+            updated_at: 0,
+        };
+        let products = vec![
+            with_attributes(
+                ProductBuilder::new()
+                    .name("trail")
+                    .category(1)
+                    .price(100_000)
+                    .discount(10)
+                    .build(),
+                r#"{"color": "red", "wheel_size": 29}"#,
+            ),
+            with_attributes(
+                ProductBuilder::new()
+                    .name("gravel")
+                    .category(1)
+                    .price(80_000)
+                    .build(),
+                r#"{"color": "red", "wheel_size": 28, "disc_brakes": true}"#,
+            ),
+        ];
+
+        let comparison = build_comparison(products, Some(&schema)).unwrap();
+
+        let keys: Vec<&str> = comparison
+            .attributes
+            .iter()
+            .map(|a| a.key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["wheel_size", "color", "disc_brakes"]);
+
+        let wheel = &comparison.attributes[0];
+        assert_eq!(wheel.label, "Wheel size");
+        assert!(wheel.differs);
+        assert!(!comparison.attributes[1].differs);
+        assert_eq!(
+            comparison.attributes[2].values,
+            vec![None, Some(AttributeValue::Boolean(true))]
+        );
+        assert_eq!(comparison.products[0].final_price.amount, 90_000);
+    }
+
+    #[test]
+    fn test_comparison_rejects_mixed_categories() {
+        let products = vec![
+            ProductBuilder::new()
+                .name("helmet")
+                .category(0)
+                .price(5_000)
+                .build(),
+            ProductBuilder::new()
+                .name("bike")
+                .category(1)
+                .price(80_000)
+                .build(),
+        ];
+
+        assert!(matches!(
+            build_comparison(products, None),
+            Err(AppErrors::BadRequest(_))
+        ));
+    }
+}
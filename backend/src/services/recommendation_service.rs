@@ -0,0 +1,163 @@
+use std::env;
+
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, Bson};
+use mongodb::options::IndexOptions;
+use mongodb::{Database, IndexModel};
+use uuid::Uuid;
+
+use crate::{
+    dto::product::RelatedQuery,
+    errors::AppErrors,
+    models::{
+        order::Order,
+        product::Product,
+        product_pair::{count_pairs, order_pairs, ProductPair},
+    },
+    services::{campaign_service, product_service},
+};
+
+const DEFAULT_RELATED_LIMIT: usize = 5;
+
+/// Pairs seen in fewer orders than this are treated as noise
+fn get_min_pair_count() -> u32 {
+    env::var("RELATED_MIN_ORDERS")
+        .ok()
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(2)
+}
+
+/// Counts the products of a new order as bought together
+pub async fn record_order(db: &Database, products_id: &[String]) -> Result<(), AppErrors> {
+    let collection = db.collection::<ProductPair>("product_pairs");
+
+    for (product_id, related_id) in order_pairs(products_id) {
+        collection
+            .update_one(
+                doc! { "product_id": &product_id, "related_id": &related_id },
+                doc! {
+                    "$inc": { "count": 1 },
+                    "$setOnInsert": { "_id": Uuid::new_v4() },
+                },
+            )
+            .upsert(true)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Recomputes all pairs from the full order history. The pairs are written
+/// to a scratch collection that then replaces the live one in a single step,
+/// so readers never see a half-built table. Orders placed during the rebuild
+/// are only counted by the next one.
+pub async fn rebuild_pairs(db: &Database) -> Result<usize, AppErrors> {
+    const SCRATCH: &str = "product_pairs_rebuild";
+
+    let mut orders = Vec::new();
+    let mut cursor = db.collection::<Order>("orders").find(doc! {}).await?;
+    while let Some(order) = cursor.try_next().await? {
+        orders.push(order.unit_product_ids());
+    }
+    let pairs = count_pairs(orders);
+
+    let scratch = db.collection::<ProductPair>(SCRATCH);
+    scratch.drop().await?;
+    scratch
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "product_id": 1, "related_id": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+        )
+        .await?;
+    if !pairs.is_empty() {
+        scratch.insert_many(&pairs).await?;
+    }
+
+    db.client()
+        .database("admin")
+        .run_command(doc! {
+            "renameCollection": format!("{}.{}", db.name(), SCRATCH),
+            "to": format!("{}.product_pairs", db.name()),
+            "dropTarget": true,
+        })
+        .await?;
+
+    Ok(pairs.len())
+}
+
+async fn find_published(db: &Database, ids: Vec<Uuid>) -> Result<Vec<Product>, AppErrors> {
+    let collection = db.collection::<Product>("products");
+
+    let mut filter = product_service::published_filter();
+    filter.insert("_id", doc! { "$in": ids });
+
+    let products = collection.find(filter).await?.try_collect().await?;
+    Ok(products)
+}
+
+/// Products most often bought together with `product_id`, topped up with
+/// products from the same category when the order history is too thin
+pub async fn get_related(
+    db: &Database,
+    product_id: &str,
+    query: &RelatedQuery,
+) -> Result<Vec<Product>, AppErrors> {
+    let limit = query.limit.unwrap_or(DEFAULT_RELATED_LIMIT);
+    let uuid = Uuid::parse_str(product_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let product = find_published(db, vec![uuid])
+        .await?
+        .pop()
+        .ok_or_else(|| AppErrors::NotFound("Product".to_string()))?;
+
+    let pairs: Vec<ProductPair> = db
+        .collection::<ProductPair>("product_pairs")
+        .find(doc! { "product_id": product_id, "count": { "$gte": get_min_pair_count() } })
+        .sort(doc! { "count": -1 })
+        .limit(limit as i64 * 3)
+        .await?
+        .try_collect()
+        .await?;
+
+    let pair_ids: Vec<Uuid> = pairs
+        .iter()
+        .filter_map(|pair| Uuid::parse_str(&pair.related_id).ok())
+        .collect();
+
+    // Unpublished products drop out here, so keep the pair order by hand
+    let mut found = find_published(db, pair_ids.clone()).await?;
+    let mut related: Vec<Product> = pair_ids
+        .iter()
+        .filter_map(|id| {
+            let index = found.iter().position(|p| p._id == *id)?;
+            Some(found.swap_remove(index))
+        })
+        .take(limit)
+        .collect();
+
+    if related.len() < limit {
+        let mut exclude: Vec<Bson> = related.iter().map(|p| Bson::from(p._id)).collect();
+        exclude.push(Bson::from(uuid));
+
+        let mut filter = product_service::published_filter();
+        filter.insert("category", i32::from(product.category));
+        filter.insert("_id", doc! { "$nin": exclude });
+
+        let fallback: Vec<Product> = db
+            .collection::<Product>("products")
+            .find(filter)
+            .sort(doc! { "discount": -1, "name": 1 })
+            .limit((limit - related.len()) as i64)
+            .await?
+            .try_collect()
+            .await?;
+
+        related.extend(fallback);
+    }
+
+    campaign_service::apply_active_campaigns(db, &mut related).await?;
+
+    Ok(related)
+}
@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+use std::env;
+
+use actix_web::web;
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use mongodb::{bson::doc, Database};
+use uuid::Uuid;
+
+use crate::{
+    dto::{order::PriceBreakdown, tax::SetTaxRuleDto},
+    errors::AppErrors,
+    models::{
+        coupon::Coupon,
+        money::Money,
+        tax::{TaxLine, TaxMode, TaxRule},
+    },
+    services::coupon_service,
+};
+
+/// Whether catalog prices include tax, from `TAX_PRICE_MODE`. Panics on an
+/// unknown mode rather than charge every customer the wrong amount.
+pub fn get_tax_mode() -> TaxMode {
+    parse_tax_mode(env::var("TAX_PRICE_MODE").ok().as_deref())
+        .unwrap_or_else(|e| panic!("Invalid TAX_PRICE_MODE: {}", e))
+}
+
+fn parse_tax_mode(value: Option<&str>) -> Result<TaxMode, String> {
+    let Some(mode) = value else {
+        return Ok(TaxMode::Exclusive);
+    };
+
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "inclusive" => Ok(TaxMode::Inclusive),
+        "exclusive" => Ok(TaxMode::Exclusive),
+        other => Err(format!("Unknown tax mode: {}", other)),
+    }
+}
+
+/// Country orders without one are taxed in; untaxed when unset
+pub fn get_shop_country() -> Option<String> {
+    env::var("SHOP_COUNTRY")
+        .ok()
+        .map(|country| normalize(&country))
+        .filter(|country| !country.is_empty())
+}
+
+/// Country and region codes are stored in upper case without surrounding spaces
+pub fn normalize(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+pub async fn set_rule(
+    db: &Database,
+    admin_id: String,
+    data: web::Json<SetTaxRuleDto>,
+) -> Result<String, AppErrors> {
+    let data = data.into_inner();
+    let region = data.region.as_deref().map(normalize);
+    let category = data.category.map(i32::from);
+
+    // Missing region and category are stored as null so the unique index
+    // tells a country-wide rule apart from a narrower one
+    db.collection::<TaxRule>("tax_rules")
+        .update_one(
+            doc! {"country": normalize(&data.country), "region": region, "category": category},
+            doc! {
+                "$set": {
+                    "name": data.name.trim(),
+                    "rate": data.rate,
+                    "updated_at": Utc::now().timestamp(),
+                    "updated_by": admin_id,
+                },
+                "$setOnInsert": {"_id": Uuid::new_v4()},
+            },
+        )
+        .upsert(true)
+        .await?;
+
+    Ok(String::from("Tax rule saved successfully"))
+}
+
+pub async fn get_rules(db: &Database, country: Option<&str>) -> Result<Vec<TaxRule>, AppErrors> {
+    let filter = match country {
+        Some(country) => doc! {"country": normalize(country)},
+        None => doc! {},
+    };
+
+    let rules: Vec<TaxRule> = db
+        .collection::<TaxRule>("tax_rules")
+        .find(filter)
+        .sort(doc! {"country": 1, "region": 1, "category": 1})
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(rules)
+}
+
+/// Orders placed before keep the tax they were charged
+pub async fn delete_rule(db: &Database, rule_id: &str) -> Result<String, AppErrors> {
+    let uuid = Uuid::parse_str(rule_id).map_err(|_| AppErrors::InvalidUUID)?;
+
+    let result = db
+        .collection::<TaxRule>("tax_rules")
+        .delete_one(doc! {"_id": uuid})
+        .await?;
+
+    if result.deleted_count == 0 {
+        return Err(AppErrors::NotFound("Tax rule".to_string()));
+    }
+
+    Ok(String::from("Tax rule deleted successfully"))
+}
+
+/// Splits the coupon discount over the lines it covers in proportion to
+/// their totals; the last covered line takes the rounding remainder
+fn coupon_shares(discount: u64, amounts: &[u64], covered: &[bool]) -> Vec<u64> {
+    let eligible: u64 = amounts
+        .iter()
+        .zip(covered)
+        .filter(|(_, covered)| **covered)
+        .map(|(amount, _)| amount)
+        .sum();
+    let mut shares = vec![0; amounts.len()];
+
+    if eligible == 0 {
+        return shares;
+    }
+
+    let last = covered.iter().rposition(|covered| *covered);
+    let mut left = discount;
+    for (i, amount) in amounts.iter().enumerate() {
+        if !covered[i] {
+            continue;
+        }
+        let share = if Some(i) == last {
+            left
+        } else {
+            (u128::from(discount) * u128::from(*amount) / u128::from(eligible)) as u64
+        };
+        shares[i] = share.min(left);
+        left -= shares[i];
+    }
+
+    shares
+}
+
+/// Adds tax lines for shipping to `country` and `region`. Lines are taxed
+/// after their discounts and their share of the coupon, under the most
+/// specific matching rule; bundles only match rules without a category.
+/// Exclusive taxes are added to the total, inclusive ones are already in it.
+pub fn apply_taxes(
+    pricing: &mut PriceBreakdown,
+    rules: &[TaxRule],
+    country: &str,
+    region: Option<&str>,
+    categories: &HashMap<String, u8>,
+    coupon: Option<&Coupon>,
+    mode: TaxMode,
+) -> Result<(), AppErrors> {
+    let currency = pricing.total.currency;
+
+    let line_categories: Vec<Option<u8>> = pricing
+        .items
+        .iter()
+        .map(|item| categories.get(&item.product_id).copied())
+        .chain(pricing.bundles.iter().map(|_| None))
+        .collect();
+    let mut amounts: Vec<u64> = pricing
+        .items
+        .iter()
+        .map(|item| item.total.amount)
+        .chain(pricing.bundles.iter().map(|bundle| bundle.total.amount))
+        .collect();
+
+    if let (Some(coupon), Some(applied)) = (coupon, &pricing.coupon) {
+        let covered = coupon_service::covered_lines(coupon, pricing, categories);
+        let shares = coupon_shares(applied.discount.amount, &amounts, &covered);
+        for (amount, share) in amounts.iter_mut().zip(shares) {
+            *amount = amount.saturating_sub(share);
+        }
+    }
+
+    let mut groups: Vec<(&TaxRule, u64)> = Vec::new();
+    for (category, amount) in line_categories.into_iter().zip(amounts) {
+        let Some(rule) = rules
+            .iter()
+            .filter(|rule| rule.matches(country, region, category))
+            .max_by_key(|rule| rule.specificity())
+        else {
+            continue;
+        };
+
+        match groups.iter_mut().find(|(seen, _)| seen._id == rule._id) {
+            Some((_, sum)) => *sum += amount,
+            None => groups.push((rule, amount)),
+        }
+    }
+
+    let mut taxes = Vec::new();
+    let mut tax = Money::zero(currency);
+    for (rule, amount) in groups {
+        let amount = Money::new(amount, currency);
+        let (taxable, line_tax) = match mode {
+            TaxMode::Exclusive => (amount, rule.tax_on_net(amount)),
+            TaxMode::Inclusive => {
+                let line_tax = rule.tax_in_gross(amount);
+                (
+                    Money::new(amount.amount - line_tax.amount, currency),
+                    line_tax,
+                )
+            }
+        };
+
+        tax = tax
+            .checked_add(line_tax)
+            .ok_or_else(|| AppErrors::BadRequest("Order total is out of range".to_string()))?;
+        taxes.push(TaxLine {
+            rule_id: rule._id.to_string(),
+            name: rule.name.clone(),
+            country: rule.country.clone(),
+            region: rule.region.clone(),
+            category: rule.category,
+            rate: rule.rate,
+            taxable,
+            amount: line_tax,
+        });
+    }
+
+    if mode == TaxMode::Exclusive {
+        pricing.total = pricing
+            .total
+            .checked_add(tax)
+            .ok_or_else(|| AppErrors::BadRequest("Order total is out of range".to_string()))?;
+    }
+    pricing.tax_mode = mode;
+    pricing.taxes = taxes;
+    pricing.tax = tax;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        coupon::{AppliedCoupon, CouponDiscount},
+        order::{OrderBundle, OrderItem},
+    };
+
+    fn pricing(items: Vec<OrderItem>, bundles: Vec<OrderBundle>) -> PriceBreakdown {
+        let total = items
+            .iter()
+            .map(|i| i.total.amount)
+            .chain(bundles.iter().map(|b| b.total.amount))
+            .sum();
+
+        PriceBreakdown {
+            items,
+            bundles,
+            subtotal: Money::usd(total),
+            discount: Money::usd(0),
+            coupon: None,
+            tax_mode: TaxMode::Exclusive,
+            taxes: vec![],
+            tax: Money::usd(0),
+            total: Money::usd(total),
+        }
+    }
+
+    fn categories() -> HashMap<String, u8> {
+        HashMap::from([("bike".to_string(), 0), ("helmet".to_string(), 1)])
+    }
+
+    #[test]
+    fn test_exclusive_tax_is_added_per_most_specific_rule() {
+        let rules = vec![
+            TaxRule::us(None, None, 500),
+            TaxRule::us(Some("CA"), None, 725),
+            TaxRule::us(Some("CA"), Some(1), 0),
+        ];
+        let mut pricing = pricing(
+            vec![
+                OrderItem::single("bike", 100_000),
+                OrderItem::single("helmet", 5_000),
+            ],
+            vec![],
+        );
+
+        apply_taxes(
+            &mut pricing,
+            &rules,
+            "US",
+            Some("CA"),
+            &categories(),
+            None,
+            TaxMode::Exclusive,
+        )
+        .unwrap();
+
+        assert_eq!(pricing.taxes.len(), 2);
+        assert_eq!(pricing.taxes[0].rate, 725);
+        assert_eq!(pricing.taxes[0].taxable, Money::usd(100_000));
+        assert_eq!(pricing.taxes[0].amount, Money::usd(7_250));
+        assert_eq!(pricing.taxes[1].amount, Money::usd(0));
+        assert_eq!(pricing.tax, Money::usd(7_250));
+        assert_eq!(pricing.total, Money::usd(112_250));
+    }
+
+    #[test]
+    fn test_inclusive_tax_is_broken_out_of_the_total() {
+        let mut rule = TaxRule::us(None, None, 2_000);
+        rule.country = "DE".to_string();
+        let mut pricing = pricing(vec![OrderItem::single("bike", 12_000)], vec![]);
+
+        apply_taxes(
+            &mut pricing,
+            &[rule],
+            "DE",
+            None,
+            &categories(),
+            None,
+            TaxMode::Inclusive,
+        )
+        .unwrap();
+
+        assert_eq!(pricing.taxes[0].taxable, Money::usd(10_000));
+        assert_eq!(pricing.tax, Money::usd(2_000));
+        assert_eq!(pricing.total, Money::usd(12_000));
+        assert_eq!(pricing.tax_mode, TaxMode::Inclusive);
+    }
+
+    #[test]
+    fn test_coupon_lowers_the_taxable_amount_of_covered_lines() {
+        let coupon = Coupon::unlimited(
+            CouponDiscount::Fixed {
+                amount: Money::usd(1_000),
+            },
+            vec![1],
+        );
+        let rules = vec![
+            TaxRule::us(None, Some(0), 1_000),
+            TaxRule::us(None, Some(1), 500),
+        ];
+        let mut pricing = pricing(
+            vec![
+                OrderItem::single("bike", 100_000),
+                OrderItem::single("helmet", 5_000),
+            ],
+            vec![],
+        );
+        pricing.coupon = Some(AppliedCoupon {
+            coupon_id: coupon._id.to_string(),
+            code: coupon.code.clone(),
+            discount: Money::usd(1_000),
+        });
+        pricing.total = Money::usd(104_000);
+
+        apply_taxes(
+            &mut pricing,
+            &rules,
+            "US",
+            None,
+            &categories(),
+            Some(&coupon),
+            TaxMode::Exclusive,
+        )
+        .unwrap();
+
+        assert_eq!(pricing.taxes[0].taxable, Money::usd(100_000));
+        assert_eq!(pricing.taxes[1].taxable, Money::usd(4_000));
+        assert_eq!(pricing.tax, Money::usd(10_200));
+        assert_eq!(pricing.total, Money::usd(114_200));
+    }
+
+    #[test]
+    fn test_no_matching_rule_means_no_tax() {
+        let mut pricing = pricing(vec![OrderItem::single("bike", 100_000)], vec![]);
+
+        apply_taxes(
+            &mut pricing,
+            &[TaxRule::us(Some("CA"), None, 725)],
+            "US",
+            Some("OR"),
+            &categories(),
+            None,
+            TaxMode::Exclusive,
+        )
+        .unwrap();
+
+        assert!(pricing.taxes.is_empty());
+        assert_eq!(pricing.tax, Money::usd(0));
+        assert_eq!(pricing.total, Money::usd(100_000));
+    }
+
+    #[test]
+    fn test_coupon_shares_add_up_to_the_discount() {
+        let shares = coupon_shares(1_000, &[3_333, 5_000, 3_333], &[true, false, true]);
+
+        assert_eq!(shares, vec![500, 0, 500]);
+        assert_eq!(
+            coupon_shares(100, &[333, 333, 334], &[true, true, true]),
+            vec![33, 33, 34]
+        );
+    }
+
+    #[test]
+    fn test_parse_tax_mode() {
+        assert_eq!(parse_tax_mode(Some("Inclusive")), Ok(TaxMode::Inclusive));
+        assert_eq!(parse_tax_mode(Some("exclusive")), Ok(TaxMode::Exclusive));
+        assert!(parse_tax_mode(Some("inclusve")).is_err());
+        assert_eq!(parse_tax_mode(None), Ok(TaxMode::Exclusive));
+    }
+}
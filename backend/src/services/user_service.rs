@@ -7,6 +7,7 @@ use crate::{
     dto::{auth::UserInfo, user::UpdateUserDto},
     errors::AppErrors,
     models::{order::Order, user::User},
+    services::{alert_service, notification_service, wishlist_service},
 };
 
 pub async fn me(db: &Database, user_id: String) -> Result<UserInfo, AppErrors> {
@@ -60,6 +61,10 @@ pub async fn delete_user(db: &Database, user_id: String) -> Result<String, AppEr
         return Err(AppErrors::NotFound("User".to_string()));
     }
 
+    wishlist_service::delete_wishlist(db, &uuid.to_string()).await?;
+    alert_service::delete_alerts(db, &uuid.to_string()).await?;
+    notification_service::delete_inbox(db, &uuid.to_string()).await?;
+
     Ok(String::from("User deleted successfully"))
 }
 
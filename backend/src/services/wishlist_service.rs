@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, Bson};
+use mongodb::Database;
+use redis::aio::ConnectionManager;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    dto::{order::OrderItemDto, wishlist::WishlistEntry},
+    errors::AppErrors,
+    models::{cart::CartOwner, product::Product, wishlist::WishlistItem},
+    services::{campaign_service, cart_service, product_service},
+};
+
+fn parse_product_id(product_id: &str) -> Result<Uuid, AppErrors> {
+    Uuid::parse_str(product_id).map_err(|_| AppErrors::InvalidUUID)
+}
+
+/// Adds a published product to the wishlist. Adding it again keeps the
+/// original date.
+pub async fn add_item(db: &Database, user_id: &str, product_id: &str) -> Result<String, AppErrors> {
+    let uuid = parse_product_id(product_id)?;
+
+    let mut filter = product_service::published_filter();
+    filter.insert("_id", uuid);
+
+    if db
+        .collection::<Product>("products")
+        .count_documents(filter)
+        .await?
+        == 0
+    {
+        return Err(AppErrors::NotFound("Product".to_string()));
+    }
+
+    db.collection::<WishlistItem>("wishlists")
+        .update_one(
+            doc! { "user_id": user_id, "product_id": uuid.to_string() },
+            doc! {
+                "$setOnInsert": {
+                    "_id": Bson::from(Uuid::new_v4()),
+                    "added_at": Utc::now().timestamp(),
+                }
+            },
+        )
+        .upsert(true)
+        .await?;
+
+    Ok(String::from("Product added to wishlist"))
+}
+
+pub async fn remove_item(
+    db: &Database,
+    user_id: &str,
+    product_id: &str,
+) -> Result<String, AppErrors> {
+    let uuid = parse_product_id(product_id)?;
+
+    let result = db
+        .collection::<WishlistItem>("wishlists")
+        .delete_one(doc! { "user_id": user_id, "product_id": uuid.to_string() })
+        .await?;
+
+    if result.deleted_count == 0 {
+        return Err(AppErrors::NotFound("Wishlist item".to_string()));
+    }
+
+    Ok(String::from("Product removed from wishlist"))
+}
+
+/// Newest first, with the current product data. Products that were archived
+/// or deleted since stay on the list, marked as unavailable.
+pub async fn get_wishlist(db: &Database, user_id: &str) -> Result<Vec<WishlistEntry>, AppErrors> {
+    let items: Vec<WishlistItem> = db
+        .collection::<WishlistItem>("wishlists")
+        .find(doc! { "user_id": user_id })
+        .sort(doc! { "added_at": -1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    let ids: Vec<Bson> = items
+        .iter()
+        .filter_map(|item| Uuid::parse_str(&item.product_id).ok())
+        .map(Bson::from)
+        .collect();
+
+    let mut products: Vec<Product> = db
+        .collection::<Product>("products")
+        .find(doc! { "_id": { "$in": ids } })
+        .await?
+        .try_collect()
+        .await?;
+
+    campaign_service::apply_active_campaigns(db, &mut products).await?;
+
+    let mut products: HashMap<String, Product> = products
+        .into_iter()
+        .map(|product| (product._id.to_string(), product))
+        .collect();
+
+    Ok(items
+        .into_iter()
+        .map(|item| {
+            let product = products.remove(&item.product_id);
+            WishlistEntry::new(item.product_id, item.added_at, product)
+        })
+        .collect())
+}
+
+/// Puts one unit of the product into the user's cart and takes it off the
+/// wishlist. Products that can no longer be ordered stay on the wishlist.
+pub async fn move_to_cart(
+    db: &Database,
+    redis: ConnectionManager,
+    user_id: &str,
+    product_id: &str,
+) -> Result<Product, AppErrors> {
+    let uuid = parse_product_id(product_id)?;
+    let collection = db.collection::<WishlistItem>("wishlists");
+    let filter = doc! { "user_id": user_id, "product_id": uuid.to_string() };
+
+    if collection.count_documents(filter.clone()).await? == 0 {
+        return Err(AppErrors::NotFound("Wishlist item".to_string()));
+    }
+
+    let product = match product_service::get_product(db, &uuid.to_string()).await {
+        Err(AppErrors::NotFound(_)) => {
+            return Err(AppErrors::BadRequest(
+                "Product is no longer available".to_string(),
+            ))
+        }
+        result => result?,
+    };
+
+    let item = OrderItemDto {
+        product_id: uuid.to_string(),
+        variant_id: None,
+        quantity: 1,
+    };
+    cart_service::add_item(db, redis, &CartOwner::User(user_id.to_string()), item).await?;
+
+    collection.delete_one(filter).await?;
+
+    Ok(product)
+}
+
+/// Removes the wishlist of a deleted account
+pub async fn delete_wishlist(db: &Database, user_id: &str) -> Result<(), AppErrors> {
+    db.collection::<WishlistItem>("wishlists")
+        .delete_many(doc! { "user_id": user_id })
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ProductCount {
+    _id: String,
+    count: u32,
+}
+
+/// Number of wishlists every product is on
+pub async fn count_by_product(db: &Database) -> Result<HashMap<String, u32>, AppErrors> {
+    let pipeline = vec![doc! { "$group": { "_id": "$product_id", "count": { "$sum": 1 } } }];
+
+    let counts: Vec<ProductCount> = db
+        .collection::<WishlistItem>("wishlists")
+        .aggregate(pipeline)
+        .with_type::<ProductCount>()
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(counts.into_iter().map(|c| (c._id, c.count)).collect())
+}
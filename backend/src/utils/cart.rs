@@ -0,0 +1,84 @@
+use actix_web::HttpRequest;
+use uuid::Uuid;
+
+use crate::{errors::AppErrors, models::cart::CartOwner, utils::jwt};
+
+pub const CART_TOKEN_HEADER: &str = "X-Cart-Token";
+
+/// Guest cart token sent by the client, if any
+pub fn guest_token(req: &HttpRequest) -> Result<Option<Uuid>, AppErrors> {
+    req.headers()
+        .get(CART_TOKEN_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(|token| {
+            Uuid::parse_str(token.trim())
+                .map_err(|_| AppErrors::BadRequest("Invalid cart token".to_string()))
+        })
+        .transpose()
+}
+
+/// The signed-in user's cart, otherwise the guest cart named by
+/// `X-Cart-Token`. Guests without a token get a new one.
+pub fn requested_cart(req: &HttpRequest) -> Result<CartOwner, AppErrors> {
+    let bearer = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+
+    if let Some(token) = bearer {
+        let claims = jwt::validate_token(token.to_string())?;
+        return Ok(CartOwner::User(claims.sub));
+    }
+
+    Ok(CartOwner::Guest(
+        guest_token(req)?.unwrap_or_else(Uuid::new_v4),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::role::Role;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_signed_in_users_get_their_own_cart() {
+        let token = jwt::generate_access_token("user".to_string(), Role::User).unwrap();
+        let req = TestRequest::get()
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .insert_header((CART_TOKEN_HEADER, Uuid::new_v4().to_string()))
+            .to_http_request();
+
+        assert_eq!(
+            requested_cart(&req).unwrap(),
+            CartOwner::User("user".to_string())
+        );
+    }
+
+    #[test]
+    fn test_guests_keep_their_token() {
+        let token = Uuid::new_v4();
+        let req = TestRequest::get()
+            .insert_header((CART_TOKEN_HEADER, token.to_string()))
+            .to_http_request();
+
+        assert_eq!(requested_cart(&req).unwrap(), CartOwner::Guest(token));
+        assert!(matches!(
+            requested_cart(&TestRequest::get().to_http_request()),
+            Ok(CartOwner::Guest(_))
+        ));
+    }
+
+    #[test]
+    fn test_malformed_tokens_are_rejected() {
+        let req = TestRequest::get()
+            .insert_header((CART_TOKEN_HEADER, "cart:user:someone"))
+            .to_http_request();
+
+        assert!(matches!(
+            requested_cart(&req),
+            Err(AppErrors::BadRequest(_))
+        ));
+    }
+}
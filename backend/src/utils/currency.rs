@@ -0,0 +1,92 @@
+use std::env;
+
+use actix_web::{web, HttpRequest};
+use serde::Deserialize;
+
+use crate::{errors::AppErrors, models::money::Currency};
+
+#[derive(Deserialize)]
+struct CurrencyQuery {
+    currency: Option<String>,
+}
+
+/// Currency all catalog prices are stored in. Panics on an unsupported code
+/// rather than label every price in the wrong currency.
+pub fn get_base_currency() -> Currency {
+    parse_base_currency(env::var("BASE_CURRENCY").ok().as_deref())
+        .unwrap_or_else(|e| panic!("Invalid BASE_CURRENCY: {}", e))
+}
+
+fn parse_base_currency(value: Option<&str>) -> Result<Currency, String> {
+    match value {
+        Some(code) => code.parse::<Currency>(),
+        None => Ok(Currency::Usd),
+    }
+}
+
+/// Reads `?currency=` or, failing that, the `Accept-Currency` header
+pub fn requested_currency(req: &HttpRequest) -> Result<Option<Currency>, AppErrors> {
+    let from_query = web::Query::<CurrencyQuery>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.into_inner().currency);
+
+    let from_header = || {
+        req.headers()
+            .get("Accept-Currency")
+            .and_then(|h| h.to_str().ok())
+            .map(str::to_string)
+    };
+
+    match from_query.or_else(from_header) {
+        Some(code) => code.parse().map(Some).map_err(AppErrors::BadRequest),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_requested_currency_prefers_query() {
+        let req = TestRequest::get()
+            .uri("/product/products?currency=eur")
+            .insert_header(("Accept-Currency", "UAH"))
+            .to_http_request();
+
+        assert_eq!(requested_currency(&req).unwrap(), Some(Currency::Eur));
+    }
+
+    #[test]
+    fn test_requested_currency_from_header() {
+        let req = TestRequest::get()
+            .insert_header(("Accept-Currency", "UAH"))
+            .to_http_request();
+
+        assert_eq!(requested_currency(&req).unwrap(), Some(Currency::Uah));
+        assert_eq!(
+            requested_currency(&TestRequest::get().to_http_request()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_requested_currency_unsupported() {
+        let req = TestRequest::get()
+            .uri("/product/products?currency=GBP")
+            .to_http_request();
+
+        assert!(matches!(
+            requested_currency(&req),
+            Err(AppErrors::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_base_currency() {
+        assert_eq!(parse_base_currency(Some("uah")), Ok(Currency::Uah));
+        assert!(parse_base_currency(Some("UHA")).is_err());
+        assert_eq!(parse_base_currency(None), Ok(Currency::Usd));
+    }
+}
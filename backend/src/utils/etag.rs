@@ -0,0 +1,71 @@
+use actix_web::{http::header, HttpRequest};
+
+use crate::errors::AppErrors;
+
+/// Strong entity tag of a document version
+pub fn etag(version: u32) -> String {
+    format!("\"{}\"", version)
+}
+
+/// Version the client expects to overwrite, from `If-Match`. `None` when the
+/// header is absent or `*`, in which case the update is unconditional.
+/// Catalog tags like `"7-12-9f3a"` carry the document version up front.
+pub fn if_match_version(req: &HttpRequest) -> Result<Option<u32>, AppErrors> {
+    let Some(value) = req.headers().get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+
+    let invalid = || AppErrors::BadRequest("Invalid If-Match header".to_string());
+
+    let value = value.to_str().map_err(|_| invalid())?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .and_then(|v| v.split('-').next())
+        .and_then(|v| v.parse::<u32>().ok())
+        .map(Some)
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn with_if_match(value: &str) -> HttpRequest {
+        TestRequest::put()
+            .insert_header((header::IF_MATCH, value))
+            .to_http_request()
+    }
+
+    #[test]
+    fn test_if_match_round_trips_etag() {
+        assert_eq!(if_match_version(&with_if_match(&etag(7))).unwrap(), Some(7));
+    }
+
+    #[test]
+    fn test_if_match_reads_version_from_catalog_tag() {
+        assert_eq!(
+            if_match_version(&with_if_match("\"7-12-9f3a\"")).unwrap(),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn test_if_match_absent_or_wildcard_is_unconditional() {
+        let req = TestRequest::put().to_http_request();
+
+        assert_eq!(if_match_version(&req).unwrap(), None);
+        assert_eq!(if_match_version(&with_if_match("*")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_if_match_rejects_weak_and_unquoted_tags() {
+        assert!(if_match_version(&with_if_match("W/\"7\"")).is_err());
+        assert!(if_match_version(&with_if_match("7")).is_err());
+    }
+}
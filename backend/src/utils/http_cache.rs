@@ -0,0 +1,235 @@
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, UNIX_EPOCH};
+
+use actix_web::{
+    http::header::{self, HttpDate},
+    HttpRequest, HttpResponse, HttpResponseBuilder,
+};
+use chrono::Utc;
+use mongodb::Database;
+use redis::{aio::ConnectionManager, AsyncCommands};
+
+use crate::{errors::AppErrors, services::campaign_service};
+
+const CATALOG_VERSION_KEY: &str = "catalog:version";
+const CATALOG_MODIFIED_KEY: &str = "catalog:modified_at";
+
+/// Public catalog routes with their own `Cache-Control` policy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheRoute {
+    Products,
+    Product,
+    Deals,
+}
+
+impl CacheRoute {
+    fn env_var(self) -> &'static str {
+        match self {
+            CacheRoute::Products => "CACHE_CONTROL_PRODUCTS",
+            CacheRoute::Product => "CACHE_CONTROL_PRODUCT",
+            CacheRoute::Deals => "CACHE_CONTROL_DEALS",
+        }
+    }
+
+    /// `Cache-Control` value, overridable through the route's env var
+    pub fn cache_control(self) -> String {
+        env::var(self.env_var())
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| "public, max-age=60, must-revalidate".to_string())
+    }
+}
+
+/// Marks the catalog as changed so every validator handed out so far is stale
+pub async fn bump_catalog_version(redis: &mut ConnectionManager) {
+    let _: std::result::Result<i64, _> = redis.incr(CATALOG_VERSION_KEY, 1).await;
+    let _: std::result::Result<(), _> = redis
+        .set(CATALOG_MODIFIED_KEY, Utc::now().timestamp())
+        .await;
+}
+
+/// ETag and Last-Modified of a catalog response
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogValidator {
+    pub etag: String,
+    /// Unix timestamp (seconds)
+    pub last_modified: i64,
+}
+
+/// Builds the validator for the current request from the catalog version,
+/// the last campaign boundary and everything in the request that changes the
+/// representation (path, query, requested currency and language). `document_version`
+/// prefixes the tag so single documents still work with `If-Match`.
+pub async fn catalog_validator(
+    db: &Database,
+    mut redis: ConnectionManager,
+    req: &HttpRequest,
+    document_version: Option<u32>,
+) -> Result<CatalogValidator, AppErrors> {
+    let now = Utc::now().timestamp();
+
+    let version: i64 = redis.get(CATALOG_VERSION_KEY).await.unwrap_or(0);
+
+    // Fixed on first use so validators stay stable until the next change
+    let _: std::result::Result<bool, _> = redis.set_nx(CATALOG_MODIFIED_KEY, now).await;
+    let modified_at: i64 = redis.get(CATALOG_MODIFIED_KEY).await.unwrap_or(now);
+
+    let boundary = campaign_service::last_boundary_until(db, now)
+        .await?
+        .unwrap_or(0);
+
+    let mut hasher = DefaultHasher::new();
+    req.path().hash(&mut hasher);
+    req.query_string().hash(&mut hasher);
+    for name in ["Accept-Currency", "Accept-Language"] {
+        req.headers()
+            .get(name)
+            .and_then(|h| h.to_str().ok())
+            .hash(&mut hasher);
+    }
+
+    let prefix = document_version.map_or_else(String::new, |v| format!("{}-", v));
+
+    Ok(CatalogValidator {
+        etag: format!(
+            "\"{}{}-{}-{:x}\"",
+            prefix,
+            version,
+            boundary,
+            hasher.finish()
+        ),
+        last_modified: modified_at.max(boundary),
+    })
+}
+
+/// The quoted part of an entity tag, without the weakness indicator
+fn opaque_tag(tag: &str) -> &str {
+    let tag = tag.trim();
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+fn http_date(timestamp: i64) -> HttpDate {
+    HttpDate::from(UNIX_EPOCH + Duration::from_secs(timestamp.max(0) as u64))
+}
+
+impl CatalogValidator {
+    /// Whether the client's copy is current. `If-None-Match` wins over
+    /// `If-Modified-Since` when both are sent, and compares tags weakly as
+    /// RFC 9110 asks, so a `W/` prefix added by a proxy still matches.
+    pub fn is_fresh(&self, req: &HttpRequest) -> bool {
+        if let Some(tags) = req
+            .headers()
+            .get(header::IF_NONE_MATCH)
+            .and_then(|h| h.to_str().ok())
+        {
+            if tags.trim() == "*" {
+                return true;
+            }
+
+            let etag = opaque_tag(&self.etag);
+            return tags.split(',').any(|tag| opaque_tag(tag) == etag);
+        }
+
+        req.headers()
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|value| value.parse::<HttpDate>().ok())
+            .is_some_and(|since| since >= http_date(self.last_modified))
+    }
+
+    /// Adds the validator and the route's caching policy to a response
+    pub fn respond(&self, builder: &mut HttpResponseBuilder, route: CacheRoute) {
+        builder
+            .insert_header((header::ETAG, self.etag.clone()))
+            .insert_header((header::LAST_MODIFIED, http_date(self.last_modified)))
+            .insert_header((header::CACHE_CONTROL, route.cache_control()))
+            .insert_header((header::VARY, "Accept-Currency, Accept-Language"));
+    }
+
+    pub fn not_modified(&self, route: CacheRoute) -> HttpResponse {
+        let mut builder = HttpResponse::NotModified();
+        self.respond(&mut builder, route);
+        builder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use serial_test::serial;
+
+    fn validator() -> CatalogValidator {
+        CatalogValidator {
+            etag: "\"3-0-abc\"".to_string(),
+            last_modified: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_fresh_when_etag_matches() {
+        let req = TestRequest::get()
+            .insert_header((header::IF_NONE_MATCH, "\"2-0-abc\", \"3-0-abc\""))
+            .to_http_request();
+
+        assert!(validator().is_fresh(&req));
+    }
+
+    #[test]
+    fn test_fresh_by_weak_comparison_or_wildcard() {
+        let weak = TestRequest::get()
+            .insert_header((header::IF_NONE_MATCH, "W/\"3-0-abc\""))
+            .to_http_request();
+        let any = TestRequest::get()
+            .insert_header((header::IF_NONE_MATCH, "*"))
+            .to_http_request();
+        let other = TestRequest::get()
+            .insert_header((header::IF_NONE_MATCH, "W/\"2-0-abc\""))
+            .to_http_request();
+
+        assert!(validator().is_fresh(&weak));
+        assert!(validator().is_fresh(&any));
+        assert!(!validator().is_fresh(&other));
+    }
+
+    #[test]
+    fn test_etag_mismatch_ignores_if_modified_since() {
+        let req = TestRequest::get()
+            .insert_header((header::IF_NONE_MATCH, "\"2-0-abc\""))
+            .insert_header((header::IF_MODIFIED_SINCE, http_date(1_800_000_000)))
+            .to_http_request();
+
+        assert!(!validator().is_fresh(&req));
+    }
+
+    #[test]
+    fn test_fresh_by_modification_date() {
+        let current = TestRequest::get()
+            .insert_header((header::IF_MODIFIED_SINCE, http_date(1_700_000_000)))
+            .to_http_request();
+        let stale = TestRequest::get()
+            .insert_header((header::IF_MODIFIED_SINCE, http_date(1_600_000_000)))
+            .to_http_request();
+
+        assert!(validator().is_fresh(&current));
+        assert!(!validator().is_fresh(&stale));
+        assert!(!validator().is_fresh(&TestRequest::get().to_http_request()));
+    }
+
+    #[test]
+    #[serial]
+    fn test_cache_control_per_route() {
+        env::set_var("CACHE_CONTROL_DEALS", "public, max-age=300");
+        env::remove_var("CACHE_CONTROL_PRODUCTS");
+
+        assert_eq!(CacheRoute::Deals.cache_control(), "public, max-age=300");
+        assert_eq!(
+            CacheRoute::Products.cache_control(),
+            "public, max-age=60, must-revalidate"
+        );
+
+        env::remove_var("CACHE_CONTROL_DEALS");
+    }
+}
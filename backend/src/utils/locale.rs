@@ -0,0 +1,108 @@
+use std::env;
+
+use actix_web::{http::header, web, HttpRequest};
+use serde::Deserialize;
+
+use crate::{errors::AppErrors, models::locale::Locale};
+
+#[derive(Deserialize)]
+struct LocaleQuery {
+    locale: Option<String>,
+}
+
+/// Locale `Product.name` and `description` are written in
+pub fn get_default_locale() -> Locale {
+    env::var("DEFAULT_LOCALE")
+        .ok()
+        .and_then(|s| s.parse::<Locale>().ok())
+        .unwrap_or(Locale::Uk)
+}
+
+/// Best supported language of an `Accept-Language` header, by quality
+fn from_accept_language(value: &str) -> Option<Locale> {
+    let mut best: Option<(Locale, f32)> = None;
+
+    for range in value.split(',') {
+        let mut parts = range.split(';').map(str::trim);
+        let Some(Ok(locale)) = parts.next().map(str::parse::<Locale>) else {
+            continue;
+        };
+
+        let quality = parts
+            .find_map(|param| param.strip_prefix("q="))
+            .and_then(|q| q.parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+            best = Some((locale, quality));
+        }
+    }
+
+    best.map(|(locale, _)| locale)
+}
+
+/// Reads `?locale=`, then `Accept-Language`, then falls back to the default
+/// locale. Only an explicit unsupported `?locale=` is an error.
+pub fn requested_locale(req: &HttpRequest) -> Result<Locale, AppErrors> {
+    let from_query = web::Query::<LocaleQuery>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.into_inner().locale);
+
+    if let Some(code) = from_query {
+        return code.parse().map_err(AppErrors::BadRequest);
+    }
+
+    Ok(req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|h| h.to_str().ok())
+        .and_then(from_accept_language)
+        .unwrap_or_else(get_default_locale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use serial_test::serial;
+
+    #[test]
+    fn test_accept_language_by_quality() {
+        assert_eq!(
+            from_accept_language("de-DE, en;q=0.8, uk;q=0.9"),
+            Some(Locale::Uk)
+        );
+        assert_eq!(from_accept_language("en-US,en;q=0.5"), Some(Locale::En));
+        assert_eq!(from_accept_language("uk;q=0, fr"), None);
+    }
+
+    #[test]
+    #[serial]
+    fn test_requested_locale_prefers_query_then_header() {
+        env::remove_var("DEFAULT_LOCALE");
+
+        let req = TestRequest::get()
+            .uri("/product/products?locale=en")
+            .insert_header((header::ACCEPT_LANGUAGE, "uk-UA"))
+            .to_http_request();
+        assert_eq!(requested_locale(&req).unwrap(), Locale::En);
+
+        let req = TestRequest::get()
+            .insert_header((header::ACCEPT_LANGUAGE, "en-GB,en;q=0.9"))
+            .to_http_request();
+        assert_eq!(requested_locale(&req).unwrap(), Locale::En);
+
+        let req = TestRequest::get()
+            .insert_header((header::ACCEPT_LANGUAGE, "fr"))
+            .to_http_request();
+        assert_eq!(requested_locale(&req).unwrap(), Locale::Uk);
+
+        let req = TestRequest::get()
+            .uri("/product/products?locale=fr")
+            .to_http_request();
+        assert!(matches!(
+            requested_locale(&req),
+            Err(AppErrors::BadRequest(_))
+        ));
+    }
+}
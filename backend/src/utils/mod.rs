@@ -1,2 +1,8 @@
+pub mod cart;
+pub mod currency;
+pub mod etag;
 pub mod hash;
+pub mod http_cache;
 pub mod jwt;
+pub mod locale;
+pub mod webhook;
@@ -0,0 +1,141 @@
+use std::env;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::errors::AppErrors;
+
+pub const SIGNATURE_HEADER: &str = "X-Payment-Signature";
+
+/// Signatures older than this are rejected, so captured requests cannot be replayed
+const TOLERANCE_SECONDS: i64 = 5 * 60;
+
+/// Secrets from `PAYMENT_WEBHOOK_SECRET`, separated by commas. The first one
+/// signs and any of them verifies, so a new secret can be added before the
+/// provider switches to it. Panics when none is set, as anyone could forge
+/// payment events with a default secret.
+pub fn get_webhook_secrets() -> Vec<String> {
+    let secrets: Vec<String> = env::var("PAYMENT_WEBHOOK_SECRET")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|secret| !secret.is_empty())
+        .map(String::from)
+        .collect();
+
+    assert!(!secrets.is_empty(), "PAYMENT_WEBHOOK_SECRET must be set");
+
+    secrets
+}
+
+fn mac(secret: &str, timestamp: i64, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+fn sign_with(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    format!(
+        "t={},v1={}",
+        timestamp,
+        hex::encode(mac(secret, timestamp, body).finalize().into_bytes())
+    )
+}
+
+/// `t=<timestamp>,v1=<hex HMAC-SHA256 of "<timestamp>.<body>">`, as the
+/// provider sends it; used by tests to post webhooks
+pub fn sign(timestamp: i64, body: &[u8]) -> String {
+    sign_with(&get_webhook_secrets()[0], timestamp, body)
+}
+
+/// Checks a signature header made by `sign` against the raw body
+pub fn verify(header: &str, body: &[u8], now: i64) -> Result<(), AppErrors> {
+    verify_with(&get_webhook_secrets(), header, body, now)
+}
+
+fn verify_with(secrets: &[String], header: &str, body: &[u8], now: i64) -> Result<(), AppErrors> {
+    let invalid = || AppErrors::BadRequest("Invalid webhook signature".to_string());
+
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.extend(hex::decode(value).ok()),
+            _ => {}
+        }
+    }
+
+    let timestamp = timestamp.ok_or_else(invalid)?;
+    if (now - timestamp).abs() > TOLERANCE_SECONDS {
+        return Err(invalid());
+    }
+
+    let matches = |secret: &String| {
+        signatures
+            .iter()
+            .any(|signature| mac(secret, timestamp, body).verify_slice(signature).is_ok())
+    };
+
+    if secrets.iter().any(matches) {
+        Ok(())
+    } else {
+        Err(invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets() -> Vec<String> {
+        vec!["current".to_string()]
+    }
+
+    fn sign(timestamp: i64, body: &[u8]) -> String {
+        sign_with("current", timestamp, body)
+    }
+
+    fn verify(header: &str, body: &[u8], now: i64) -> Result<(), AppErrors> {
+        verify_with(&secrets(), header, body, now)
+    }
+
+    #[test]
+    fn test_accepts_own_signature() {
+        let body = br#"{"id":"evt_1"}"#;
+
+        assert!(verify(&sign(1_700_000_000, body), body, 1_700_000_060).is_ok());
+    }
+
+    #[test]
+    fn test_accepts_every_secret_during_rotation() {
+        let body = b"{}";
+        let rotating = vec!["next".to_string(), "current".to_string()];
+
+        assert!(verify_with(&rotating, &sign(1_700_000_000, body), body, 1_700_000_000).is_ok());
+        assert!(verify(&sign_with("next", 1_700_000_000, body), body, 1_700_000_000).is_err());
+    }
+
+    #[test]
+    fn test_rejects_changed_body() {
+        let header = sign(1_700_000_000, br#"{"id":"evt_1"}"#);
+
+        assert!(verify(&header, br#"{"id":"evt_2"}"#, 1_700_000_000).is_err());
+    }
+
+    #[test]
+    fn test_rejects_old_signatures() {
+        let body = b"{}";
+
+        assert!(verify(&sign(1_700_000_000, body), body, 1_700_000_000 + 301).is_err());
+    }
+
+    #[test]
+    fn test_rejects_malformed_headers() {
+        assert!(verify("v1=zz", b"{}", 0).is_err());
+        assert!(verify("", b"{}", 0).is_err());
+    }
+}
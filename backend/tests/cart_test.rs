@@ -0,0 +1,204 @@
+use actix_web::{http::StatusCode, test};
+use serde_json::json;
+
+mod common;
+
+#[actix_web::test]
+async fn test_guest_cart_keeps_its_token() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let product_id = common::insert_product(&db, None).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/cart/items")
+        .set_json(json!({"product_id": product_id.to_string(), "quantity": 2}))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let cart_token = res
+        .headers()
+        .get("x-cart-token")
+        .expect("Cart token header missing")
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let req = test::TestRequest::get()
+        .uri("/api/cart")
+        .insert_header(("X-Cart-Token", cart_token.as_str()))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(body["pricing"]["items"][0]["quantity"], 2);
+    assert_eq!(body["pricing"]["total"]["amount"], 10_000);
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_sold_out_products_are_unavailable() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let product_id = common::insert_product(&db, Some(1)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/cart/items")
+        .set_json(json!({"product_id": product_id.to_string(), "quantity": 3}))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(body["unavailable"][0], product_id.to_string());
+    assert_eq!(body["pricing"]["items"], json!([]));
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_guest_cart_is_merged_on_login_and_checked_out() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let product_id = common::insert_product(&db, Some(10)).await;
+
+    let res =
+        common::register_test_user(&app, "John Doe", "john@example.com", "SecurePass123!").await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let req = test::TestRequest::post()
+        .uri("/api/cart/items")
+        .set_json(json!({"product_id": product_id.to_string(), "quantity": 1}))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    let cart_token = res.headers().get("x-cart-token").unwrap().clone();
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .insert_header(("X-Cart-Token", cart_token))
+        .set_json(json!({"email": "john@example.com", "password": "SecurePass123!"}))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let access_token = res
+        .headers()
+        .get("x-access-token")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let req = test::TestRequest::post()
+        .uri("/api/cart/checkout")
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(
+        body["pricing"]["items"][0]["product_id"],
+        product_id.to_string()
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/api/cart")
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(body["pricing"]["items"], json!([]));
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_wishlist_item_moves_into_the_cart() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let product_id = common::insert_product(&db, Some(10)).await;
+
+    let res =
+        common::register_test_user(&app, "John Doe", "john@example.com", "SecurePass123!").await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let access_token = res
+        .headers()
+        .get("x-access-token")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/user/wishlist/{}", product_id))
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/user/wishlist/{}/move_to_cart", product_id))
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get()
+        .uri("/api/cart")
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(
+        body["pricing"]["items"][0]["product_id"],
+        product_id.to_string()
+    );
+    assert_eq!(body["pricing"]["items"][0]["quantity"], 1);
+
+    let req = test::TestRequest::get()
+        .uri("/api/user/wishlist")
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(body, json!([]));
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_checkout_needs_an_account() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/cart/checkout")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    common::teardown_test_db(&db).await;
+}
@@ -1,15 +1,27 @@
 use actix_http::Request;
 use actix_web::{
     dev::{Service, ServiceResponse},
+    http::StatusCode,
     test, web, App,
 };
 use mongodb::{Client, Database};
 use redis::aio::ConnectionManager;
 use serde_json::json;
+use std::sync::Arc;
 use uuid::Uuid;
 
 use bike_shopping_backend::{
-    models::app::AppState, routes, utils::jwt::generate_access_token, Role,
+    models::{
+        app::AppState,
+        money::{Currency, Money},
+        order::{Order, OrderItem, OrderStatus},
+        product::{Product, ProductStatus},
+        tax::TaxMode,
+    },
+    routes,
+    services::payment_service::MockPaymentProvider,
+    utils::jwt::generate_access_token,
+    Role,
 };
 
 pub async fn setup_test_db() -> Database {
@@ -77,7 +89,26 @@ pub async fn create_test_app(
     Response = actix_web::dev::ServiceResponse,
     Error = actix_web::Error,
 > {
-    let state = web::Data::new(AppState { mongo: db, redis });
+    create_test_app_with_payments(db, redis, Arc::new(MockPaymentProvider::default())).await
+}
+
+/// Test app whose payments go to `payments`, so tests can settle them
+pub async fn create_test_app_with_payments(
+    db: Database,
+    redis: ConnectionManager,
+    payments: Arc<MockPaymentProvider>,
+) -> impl actix_web::dev::Service<
+    actix_http::Request,
+    Response = actix_web::dev::ServiceResponse,
+    Error = actix_web::Error,
+> {
+    std::env::set_var("PAYMENT_WEBHOOK_SECRET", "test_webhook_secret");
+
+    let state = web::Data::new(AppState {
+        mongo: db,
+        redis,
+        payments,
+    });
 
     test::init_service(
         App::new()
@@ -111,6 +142,97 @@ pub async fn register_test_user(
     test::call_service(app, register_req).await
 }
 
+/// Registers a customer and returns their id and access token
+pub async fn register_customer(
+    app: &impl Service<Request, Response = ServiceResponse, Error = actix_web::Error>,
+    email: &str,
+) -> (String, String) {
+    let res = register_test_user(app, "John Doe", email, "SecurePass123!").await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let access_token = res
+        .headers()
+        .get("x-access-token")
+        .expect("Access token header missing")
+        .to_str()
+        .expect("Access token header is not valid UTF-8")
+        .to_string();
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+
+    (body["id"].as_str().unwrap().to_string(), access_token)
+}
+
+/// Inserts a published 50.00 USD helmet
+pub async fn insert_product(db: &Database, stock: Option<u32>) -> Uuid {
+    let product = Product {
+        _id: Uuid::new_v4(),
+        sku: None,
+        name: "Helmet".to_string(),
+        price: Money::new(5_000, Currency::Usd),
+        description: String::new(),
+        translations: vec![],
+        images: vec![],
+        discount: 0,
+        category: 0,
+        attributes: Default::default(),
+        stock,
+        status: ProductStatus::Published,
+        deleted_at: None,
+        version: 1,
+    };
+
+    db.collection::<Product>("products")
+        .insert_one(&product)
+        .await
+        .expect("Failed to insert product");
+
+    product._id
+}
+
+/// Inserts a one-unit 50.00 USD order of `product_id` in `status`
+pub async fn insert_order(
+    db: &Database,
+    customer_id: &str,
+    product_id: Uuid,
+    status: OrderStatus,
+) -> Uuid {
+    let price = Money::new(5_000, Currency::Usd);
+    let order = Order {
+        _id: Uuid::new_v4(),
+        items: vec![OrderItem {
+            product_id: product_id.to_string(),
+            variant_id: None,
+            name: "Helmet".to_string(),
+            quantity: 1,
+            unit_price: price,
+            discount: Money::zero(Currency::Usd),
+            total: price,
+        }],
+        bundles: vec![],
+        subtotal: price,
+        discount: Money::zero(Currency::Usd),
+        coupon: None,
+        tax_mode: TaxMode::Exclusive,
+        taxes: vec![],
+        tax: None,
+        total_price: price,
+        exchange_rate: 1.0,
+        customer_id: customer_id.to_string(),
+        status,
+        history: vec![],
+        refunds: vec![],
+        version: 1,
+    };
+
+    db.collection::<Order>("orders")
+        .insert_one(&order)
+        .await
+        .expect("Failed to insert order");
+
+    order._id
+}
+
 pub fn setup_test_env() {
     std::env::set_var("JWT_SECRET", "test_secret_key_for_testing");
     std::env::set_var("ACCESS_TOKEN_DURATION_MINUTES", "60");
@@ -0,0 +1,136 @@
+use actix_http::Request;
+use actix_web::{
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test,
+};
+use bike_shopping_backend::models::coupon::{Coupon, CouponRedemption};
+use chrono::Utc;
+use mongodb::bson::doc;
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+/// Creates a 10% coupon running for the next hour
+async fn create_coupon(
+    app: &impl Service<Request, Response = ServiceResponse, Error = actix_web::Error>,
+    limits: serde_json::Value,
+) {
+    let now = Utc::now().timestamp();
+    let mut body = json!({
+        "code": "spring10",
+        "discount": {"type": "Percentage", "percent": 10},
+        "starts_at": now - 60,
+        "ends_at": now + 60 * 60,
+    });
+    body.as_object_mut()
+        .unwrap()
+        .extend(limits.as_object().unwrap().clone());
+
+    let admin_token = common::generate_test_admin_token().await.unwrap();
+    let req = test::TestRequest::post()
+        .uri("/api/coupon/admin/create")
+        .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+        .set_json(body)
+        .to_request();
+    let res = test::call_service(app, req).await;
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+}
+
+fn order_with_coupon(product_id: Uuid, access_token: &str, uri: &str) -> Request {
+    test::TestRequest::post()
+        .uri(uri)
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .set_json(json!({
+            "items": [{"product_id": product_id.to_string(), "quantity": 1}],
+            "coupon_code": "Spring10"
+        }))
+        .to_request()
+}
+
+#[actix_web::test]
+async fn test_coupon_is_validated_and_redeemed_once_per_customer() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    create_coupon(&app, json!({"max_uses_per_user": 1})).await;
+    let (_, access_token) = common::register_customer(&app, "john@example.com").await;
+    let product_id = common::insert_product(&db, Some(10)).await;
+
+    let req = order_with_coupon(product_id, &access_token, "/api/coupon/validate");
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let pricing: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(pricing["coupon"]["code"], "SPRING10");
+    assert_eq!(pricing["coupon"]["discount"]["amount"], 500);
+    assert_eq!(pricing["total"]["amount"], 4_500);
+
+    let req = order_with_coupon(product_id, &access_token, "/api/order/create");
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let created: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(created["pricing"]["total"]["amount"], 4_500);
+
+    let req = order_with_coupon(product_id, &access_token, "/api/order/create");
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let redemptions = db
+        .collection::<CouponRedemption>("coupon_redemptions")
+        .count_documents(doc! {"order_id": created["order_id"].as_str().unwrap()})
+        .await
+        .unwrap();
+
+    assert_eq!(redemptions, 1);
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_concurrent_orders_do_not_exceed_total_limit() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    create_coupon(&app, json!({"max_uses": 1})).await;
+    let product_id = common::insert_product(&db, Some(10)).await;
+    let (_, first) = common::register_customer(&app, "john@example.com").await;
+    let (_, second) = common::register_customer(&app, "jane@example.com").await;
+
+    let (a, b) = futures_util::join!(
+        test::call_service(
+            &app,
+            order_with_coupon(product_id, &first, "/api/order/create")
+        ),
+        test::call_service(
+            &app,
+            order_with_coupon(product_id, &second, "/api/order/create")
+        ),
+    );
+
+    let mut statuses = [a.status(), b.status()];
+    statuses.sort();
+
+    assert_eq!(statuses, [StatusCode::CREATED, StatusCode::BAD_REQUEST]);
+
+    let coupon = db
+        .collection::<Coupon>("coupons")
+        .find_one(doc! {"code": "SPRING10"})
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(coupon.uses, 1);
+
+    common::teardown_test_db(&db).await;
+}
@@ -0,0 +1,345 @@
+use actix_http::Request;
+use actix_web::{
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test,
+};
+use bike_shopping_backend::models::{
+    order::{Order, OrderStatus},
+    product::Product,
+};
+use mongodb::bson::doc;
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+async fn get_order(
+    app: &impl Service<Request, Response = ServiceResponse, Error = actix_web::Error>,
+    order_id: Uuid,
+    access_token: &str,
+) -> ServiceResponse {
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/order/{}", order_id))
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+
+    test::call_service(app, req).await
+}
+
+#[actix_web::test]
+async fn test_get_own_order() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (customer_id, access_token) = common::register_customer(&app, "john@example.com").await;
+    let order_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Pending).await;
+
+    let res = get_order(&app, order_id, &access_token).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(body["customer_id"], customer_id);
+    assert_eq!(body["items"][0]["name"], "Helmet");
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_get_other_customers_order_is_not_found() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (owner_id, _) = common::register_customer(&app, "john@example.com").await;
+    let (_, other_token) = common::register_customer(&app, "jane@example.com").await;
+    let order_id = common::insert_order(&db, &owner_id, Uuid::new_v4(), OrderStatus::Pending).await;
+
+    let res = get_order(&app, order_id, &other_token).await;
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let other_order: serde_json::Value = test::read_body_json(res).await;
+
+    // Same answer as for an order that does not exist at all
+    let res = get_order(&app, Uuid::new_v4(), &other_token).await;
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let missing_order: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(other_order, missing_order);
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_admin_gets_any_order() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (customer_id, _) = common::register_customer(&app, "john@example.com").await;
+    let order_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Pending).await;
+
+    let admin_token = common::generate_test_admin_token()
+        .await
+        .expect("Failed to generate admin token");
+
+    let res = get_order(&app, order_id, &admin_token).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(body["customer_id"], customer_id);
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_get_order_without_token() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (customer_id, _) = common::register_customer(&app, "john@example.com").await;
+    let order_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Pending).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/order/{}", order_id))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    common::teardown_test_db(&db).await;
+}
+
+async fn cancel_order(
+    app: &impl Service<Request, Response = ServiceResponse, Error = actix_web::Error>,
+    order_id: Uuid,
+    access_token: &str,
+) -> ServiceResponse {
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/order/cancel/{}", order_id))
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+
+    test::call_service(app, req).await
+}
+
+#[actix_web::test]
+async fn test_cancel_paid_order_restocks_and_refunds() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (customer_id, access_token) = common::register_customer(&app, "john@example.com").await;
+    let product_id = common::insert_product(&db, Some(4)).await;
+    let order_id = common::insert_order(&db, &customer_id, product_id, OrderStatus::Paid).await;
+
+    let res = cancel_order(&app, order_id, &access_token).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(body["status"], "Cancelled");
+    assert_eq!(body["history"][0]["changed_by"], customer_id);
+    assert_eq!(body["refunds"][0]["amount"]["amount"], 5_000);
+
+    let product = db
+        .collection::<Product>("products")
+        .find_one(doc! {"_id": product_id})
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(product.stock, Some(5));
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_cancel_shipped_order_fails() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (customer_id, access_token) = common::register_customer(&app, "john@example.com").await;
+    let order_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Shipped).await;
+
+    let res = cancel_order(&app, order_id, &access_token).await;
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_cancel_other_customers_order_is_not_found() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (owner_id, _) = common::register_customer(&app, "john@example.com").await;
+    let (_, other_token) = common::register_customer(&app, "jane@example.com").await;
+    let order_id = common::insert_order(&db, &owner_id, Uuid::new_v4(), OrderStatus::Pending).await;
+
+    let res = cancel_order(&app, order_id, &other_token).await;
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_partial_then_full_refund() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (customer_id, _) = common::register_customer(&app, "john@example.com").await;
+    let order_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Delivered).await;
+
+    let admin_token = common::generate_test_admin_token()
+        .await
+        .expect("Failed to generate admin token");
+
+    let refund = |body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&format!("/api/order/admin/refund/{}", order_id))
+            .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+            .set_json(body)
+            .to_request()
+    };
+
+    let res = test::call_service(
+        &app,
+        refund(json!({"amount": 2_000, "reason": "Scratched"})),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert_eq!(body["status"], "Delivered");
+
+    let res = test::call_service(&app, refund(json!({"amount": 3_001}))).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = test::call_service(&app, refund(json!({}))).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert_eq!(body["status"], "Refunded");
+    assert_eq!(body["refunds"][1]["amount"]["amount"], 3_000);
+
+    let req = test::TestRequest::get()
+        .uri("/api/order/admin/refunds/export")
+        .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let csv = String::from_utf8(test::read_body(res).await.to_vec()).unwrap();
+
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.contains("2000,USD,Scratched"));
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_admin_deletes_only_cancelled_orders() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (customer_id, _) = common::register_customer(&app, "john@example.com").await;
+    let pending_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Pending).await;
+    let cancelled_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Cancelled).await;
+
+    let admin_token = common::generate_test_admin_token()
+        .await
+        .expect("Failed to generate admin token");
+
+    let delete = |order_id: Uuid| {
+        test::TestRequest::delete()
+            .uri(&format!("/api/order/admin/delete/{}", order_id))
+            .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+            .to_request()
+    };
+
+    let res = test::call_service(&app, delete(pending_id)).await;
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    let res = test::call_service(&app, delete(cancelled_id)).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let left = db
+        .collection::<Order>("orders")
+        .count_documents(doc! {})
+        .await
+        .unwrap();
+    assert_eq!(left, 1);
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_retried_create_order_is_replayed() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (customer_id, access_token) = common::register_customer(&app, "john@example.com").await;
+    let product_id = common::insert_product(&db, Some(4)).await;
+    let idempotency_key = Uuid::new_v4().to_string();
+
+    let create = |quantity: u32| {
+        test::TestRequest::post()
+            .uri("/api/order/create")
+            .insert_header(("Authorization", format!("Bearer {}", access_token)))
+            .insert_header(("Idempotency-Key", idempotency_key.as_str()))
+            .set_json(
+                json!({"items": [{"product_id": product_id.to_string(), "quantity": quantity}]}),
+            )
+            .to_request()
+    };
+
+    let res = test::call_service(&app, create(1)).await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let first: serde_json::Value = test::read_body_json(res).await;
+
+    let res = test::call_service(&app, create(1)).await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+    assert_eq!(res.headers().get("idempotent-replayed").unwrap(), "true");
+    let second: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(first["order_id"], second["order_id"]);
+
+    let res = test::call_service(&app, create(2)).await;
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let orders = db
+        .collection::<Order>("orders")
+        .count_documents(doc! {"customer_id": &customer_id})
+        .await
+        .unwrap();
+
+    assert_eq!(orders, 1);
+
+    common::teardown_test_db(&db).await;
+}
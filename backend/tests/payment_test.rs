@@ -0,0 +1,231 @@
+use actix_http::Request;
+use actix_web::{
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test,
+};
+use bike_shopping_backend::{
+    models::{
+        order::{Order, OrderStatus},
+        payment::{Payment, PaymentStatus},
+    },
+    services::payment_service::MockPaymentProvider,
+    utils::webhook,
+};
+use chrono::Utc;
+use mongodb::{bson::doc, Database};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+mod common;
+
+/// Starts a payment for the order and returns its intent id
+async fn create_intent(
+    app: &impl Service<Request, Response = ServiceResponse, Error = actix_web::Error>,
+    order_id: Uuid,
+    access_token: &str,
+) -> String {
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/payment/intent/{}", order_id))
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+    let res = test::call_service(app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(body["amount"]["amount"], 5_000);
+    assert_eq!(body["status"], "RequiresPayment");
+
+    body["intent_id"].as_str().unwrap().to_string()
+}
+
+async fn send_webhook(
+    app: &impl Service<Request, Response = ServiceResponse, Error = actix_web::Error>,
+    body: &serde_json::Value,
+    signature: &str,
+) -> ServiceResponse {
+    let req = test::TestRequest::post()
+        .uri("/api/payment/webhook")
+        .insert_header((webhook::SIGNATURE_HEADER, signature))
+        .insert_header(("Content-Type", "application/json"))
+        .set_payload(body.to_string())
+        .to_request();
+
+    test::call_service(app, req).await
+}
+
+async fn order_status(db: &Database, order_id: Uuid) -> OrderStatus {
+    db.collection::<Order>("orders")
+        .find_one(doc! {"_id": order_id})
+        .await
+        .unwrap()
+        .expect("Order missing")
+        .status
+}
+
+#[actix_web::test]
+async fn test_webhook_marks_order_paid_once() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let payments = Arc::new(MockPaymentProvider::default());
+    let app = common::create_test_app_with_payments(db.clone(), redis, payments.clone()).await;
+
+    let (customer_id, access_token) = common::register_customer(&app, "john@example.com").await;
+    let order_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Pending).await;
+    let intent_id = create_intent(&app, order_id, &access_token).await;
+
+    payments.succeed(&intent_id);
+    let event = json!({"id": "evt_1", "intent_id": intent_id, "status": "Succeeded"});
+    let signature = webhook::sign(Utc::now().timestamp(), event.to_string().as_bytes());
+
+    // The provider redelivers events it has no answer for
+    for _ in 0..2 {
+        let res = send_webhook(&app, &event, &signature).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let order = db
+        .collection::<Order>("orders")
+        .find_one(doc! {"_id": order_id})
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(order.history.len(), 1);
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_payment_after_cancel_is_refunded_once() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let payments = Arc::new(MockPaymentProvider::default());
+    let app = common::create_test_app_with_payments(db.clone(), redis, payments.clone()).await;
+
+    let (customer_id, access_token) = common::register_customer(&app, "john@example.com").await;
+    let order_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Pending).await;
+    let intent_id = create_intent(&app, order_id, &access_token).await;
+
+    // The customer cancels while the authorization webhook is on its way
+    payments.authorize(&intent_id);
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/order/cancel/{}", order_id))
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let event = json!({"id": "evt_1", "intent_id": intent_id, "status": "RequiresCapture"});
+    let signature = webhook::sign(Utc::now().timestamp(), event.to_string().as_bytes());
+
+    for _ in 0..2 {
+        let res = send_webhook(&app, &event, &signature).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let order = db
+        .collection::<Order>("orders")
+        .find_one(doc! {"_id": order_id})
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(order.status, OrderStatus::Cancelled);
+    assert_eq!(order.refunds.len(), 1);
+    assert_eq!(order.refunds[0].amount.amount, 5_000);
+    assert_eq!(payments.refunded(&intent_id), Some(5_000));
+
+    let payment = db
+        .collection::<Payment>("payments")
+        .find_one(doc! {"intent_id": &intent_id})
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(payment.status, PaymentStatus::Succeeded);
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_webhook_with_wrong_signature_is_rejected() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let (customer_id, access_token) = common::register_customer(&app, "john@example.com").await;
+    let order_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Pending).await;
+    let intent_id = create_intent(&app, order_id, &access_token).await;
+
+    let event = json!({"id": "evt_1", "intent_id": intent_id, "status": "Succeeded"});
+    let signature = webhook::sign(Utc::now().timestamp(), b"{}");
+
+    let res = send_webhook(&app, &event, &signature).await;
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(order_status(&db, order_id).await, OrderStatus::Pending);
+
+    common::teardown_test_db(&db).await;
+}
+
+#[actix_web::test]
+async fn test_reconcile_captures_payments_without_webhook() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let payments = Arc::new(MockPaymentProvider::default());
+    let app = common::create_test_app_with_payments(db.clone(), redis, payments.clone()).await;
+
+    let (customer_id, access_token) = common::register_customer(&app, "john@example.com").await;
+    let order_id =
+        common::insert_order(&db, &customer_id, Uuid::new_v4(), OrderStatus::Pending).await;
+    let intent_id = create_intent(&app, order_id, &access_token).await;
+
+    payments.authorize(&intent_id);
+
+    // The webhook never came and the payment has been sitting there for an hour
+    db.collection::<Payment>("payments")
+        .update_one(
+            doc! {"intent_id": &intent_id},
+            doc! {"$set": {"updated_at": Utc::now().timestamp() - 60 * 60}},
+        )
+        .await
+        .unwrap();
+
+    let admin_token = common::generate_test_admin_token().await.unwrap();
+    let req = test::TestRequest::post()
+        .uri("/api/payment/admin/reconcile")
+        .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(
+        body,
+        json!({"checked": 1, "succeeded": 1, "failed": 0, "pending": 0})
+    );
+    assert_eq!(order_status(&db, order_id).await, OrderStatus::Paid);
+
+    // Refunds now go back through the provider
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/order/admin/refund/{}", order_id))
+        .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+        .set_json(json!({"amount": 2_000}))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(payments.refunded(&intent_id), Some(2_000));
+
+    common::teardown_test_db(&db).await;
+}
@@ -0,0 +1,88 @@
+use actix_web::{http::StatusCode, test};
+use bike_shopping_backend::models::{
+    money::{Currency, Money},
+    order::Order,
+};
+use mongodb::bson::doc;
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+#[actix_web::test]
+async fn test_order_is_taxed_for_its_region_and_exported() {
+    let db = common::setup_test_db().await;
+    let redis = common::setup_test_redis().await;
+    let app = common::create_test_app(db.clone(), redis).await;
+
+    let admin_token = common::generate_test_admin_token().await.unwrap();
+    for rule in [
+        json!({"country": "us", "name": "Sales tax", "rate": 500}),
+        json!({"country": "US", "region": "CA", "name": "Sales tax", "rate": 725}),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/api/tax/admin/rules/set")
+            .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+            .set_json(rule)
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::CREATED);
+    }
+
+    let (_, access_token) = common::register_customer(&app, "john@example.com").await;
+
+    let product_id = common::insert_product(&db, Some(10)).await;
+    let req = test::TestRequest::post()
+        .uri("/api/order/create")
+        .insert_header(("Authorization", format!("Bearer {}", access_token)))
+        .set_json(json!({
+            "items": [{"product_id": product_id.to_string(), "quantity": 1}],
+            "country": "US",
+            "region": "ca"
+        }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let created: serde_json::Value = test::read_body_json(res).await;
+
+    assert_eq!(created["pricing"]["tax_mode"], "Exclusive");
+    assert_eq!(created["pricing"]["taxes"][0]["rate"], 725);
+    assert_eq!(created["pricing"]["tax"]["amount"], 363);
+    assert_eq!(created["pricing"]["total"]["amount"], 5_363);
+
+    let order_id = Uuid::parse_str(created["order_id"].as_str().unwrap()).unwrap();
+    let order = db
+        .collection::<Order>("orders")
+        .find_one(doc! {"_id": order_id})
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(order.taxes.len(), 1);
+    assert_eq!(order.tax, Some(Money::new(363, Currency::Usd)));
+
+    let req = test::TestRequest::get()
+        .uri("/api/order/admin/export")
+        .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = String::from_utf8(test::read_body(res).await.to_vec()).unwrap();
+    let mut lines = body.lines();
+
+    assert_eq!(
+        lines.next(),
+        Some("order_id,customer_id,placed_at,status,currency,subtotal,discount,coupon,coupon_discount,tax_mode,tax,total,refunded")
+    );
+    assert!(lines
+        .next()
+        .unwrap()
+        .ends_with(",Pending,USD,5000,0,,,Exclusive,363,5363,0"));
+
+    common::teardown_test_db(&db).await;
+}
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Money } from "./Money.d";
+
+/**
+ * Coupon applied to an order
+ */
+export type AppliedCoupon = { coupon_id: string, code: string, 
+/**
+ * Taken off the order total, in the order currency
+ */
+discount: Money, };
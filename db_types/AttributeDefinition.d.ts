@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AttributeKind } from "./AttributeKind.d";
+
+export type AttributeDefinition = { 
+/**
+ * Name used in product documents and filters
+ */
+key: string, label: string, kind: AttributeKind, 
+/**
+ * Allowed values of an `Enum` attribute
+ */
+options: Array<string>, unit?: string, required: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Value of one technical specification, e.g. `29`, `true` or `"aluminium"`
+ */
+export type AttributeValue = boolean | number | string;
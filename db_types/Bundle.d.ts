@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BundleItem } from "./BundleItem.d";
+import type { BundlePricing } from "./BundlePricing.d";
+import type { ProductStatus } from "./ProductStatus.d";
+
+/**
+ * Kit of products sold together, e.g. bike + helmet + lock
+ */
+export type Bundle = { _id: string, name: string, description: string, images: Array<string>, items: Array<BundleItem>, pricing: BundlePricing, status: ProductStatus, 
+/**
+ * Incremented on every change
+ */
+version: number, };
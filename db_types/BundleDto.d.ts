@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BundleItem } from "./BundleItem.d";
+import type { BundlePricing } from "./BundlePricing.d";
+import type { ProductStatus } from "./ProductStatus.d";
+
+export type BundleDto = { name: string, description: string, images: Array<string>, items: Array<BundleItem>, pricing: BundlePricing, 
+/**
+ * Defaults to Draft
+ */
+status?: ProductStatus, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Bundle } from "./Bundle.d";
+import type { BundleComponent } from "./BundleComponent.d";
+import type { Money } from "./Money.d";
+
+/**
+ * Bundle with its components and the prices a customer would see
+ */
+export type BundleView = { bundle: Bundle, components: Array<BundleComponent>, 
+/**
+ * What the components cost when bought separately
+ */
+components_price: Money, price: Money, savings: Money, 
+/**
+ * False when a component is unpublished or sold out
+ */
+available: boolean, 
+/**
+ * Kits that can be put together from component stock; `null` when no
+ * component tracks stock
+ */
+stock: number | null, };
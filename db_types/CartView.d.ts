@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PriceBreakdown } from "./PriceBreakdown.d";
+import type { Product } from "./Product.d";
+
+/**
+ * The cart at current catalog prices
+ */
+export type CartView = { 
+/**
+ * The lines that can be ordered, priced as checkout would price them
+ */
+pricing: PriceBreakdown, 
+/**
+ * Products of the priced lines
+ */
+products: Array<Product>, 
+/**
+ * Products and bundles in the cart that cannot be ordered right now,
+ * or not in the quantity asked for
+ */
+unavailable: Array<string>, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AttributeDefinition } from "./AttributeDefinition.d";
+
+/**
+ * Technical specifications products of one category can have
+ */
+export type CategorySchema = { _id: string, category: number, attributes: Array<AttributeDefinition>, 
+/**
+ * Unix timestamp (seconds)
+ */
+updated_at: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OrderStatus } from "./OrderStatus.d";
+
+export type ChangeOrderStatusDto = { status: OrderStatus, 
+/**
+ * Kept in the status history, e.g. a tracking number
+ */
+note?: string, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AttributeValue } from "./AttributeValue.d";
+
+export type ComparedAttribute = { key: string, 
+/**
+ * Falls back to the key when the category has no schema
+ */
+label: string, unit?: string, 
+/**
+ * One value per compared product, `null` where the product lacks it
+ */
+values: Array<AttributeValue | null>, 
+/**
+ * Whether the products disagree on this attribute
+ */
+differs: boolean, };
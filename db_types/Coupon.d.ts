@@ -0,0 +1,45 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CouponDiscount } from "./CouponDiscount.d";
+import type { Money } from "./Money.d";
+
+/**
+ * Promo code customers enter at checkout
+ */
+export type Coupon = { _id: string, 
+/**
+ * Stored in upper case, matched case-insensitively
+ */
+code: string, discount: CouponDiscount, 
+/**
+ * Smallest order total, before the coupon, the coupon works for
+ */
+min_order_value?: Money, 
+/**
+ * Product categories the coupon applies to; empty means every product
+ * and bundle
+ */
+categories: Array<number>, 
+/**
+ * Redemptions allowed in total
+ */
+max_uses?: number, 
+/**
+ * Redemptions allowed per customer
+ */
+max_uses_per_user?: number, 
+/**
+ * Redemptions so far
+ */
+uses: number, 
+/**
+ * Unix timestamp (seconds), inclusive
+ */
+starts_at: number, 
+/**
+ * Unix timestamp (seconds), exclusive
+ */
+ends_at: number, 
+/**
+ * Unix timestamp (seconds)
+ */
+created_at: number, };
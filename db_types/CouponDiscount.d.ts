@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Money } from "./Money.d";
+
+/**
+ * What a coupon takes off the products it applies to
+ */
+export type CouponDiscount = { "type": "Percentage", percent: number, } | { "type": "Fixed", amount: Money, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Money } from "./Money.d";
+
+/**
+ * One use of a coupon
+ */
+export type CouponRedemption = { _id: string, coupon_id: string, code: string, order_id: string, customer_id: string, 
+/**
+ * In the order currency
+ */
+discount: Money, 
+/**
+ * Unix timestamp (seconds)
+ */
+redeemed_at: number, };
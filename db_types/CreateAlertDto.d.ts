@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlertCondition } from "./AlertCondition.d";
+
+export type CreateAlertDto = { product_id: string, 
+/**
+ * `PriceBelow` thresholds may be in any supported currency
+ */
+condition: AlertCondition, };
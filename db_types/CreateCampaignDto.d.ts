@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreateCampaignDto = { product_id: string, discount: number, 
+/**
+ * Unix timestamp (seconds); must not be in the past
+ */
+starts_at: number, 
+/**
+ * Unix timestamp (seconds)
+ */
+ends_at: number, };
@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CouponDiscount } from "./CouponDiscount.d";
+import type { Money } from "./Money.d";
+
+export type CreateCouponDto = { 
+/**
+ * Letters, digits, `-` and `_`; stored in upper case
+ */
+code: string, discount: CouponDiscount, min_order_value?: Money, 
+/**
+ * Empty applies the coupon to every product and bundle
+ */
+categories: Array<number>, max_uses?: number, max_uses_per_user?: number, 
+/**
+ * Unix timestamp (seconds)
+ */
+starts_at: number, 
+/**
+ * Unix timestamp (seconds)
+ */
+ends_at: number, };
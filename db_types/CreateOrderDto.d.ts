@@ -1,7 +1,30 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OrderBundleDto } from "./OrderBundleDto.d";
+import type { OrderItemDto } from "./OrderItemDto.d";
 
+/**
+ * What the customer orders. Prices are always taken from the catalog, so
+ * a `total_price` sent by older clients is ignored.
+ */
 export type CreateOrderDto = { 
 /**
- * products id
+ * Lines naming the same product and variant are merged
+ */
+items: Array<OrderItemDto>, 
+/**
+ * Bundles to order; each adds its components to the order
+ */
+bundles: Array<OrderBundleDto>, 
+/**
+ * Promo code to apply
+ */
+coupon_code?: string, 
+/**
+ * ISO 3166-1 alpha-2 code of the country the order ships to; taxes
+ * follow the shop's country when omitted
+ */
+country?: string, 
+/**
+ * State or province code, for regional tax rates
  */
-products_id: Array<string>, total_price: number, };
+region?: string, };
@@ -1,8 +1,27 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AttributeValue } from "./AttributeValue.d";
+import type { Money } from "./Money.d";
+import type { ProductStatus } from "./ProductStatus.d";
 
-export type CreateProductDto = { name: string, price: number, description: string, images: Array<string>, discount: number, 
+export type CreateProductDto = { 
+/**
+ * Stock keeping unit, unique across the catalog
+ */
+sku?: string, name: string, price: Money, description: string, images: Array<string>, discount: number, 
 /**
  * 0: Helmet
  * 1: Bike
  */
-category: number, };
+category: number, 
+/**
+ * Technical specifications, checked against the category schema
+ */
+attributes: { [key in string]?: AttributeValue }, 
+/**
+ * Units on hand; leave out to not track stock
+ */
+stock?: number, 
+/**
+ * Defaults to Draft
+ */
+status?: ProductStatus, };
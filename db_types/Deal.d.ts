@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Money } from "./Money.d";
+import type { Product } from "./Product.d";
+
+export type Deal = { product: Product, 
+/**
+ * Amount taken off the regular price
+ */
+savings: Money, 
+/**
+ * Price after the discount
+ */
+final_price: Money, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CategoryDeals } from "./CategoryDeals.d";
+import type { Deal } from "./Deal.d";
+
+export type DealsResponse = { overall: Array<Deal>, 
+/**
+ * Categories in ascending order
+ */
+by_category: Array<CategoryDeals>, };
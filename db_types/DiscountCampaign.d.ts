@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DiscountCampaign = { _id: string, product_id: string, discount: number, 
+/**
+ * Unix timestamp (seconds), inclusive
+ */
+starts_at: number, 
+/**
+ * Unix timestamp (seconds), exclusive
+ */
+ends_at: number, };
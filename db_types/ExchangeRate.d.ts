@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Currency } from "./Currency.d";
+
+export type ExchangeRate = { _id: string, currency: Currency, 
+/**
+ * Units of `currency` for one unit of the base currency
+ */
+rate: number, 
+/**
+ * Unix timestamp (seconds)
+ */
+created_at: number, 
+/**
+ * ID of the admin who set the rate
+ */
+created_by: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FacetValue } from "./FacetValue.d";
+
+export type Facet = { key: string, 
+/**
+ * Falls back to the key when the category has no schema
+ */
+label: string, unit?: string, values: Array<FacetValue>, };
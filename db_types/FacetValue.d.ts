@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AttributeValue } from "./AttributeValue.d";
+
+export type FacetValue = { value: AttributeValue, 
+/**
+ * Matching products with this value
+ */
+count: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Facet } from "./Facet.d";
+
+export type FacetsResponse = { 
+/**
+ * Products matching the filter
+ */
+total: bigint, facets: Array<Facet>, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportRowError } from "./ImportRowError.d";
+
+export type ImportReport = { dry_run: boolean, total: number, created: number, updated: number, failed: number, errors: Array<ImportRowError>, };
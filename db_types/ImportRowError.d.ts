@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ImportRowError = { 
+/**
+ * 1-based line number in the uploaded file
+ */
+row: number, sku: string | null, message: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Currency } from "./Currency.d";
+
+export type Money = { 
+/**
+ * Amount in minor units, e.g. 1250 is 12.50
+ */
+amount: number, currency: Currency, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Message in a user's in-app inbox
+ */
+export type Notification = { _id: string, user_id: string, title: string, body: string, 
+/**
+ * Product the notification is about, if any
+ */
+product_id: string | null, 
+/**
+ * Unix timestamp (seconds)
+ */
+created_at: number, 
+/**
+ * Unix timestamp (seconds)
+ */
+read_at: number | null, };
@@ -1,7 +1,54 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AppliedCoupon } from "./AppliedCoupon.d";
+import type { Money } from "./Money.d";
+import type { OrderBundle } from "./OrderBundle.d";
+import type { OrderItem } from "./OrderItem.d";
+import type { OrderRefund } from "./OrderRefund.d";
+import type { OrderStatus } from "./OrderStatus.d";
+import type { OrderStatusChange } from "./OrderStatusChange.d";
+import type { TaxLine } from "./TaxLine.d";
+import type { TaxMode } from "./TaxMode.d";
 
-export type Order = { _id: string, 
+export type Order = { _id: string, items: Array<OrderItem>, bundles: Array<OrderBundle>, 
 /**
- * products id
+ * Sum of the lines at regular prices
  */
-products_id: Array<string>, total_price: number, customer_id: string, };
+subtotal: Money, 
+/**
+ * Sum of the line discounts
+ */
+discount: Money, 
+/**
+ * Coupon taken off after the line discounts
+ */
+coupon?: AppliedCoupon, 
+/**
+ * Whether the lines already include the tax
+ */
+tax_mode: TaxMode, 
+/**
+ * One line per tax rule that applied
+ */
+taxes: Array<TaxLine>, 
+/**
+ * Sum of the tax lines; missing on orders placed before taxes were
+ * calculated
+ */
+tax?: Money, total_price: Money, 
+/**
+ * Units of the order currency per one unit of the base currency at purchase time
+ */
+exchange_rate: number, customer_id: string, status: OrderStatus, 
+/**
+ * Every state the order has been in, oldest first
+ */
+history: Array<OrderStatusChange>, 
+/**
+ * Refunds issued so far, oldest first
+ */
+refunds: Array<OrderRefund>, 
+/**
+ * Incremented on every change, sent as the ETag. Missing on documents
+ * stored before migration 0005, which earlier migrations still read.
+ */
+version: number, };
@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BundleItem } from "./BundleItem.d";
+import type { Money } from "./Money.d";
+
+/**
+ * Bundle line of an order as it was at purchase time
+ */
+export type OrderBundle = { bundle_id: string, 
+/**
+ * Bundle name at purchase time
+ */
+name: string, quantity: number, 
+/**
+ * What the components of one bundle cost separately
+ */
+unit_price: Money, 
+/**
+ * Bundle savings on the whole line
+ */
+discount: Money, 
+/**
+ * `unit_price * quantity - discount`
+ */
+total: Money, 
+/**
+ * Components of one bundle
+ */
+components: Array<BundleItem>, };
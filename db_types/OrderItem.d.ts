@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Money } from "./Money.d";
+
+/**
+ * Product line of an order as it was at purchase time
+ */
+export type OrderItem = { product_id: string, 
+/**
+ * Only set for products sold in variants
+ */
+variant_id?: string, 
+/**
+ * Product name at purchase time
+ */
+name: string, quantity: number, 
+/**
+ * Regular price of one unit
+ */
+unit_price: Money, 
+/**
+ * Taken off the whole line
+ */
+discount: Money, 
+/**
+ * `unit_price * quantity - discount`
+ */
+total: Money, };
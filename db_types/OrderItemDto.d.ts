@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OrderItemDto = { product_id: string, 
+/**
+ * For products sold in variants
+ */
+variant_id?: string, quantity: number, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Money } from "./Money.d";
+
+/**
+ * Money given back to the customer
+ */
+export type OrderRefund = { refund_id: string, 
+/**
+ * In the order currency
+ */
+amount: Money, reason?: string, 
+/**
+ * Unix timestamp (seconds)
+ */
+refunded_at: number, 
+/**
+ * User who issued the refund or cancelled the paid order
+ */
+refunded_by: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OrderStatus } from "./OrderStatus.d";
+
+/**
+ * One entry of an order's status history
+ */
+export type OrderStatusChange = { status: OrderStatus, 
+/**
+ * Unix timestamp (seconds)
+ */
+changed_at: number, 
+/**
+ * User who placed the order or moved it to this state
+ */
+changed_by: string, note?: string, };
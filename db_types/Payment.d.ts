@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Money } from "./Money.d";
+import type { PaymentStatus } from "./PaymentStatus.d";
+
+/**
+ * An attempt to pay for an order through the payment provider
+ */
+export type Payment = { _id: string, order_id: string, 
+/**
+ * Name of the provider that handles the payment
+ */
+provider: string, 
+/**
+ * Id of the payment at the provider
+ */
+intent_id: string, 
+/**
+ * Handed to the client to complete the payment with the provider
+ */
+client_secret: string, amount: Money, status: PaymentStatus, 
+/**
+ * Unix timestamp (seconds)
+ */
+created_at: number, 
+/**
+ * Unix timestamp (seconds)
+ */
+updated_at: number, };
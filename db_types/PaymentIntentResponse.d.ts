@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Money } from "./Money.d";
+import type { PaymentStatus } from "./PaymentStatus.d";
+
+export type PaymentIntentResponse = { payment_id: string, intent_id: string, 
+/**
+ * Completes the payment with the provider on the client
+ */
+client_secret: string, amount: Money, status: PaymentStatus, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PaymentStatus } from "./PaymentStatus.d";
+
+/**
+ * What the provider posts to the webhook when a payment changes
+ */
+export type PaymentWebhookEvent = { 
+/**
+ * Unique per event; redelivered events repeat it
+ */
+id: string, intent_id: string, status: PaymentStatus, };
@@ -0,0 +1,40 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AppliedCoupon } from "./AppliedCoupon.d";
+import type { Money } from "./Money.d";
+import type { OrderBundle } from "./OrderBundle.d";
+import type { OrderItem } from "./OrderItem.d";
+import type { TaxLine } from "./TaxLine.d";
+import type { TaxMode } from "./TaxMode.d";
+
+/**
+ * Order lines priced in the order currency
+ */
+export type PriceBreakdown = { items: Array<OrderItem>, bundles: Array<OrderBundle>, 
+/**
+ * Sum of the lines at regular prices
+ */
+subtotal: Money, 
+/**
+ * Product discounts, campaigns and bundle savings together
+ */
+discount: Money, 
+/**
+ * Coupon taken off after the line discounts
+ */
+coupon?: AppliedCoupon, 
+/**
+ * Whether the lines above already include the tax
+ */
+tax_mode: TaxMode, 
+/**
+ * One line per tax rule that applied
+ */
+taxes: Array<TaxLine>, 
+/**
+ * Sum of the tax lines
+ */
+tax: Money, 
+/**
+ * What the customer pays
+ */
+total: Money, };
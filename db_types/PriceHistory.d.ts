@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Money } from "./Money.d";
+import type { PriceChangeSource } from "./PriceChangeSource.d";
+
+export type PriceHistory = { _id: string, product_id: string, price: Money, discount: number, source: PriceChangeSource, 
+/**
+ * Set when the change comes from a discount campaign
+ */
+campaign_id: string | null, 
+/**
+ * Unix timestamp (seconds) from which this price applies
+ */
+effective_at: number, };
@@ -1,8 +1,38 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AttributeValue } from "./AttributeValue.d";
+import type { Money } from "./Money.d";
+import type { ProductStatus } from "./ProductStatus.d";
+import type { ProductTranslation } from "./ProductTranslation.d";
 
-export type Product = { _id: string, name: string, price: number, description: string, images: Array<string>, discount: number, 
+export type Product = { _id: string, 
+/**
+ * Stock keeping unit, unique across the catalog
+ */
+sku?: string, name: string, price: Money, description: string, 
+/**
+ * Name and description in other locales than the default one. Public
+ * endpoints apply the requested locale and send this empty.
+ */
+translations: Array<ProductTranslation>, images: Array<string>, discount: number, 
 /**
  * 0: Helmet
  * 1: Bike
  */
-category: number, };
+category: number, 
+/**
+ * Technical specifications, checked against the category schema
+ */
+attributes: { [key in string]?: AttributeValue }, 
+/**
+ * Units on hand; `None` when stock is not tracked
+ */
+stock: number | null, status: ProductStatus, 
+/**
+ * Unix timestamp (seconds) of a soft delete
+ */
+deleted_at: number | null, 
+/**
+ * Incremented on every change, sent as the ETag. Missing on documents
+ * stored before migration 0005, which earlier migrations still read.
+ */
+version: number, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlertCondition } from "./AlertCondition.d";
+
+/**
+ * A customer's request to be told when a product meets the condition.
+ * Alerts fire once; `triggered_at` is set when they do.
+ */
+export type ProductAlert = { _id: string, user_id: string, product_id: string, condition: AlertCondition, 
+/**
+ * Unix timestamp (seconds)
+ */
+created_at: number, 
+/**
+ * Unix timestamp (seconds)
+ */
+triggered_at: number | null, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductStatus } from "./ProductStatus.d";
+
+export type ProductAnalytics = { product_id: string, name: string, status: ProductStatus, deleted: boolean, 
+/**
+ * Customers who have the product on their wishlist
+ */
+wishlist_count: number, 
+/**
+ * Orders containing the product
+ */
+order_count: number, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ComparedAttribute } from "./ComparedAttribute.d";
+import type { Deal } from "./Deal.d";
+
+export type ProductComparison = { category: number, 
+/**
+ * Compared products in the requested order, with their effective price
+ */
+products: Array<Deal>, 
+/**
+ * Schema attributes first, in schema order, then any others by key
+ */
+attributes: Array<ComparedAttribute>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Locale } from "./Locale.d";
+
+/**
+ * Name and description of a product in one more locale
+ */
+export type ProductTranslation = { locale: Locale, name: string, description: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome of checking unsettled payments with the provider
+ */
+export type ReconcileReport = { checked: number, succeeded: number, failed: number, 
+/**
+ * Still waiting for the customer
+ */
+pending: number, };
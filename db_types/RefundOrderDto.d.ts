@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RefundOrderDto = { 
+/**
+ * Minor units of the order currency; everything not refunded yet when omitted
+ */
+amount?: number, reason?: string, };
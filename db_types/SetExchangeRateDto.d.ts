@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Currency } from "./Currency.d";
+
+export type SetExchangeRateDto = { currency: Currency, 
+/**
+ * Units of `currency` for one unit of the base currency
+ */
+rate: number, };
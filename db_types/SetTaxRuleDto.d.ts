@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Creates the rule for this country, region and category, or replaces its
+ * name and rate
+ */
+export type SetTaxRuleDto = { 
+/**
+ * ISO 3166-1 alpha-2 code
+ */
+country: string, 
+/**
+ * State or province code; omit for the whole country
+ */
+region?: string, 
+/**
+ * Omit for every category and bundles
+ */
+category?: number, name: string, 
+/**
+ * Basis points, 725 is 7.25%
+ */
+rate: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Money } from "./Money.d";
+
+/**
+ * Tax charged under one rule, summed over the lines it applies to
+ */
+export type TaxLine = { rule_id: string, name: string, country: string, region?: string, category?: number, 
+/**
+ * Basis points at purchase time
+ */
+rate: number, 
+/**
+ * Amount the rate was applied to, without tax
+ */
+taxable: Money, amount: Money, };
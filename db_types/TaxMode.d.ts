@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Whether catalog prices already include tax
+ */
+export type TaxMode = "Exclusive" | "Inclusive";
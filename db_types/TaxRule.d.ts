@@ -0,0 +1,35 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Rate charged in a country, optionally narrowed to one region and one
+ * product category. The most specific rule matching a line wins.
+ */
+export type TaxRule = { _id: string, 
+/**
+ * ISO 3166-1 alpha-2 code, upper case
+ */
+country: string, 
+/**
+ * State or province code, upper case; `null` covers the whole country
+ */
+region: string | null, 
+/**
+ * `null` covers every category and bundles
+ */
+category: number | null, 
+/**
+ * Shown on the tax line
+ */
+name: string, 
+/**
+ * Basis points, 725 is 7.25%
+ */
+rate: number, 
+/**
+ * Unix timestamp (seconds)
+ */
+updated_at: number, 
+/**
+ * ID of the admin who last set the rule
+ */
+updated_by: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Name and description of a product in a locale other than the default one
+ */
+export type TranslationDto = { name: string, description: string, };
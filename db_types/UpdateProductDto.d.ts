@@ -1,8 +1,18 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AttributeValue } from "./AttributeValue.d";
+import type { Money } from "./Money.d";
 
-export type UpdateProductDto = { _id: string, name: string | null, price: number | null, description: string | null, images: Array<string> | null, discount: number | null, 
+export type UpdateProductDto = { _id: string, sku: string | null, name: string | null, price: Money | null, description: string | null, images: Array<string> | null, discount: number | null, 
 /**
  * 0: Helmet
  * 1: Bike
  */
-category: number | null, };
+category: number | null, 
+/**
+ * Replaces all attributes of the product
+ */
+attributes: { [key in string]?: AttributeValue } | null, 
+/**
+ * Units on hand
+ */
+stock: number | null, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Product } from "./Product.d";
+
+export type WishlistEntry = { product_id: string, 
+/**
+ * Unix timestamp (seconds)
+ */
+added_at: number, 
+/**
+ * Current product data; missing if the product was removed for good
+ */
+product: Product | null, 
+/**
+ * False once the product is archived, unpublished or deleted
+ */
+available: boolean, };